@@ -6,10 +6,14 @@
 //! dispatch is negligible for a CLI, and swapping mocks in tests is a
 //! plain struct literal.
 
-use lnix_domain::interface::gateway::{NixEvaluator, NixRunner, VersionResolver};
+use lnix_domain::interface::gateway::{
+    NixEvaluator, NixRunner, PackageIndex, ServiceSupervisor, VersionResolver,
+};
+use lnix_domain::interface::lsp::LanguageClient;
 use lnix_domain::interface::output::OutputPort;
 use lnix_domain::interface::persistence::{
-    ConfigRepository, EnvFilePresenceChecker, FlakeReader, FlakeWriter, ProjectScaffolder,
+    ConfigRepository, ConfigWatcher, EnvFilePresenceChecker, FlakeReader, FlakeWriter,
+    ProjectScaffolder, ReportWriter, TaskStateStore,
 };
 
 /// Borrowed bundle of every port a use-case may touch.
@@ -33,12 +37,26 @@ pub struct Deps<'a> {
     pub nix_eval: &'a dyn NixEvaluator,
     /// Resolves/searches package versions via nix-versions (capturing).
     pub resolver: &'a dyn VersionResolver,
+    /// Lists the packages of the stable nixpkgs, for `lnix lsp`.
+    pub packages: &'a dyn PackageIndex,
+    /// Fingerprints task sources and remembers the last successful run.
+    pub task_state: &'a dyn TaskStateStore,
+    /// Writes `lnix test --report` files.
+    pub reports: &'a dyn ReportWriter,
+    /// Starts, watches, and stops the background services of `lnix up`.
+    pub services: &'a dyn ServiceSupervisor,
+    /// Waits for the config files to change, for `--watch`.
+    pub watcher: &'a dyn ConfigWatcher,
+    /// The editor `lnix lsp` serves.
+    pub lsp: &'a dyn LanguageClient,
     /// Sink for user-facing progress messages and warnings.
     pub out: &'a dyn OutputPort,
 }
 
 #[cfg(test)]
 mod tests {
+    use lnix_domain::interface::gateway::ServiceStatus;
+
     use crate::mocks::*;
 
     #[test]
@@ -52,7 +70,10 @@ mod tests {
         let write_result = deps.flake_writer.write_flake("{}");
         let pinned = deps.flake_reader.read_pinned_inputs().unwrap();
         let env_exists = deps.env.exists(".env");
-        let exit_code = deps.nix.develop_command(&["true".to_string()]).unwrap();
+        let exit_code = deps
+            .nix
+            .develop_command(&"default".parse().unwrap(), &["true".to_string()])
+            .unwrap();
         let outcome = deps
             .nix_eval
             .eval_package(&"bash".parse().unwrap(), None)
@@ -61,14 +82,37 @@ mod tests {
             .resolver
             .resolve(&"go".parse().unwrap(), &"1.21.13".parse().unwrap())
             .unwrap();
+        let recorded = deps
+            .task_state
+            .recorded(&"default".parse().unwrap(), &"build".parse().unwrap())
+            .unwrap();
+        let report_result = deps.reports.write_report("report.xml", "<testsuites/>");
+        let service_status = deps
+            .services
+            .status(&"default".parse().unwrap(), &"db".parse().unwrap())
+            .unwrap();
+        let packages = deps.packages.packages().unwrap();
+        let received = deps.lsp.receive().unwrap();
         deps.out.info("progress");
 
-        assert_eq!(config.dev_shell.package.stable[0].name.as_str(), "bash");
+        assert_eq!(
+            config.dev_shells[&"default".parse().unwrap()]
+                .package
+                .stable[0]
+                .name
+                .as_str(),
+            "bash"
+        );
         assert!(write_result.is_ok());
         assert!(pinned.is_empty());
         assert!(env_exists);
         assert_eq!(exit_code, 0);
         assert!(outcome.success);
         assert_eq!(resolved.attr, "go_1_21");
+        assert_eq!(recorded, None);
+        assert!(report_result.is_ok());
+        assert_eq!(service_status, ServiceStatus::Stopped);
+        assert!(packages.is_empty());
+        assert_eq!(received, None);
     }
 }
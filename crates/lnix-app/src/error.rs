@@ -7,7 +7,10 @@
 //! category stays visible in the type.
 
 use lnix_domain::ParseError;
-use lnix_domain::{ConfigError, FlakeError, NixError};
+use lnix_domain::{
+    ConfigError, FlakeError, LspError, NixError, ReportError, ServiceError, TaskArgError,
+    TaskStateError,
+};
 use thiserror::Error;
 
 /// Union of every failure a use-case can surface.
@@ -30,18 +33,53 @@ pub enum ApplicationError {
     #[error(transparent)]
     InvalidInput(#[from] ParseError),
 
+    #[error(transparent)]
+    TaskArgs(#[from] TaskArgError),
+
+    #[error(transparent)]
+    TaskState(#[from] TaskStateError),
+
+    #[error(transparent)]
+    Report(#[from] ReportError),
+
+    #[error(transparent)]
+    Service(#[from] ServiceError),
+
+    #[error(transparent)]
+    Lsp(#[from] LspError),
+
     #[error("File already exists: {0}. Use --force to overwrite")]
     FileExists(String),
 
     #[error("No test commands defined in lazynix.yaml. Add test attribute to devShell.")]
     NoTestCommands,
 
+    #[error("No test matches '{0}'")]
+    NoMatchingTests(String),
+
     #[error("No tasks defined in lazynix.yaml")]
     NoTasksDefined,
 
     #[error("Task '{0}' not found in lazynix.yaml")]
     TaskNotFound(String),
 
+    #[error("No services defined in lazynix.yaml. Add a `services` list to the dev shell")]
+    NoServices,
+
+    #[error("Service '{0}' not found in lazynix.yaml")]
+    ServiceNotFound(String),
+
+    #[error("Service '{0}' exited while starting. Run `lnix logs {0}` to see why")]
+    ServiceExited(String),
+
+    #[error(
+        "Service '{service}' was not ready after {seconds}s. Run `lnix logs {service}` to see why"
+    )]
+    ServiceNotReady { service: String, seconds: u64 },
+
+    #[error("Dev shell '{0}' not found in lazynix.yaml")]
+    ShellNotFound(String),
+
     #[error("command arguments cannot be empty")]
     EmptyRunCommand,
 }
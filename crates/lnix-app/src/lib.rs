@@ -18,7 +18,12 @@ mod error;
 mod mocks;
 mod pipeline;
 mod usecase;
+mod watch;
 
 pub use deps::Deps;
 pub use error::ApplicationError;
-pub use usecase::{develop, generate, init, lint, run, search, task, test, update};
+pub use usecase::{
+    TaskOptions, TestOptions, develop, direnv_init, down, env, generate, init, lint, logs, lsp, ps,
+    run, schema, search, task, test, up, update,
+};
+pub use watch::watch;
@@ -4,18 +4,27 @@
 //! a test is: build `Mocks`, run the use-case, assert on recordings.
 //! No filesystem, no subprocess, no terminal.
 
-use std::{cell::RefCell, collections::HashMap};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::num::NonZeroUsize;
+use std::rc::Rc;
+use std::time::Duration;
 
 use lnix_domain::interface::gateway::{
-    EvalOutcome, NixEvaluator, NixRunner, ResolvedVersion, VersionResolver,
+    DevEnv, EvalOutcome, NixEvaluator, NixRunner, PackageIndex, PackageInfo, Readiness,
+    ResolvedVersion, ServiceStatus, ServiceSupervisor, TestExecution, TestInvocation,
+    VersionResolver,
 };
+use lnix_domain::interface::lsp::{ClientMessage, LanguageClient, ServerMessage};
 use lnix_domain::interface::output::OutputPort;
 use lnix_domain::interface::persistence::{
-    ConfigRepository, EnvFilePresenceChecker, FlakeReader, FlakeWriter, PinnedResolution,
-    PinnedResolutions, ProjectScaffolder,
+    ConfigRepository, ConfigWatcher, EnvFilePresenceChecker, FlakeReader, FlakeWriter,
+    PinnedResolution, PinnedResolutions, ProjectScaffolder, ReportWriter, TaskStateStore,
 };
 use lnix_domain::{
-    ConfigError, DevShellDefinition, FlakeError, NixError, PackageName, PackageVersion, Settings,
+    ConfigError, DevShellDefinition, FlakeError, LspError, NixError, PackageName, PackageVersion,
+    ReadyCheck, ReportError, ServiceError, ServiceName, Settings, ShellName, TaskName,
+    TaskStateError, Timeout, YamlError,
 };
 
 use crate::deps::Deps;
@@ -26,6 +35,7 @@ pub(crate) fn config_from_yaml(yaml: &str) -> DevShellDefinition {
 
 pub(crate) struct MockRepo {
     config: Option<DevShellDefinition>,
+    extended: Vec<String>,
 }
 
 impl ConfigRepository for MockRepo {
@@ -38,24 +48,42 @@ impl ConfigRepository for MockRepo {
     fn read_settings(&self) -> Result<Option<Settings>, ConfigError> {
         Ok(None)
     }
+
+    fn extended_files(&self) -> Result<Vec<String>, ConfigError> {
+        Ok(self.extended.clone())
+    }
+
+    fn parse_config(&self, text: &str) -> Result<DevShellDefinition, YamlError> {
+        serde_yaml::from_str(text).map_err(|e| YamlError {
+            message: e.to_string(),
+            location: e.location().map(|l| (l.line(), l.column())),
+        })
+    }
 }
 
 #[derive(Default)]
 pub(crate) struct SpyWriter {
     written: RefCell<Option<String>>,
+    writes: RefCell<usize>,
 }
 
 impl FlakeWriter for SpyWriter {
     fn write_flake(&self, contents: &str) -> Result<(), FlakeError> {
         *self.written.borrow_mut() = Some(contents.to_string());
+        *self.writes.borrow_mut() += 1;
         Ok(())
     }
 }
 
 impl SpyWriter {
+    /// The last contents written.
     pub(crate) fn written(&self) -> Option<String> {
         self.written.borrow().clone()
     }
+
+    pub(crate) fn write_count(&self) -> usize {
+        *self.writes.borrow()
+    }
 }
 
 pub(crate) struct StubEnvChecker {
@@ -72,8 +100,10 @@ impl EnvFilePresenceChecker for StubEnvChecker {
 pub(crate) struct MockScaffolder {
     pub(crate) config_present: bool,
     pub(crate) flake_present: bool,
+    pub(crate) envrc_present: bool,
     config_written: RefCell<bool>,
     flake_written: RefCell<bool>,
+    envrc_written: RefCell<Option<String>>,
 }
 
 impl ProjectScaffolder for MockScaffolder {
@@ -102,6 +132,19 @@ impl ProjectScaffolder for MockScaffolder {
         *self.flake_written.borrow_mut() = true;
         Ok(())
     }
+
+    fn envrc_exists(&self) -> bool {
+        self.envrc_present
+    }
+
+    fn envrc_path_display(&self) -> String {
+        "./.envrc".to_string()
+    }
+
+    fn write_envrc(&self, contents: &str) -> Result<(), ConfigError> {
+        *self.envrc_written.borrow_mut() = Some(contents.to_string());
+        Ok(())
+    }
 }
 
 impl MockScaffolder {
@@ -112,36 +155,116 @@ impl MockScaffolder {
     pub(crate) fn flake_written(&self) -> bool {
         *self.flake_written.borrow()
     }
+
+    pub(crate) fn envrc_written(&self) -> Option<String> {
+        self.envrc_written.borrow().clone()
+    }
 }
 
 #[derive(Default)]
 pub(crate) struct FakeNix {
     develop_calls: RefCell<u32>,
     flake_update_calls: RefCell<u32>,
-    test_calls: RefCell<u32>,
-    run_task_commands: RefCell<Option<Vec<String>>>,
+    dev_env_calls: RefCell<u32>,
+    run_test_scripts: RefCell<Vec<String>>,
+    run_tests_jobs: RefCell<Option<NonZeroUsize>>,
+    failing_test_command: Option<String>,
+    run_task_calls: RefCell<Vec<Vec<String>>>,
+    failing_task_command: Option<String>,
+    /// A task command that edits the sources, and the revision it bumps.
+    sources_writer: Option<(String, Rc<RefCell<u32>>)>,
     develop_command_args: RefCell<Option<Vec<String>>>,
+    env_setups: RefCell<Vec<String>>,
+    last_shell: RefCell<Option<ShellName>>,
 }
 
 impl NixRunner for FakeNix {
-    fn develop(&self) -> Result<(), NixError> {
+    fn develop(&self, shell: &ShellName) -> Result<(), NixError> {
         *self.develop_calls.borrow_mut() += 1;
+        *self.last_shell.borrow_mut() = Some(shell.clone());
         Ok(())
     }
 
-    fn develop_command(&self, args: &[String]) -> Result<i32, NixError> {
+    fn develop_command(&self, shell: &ShellName, args: &[String]) -> Result<i32, NixError> {
         *self.develop_command_args.borrow_mut() = Some(args.to_vec());
+        *self.last_shell.borrow_mut() = Some(shell.clone());
         Ok(0)
     }
 
-    fn test(&self) -> Result<i32, NixError> {
-        *self.test_calls.borrow_mut() += 1;
-        Ok(0)
+    fn dev_env(&self, shell: &ShellName) -> Result<DevEnv, NixError> {
+        *self.dev_env_calls.borrow_mut() += 1;
+        *self.last_shell.borrow_mut() = Some(shell.clone());
+        Ok(DevEnv::new(format!("# env of {}", shell)))
     }
 
-    fn run_task(&self, commands: &[String]) -> Result<i32, NixError> {
-        *self.run_task_commands.borrow_mut() = Some(commands.to_vec());
-        Ok(0)
+    fn dev_env_vars(
+        &self,
+        shell: &ShellName,
+        setup: &str,
+    ) -> Result<BTreeMap<String, String>, NixError> {
+        self.env_setups.borrow_mut().push(setup.to_string());
+        *self.last_shell.borrow_mut() = Some(shell.clone());
+        Ok(BTreeMap::from([
+            ("PATH".to_string(), "/nix/store/fake/bin".to_string()),
+            ("SHELL_NAME".to_string(), shell.to_string()),
+        ]))
+    }
+
+    fn run_test(
+        &self,
+        _env: &DevEnv,
+        script: &str,
+        _timeout: Option<&Timeout>,
+    ) -> Result<TestExecution, NixError> {
+        self.run_test_scripts.borrow_mut().push(script.to_string());
+        let fails = self
+            .failing_test_command
+            .as_ref()
+            .is_some_and(|failing| script.contains(failing.as_str()));
+        Ok(TestExecution {
+            exit_code: if fails { 1 } else { 0 },
+            duration: Duration::from_millis(10),
+            output: format!("output of {}\n", script),
+        })
+    }
+
+    /// Runs sequentially through `run_test`, stopping like the real
+    /// runner does with `fail_fast`.
+    fn run_tests(
+        &self,
+        env: &DevEnv,
+        tests: &[TestInvocation],
+        jobs: NonZeroUsize,
+        fail_fast: bool,
+    ) -> Result<Vec<Option<TestExecution>>, NixError> {
+        *self.run_tests_jobs.borrow_mut() = Some(jobs);
+        let mut stopped = false;
+        let mut executions = Vec::new();
+        for test in tests {
+            if stopped {
+                executions.push(None);
+                continue;
+            }
+            let execution = self.run_test(env, &test.script, test.timeout.as_ref())?;
+            stopped = fail_fast && execution.exit_code != 0 && !test.allow_failure;
+            executions.push(Some(execution));
+        }
+        Ok(executions)
+    }
+
+    fn run_task(&self, shell: &ShellName, commands: &[String]) -> Result<i32, NixError> {
+        self.run_task_calls.borrow_mut().push(commands.to_vec());
+        *self.last_shell.borrow_mut() = Some(shell.clone());
+        let fails = self
+            .failing_task_command
+            .as_ref()
+            .is_some_and(|failing| commands.contains(failing));
+        if let Some((writer, revision)) = &self.sources_writer
+            && commands.contains(writer)
+        {
+            *revision.borrow_mut() += 1;
+        }
+        Ok(if fails { 1 } else { 0 })
     }
 
     fn flake_update(&self) -> Result<(), NixError> {
@@ -159,17 +282,43 @@ impl FakeNix {
         *self.flake_update_calls.borrow()
     }
 
-    pub(crate) fn test_calls(&self) -> u32 {
-        *self.test_calls.borrow()
+    /// `setup` of every `dev_env_vars` call, in order.
+    pub(crate) fn env_setups(&self) -> Vec<String> {
+        self.env_setups.borrow().clone()
+    }
+
+    pub(crate) fn dev_env_calls(&self) -> u32 {
+        *self.dev_env_calls.borrow()
     }
 
+    /// `jobs` of the most recent `run_tests` call.
+    pub(crate) fn run_tests_jobs(&self) -> Option<usize> {
+        self.run_tests_jobs.borrow().map(NonZeroUsize::get)
+    }
+
+    /// Scripts of every test run, in call order.
+    pub(crate) fn run_test_scripts(&self) -> Vec<String> {
+        self.run_test_scripts.borrow().clone()
+    }
+
+    /// Commands of the most recent `run_task` call.
     pub(crate) fn run_task_commands(&self) -> Option<Vec<String>> {
-        self.run_task_commands.borrow().clone()
+        self.run_task_calls.borrow().last().cloned()
+    }
+
+    /// Commands of every `run_task` call, in call order.
+    pub(crate) fn run_task_calls(&self) -> Vec<Vec<String>> {
+        self.run_task_calls.borrow().clone()
     }
 
     pub(crate) fn develop_command_args(&self) -> Option<Vec<String>> {
         self.develop_command_args.borrow().clone()
     }
+
+    /// The shell targeted by the most recent shell-entering call.
+    pub(crate) fn last_shell(&self) -> Option<String> {
+        self.last_shell.borrow().as_ref().map(ToString::to_string)
+    }
 }
 
 #[derive(Default)]
@@ -281,6 +430,300 @@ impl MockFlakeReader {
     }
 }
 
+/// Fingerprints are the task's commands plus a revision counter, so a
+/// test edits the sources by calling [`FakeTaskState::touch_sources`].
+#[derive(Default)]
+pub(crate) struct FakeTaskState {
+    revision: Rc<RefCell<u32>>,
+    outputs_missing: bool,
+    recorded: RefCell<HashMap<String, String>>,
+}
+
+impl TaskStateStore for FakeTaskState {
+    fn fingerprint(
+        &self,
+        _dir: Option<&str>,
+        sources: &[String],
+        commands: &[String],
+    ) -> Result<String, TaskStateError> {
+        Ok(format!(
+            "{}|{}|{}",
+            self.revision.borrow(),
+            sources.join(","),
+            commands.join(";")
+        ))
+    }
+
+    fn outputs_exist(
+        &self,
+        _dir: Option<&str>,
+        _generates: &[String],
+    ) -> Result<bool, TaskStateError> {
+        Ok(!self.outputs_missing)
+    }
+
+    fn recorded(
+        &self,
+        shell: &ShellName,
+        task: &TaskName,
+    ) -> Result<Option<String>, TaskStateError> {
+        Ok(self
+            .recorded
+            .borrow()
+            .get(&format!("{}/{}", shell, task))
+            .cloned())
+    }
+
+    fn record(
+        &self,
+        shell: &ShellName,
+        task: &TaskName,
+        fingerprint: &str,
+    ) -> Result<(), TaskStateError> {
+        self.recorded
+            .borrow_mut()
+            .insert(format!("{}/{}", shell, task), fingerprint.to_string());
+        Ok(())
+    }
+}
+
+impl FakeTaskState {
+    /// Changes every fingerprint computed from now on.
+    pub(crate) fn touch_sources(&self) {
+        *self.revision.borrow_mut() += 1;
+    }
+
+    /// `<shell>/<task>` of every task with a recorded fingerprint, sorted.
+    pub(crate) fn recorded_tasks(&self) -> Vec<String> {
+        let mut tasks: Vec<String> = self.recorded.borrow().keys().cloned().collect();
+        tasks.sort();
+        tasks
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct SpyReportWriter {
+    written: RefCell<Vec<(String, String)>>,
+}
+
+impl ReportWriter for SpyReportWriter {
+    fn write_report(&self, path: &str, contents: &str) -> Result<(), ReportError> {
+        self.written
+            .borrow_mut()
+            .push((path.to_string(), contents.to_string()));
+        Ok(())
+    }
+}
+
+impl SpyReportWriter {
+    /// `(path, contents)` of every report written, in order.
+    pub(crate) fn written(&self) -> Vec<(String, String)> {
+        self.written.borrow().clone()
+    }
+}
+
+/// Services start with increasing pids, become ready at once (unless
+/// made to exit), and log one line naming their script. Services are
+/// keyed, and recorded, as `<shell>/<service>`.
+#[derive(Default)]
+pub(crate) struct FakeServices {
+    statuses: RefCell<HashMap<String, ServiceStatus>>,
+    started: RefCell<Vec<(String, String)>>,
+    probed: RefCell<Vec<String>>,
+    stopped: RefCell<Vec<String>>,
+    followed: RefCell<Vec<String>>,
+    logs: RefCell<HashMap<String, String>>,
+    exiting_service: Option<String>,
+}
+
+fn service_key(shell: &ShellName, service: &ServiceName) -> String {
+    format!("{}/{}", shell, service)
+}
+
+impl ServiceSupervisor for FakeServices {
+    fn status(
+        &self,
+        shell: &ShellName,
+        service: &ServiceName,
+    ) -> Result<ServiceStatus, ServiceError> {
+        Ok(self
+            .statuses
+            .borrow()
+            .get(&service_key(shell, service))
+            .copied()
+            .unwrap_or(ServiceStatus::Stopped))
+    }
+
+    fn start(
+        &self,
+        _env: &DevEnv,
+        shell: &ShellName,
+        service: &ServiceName,
+        script: &str,
+    ) -> Result<u32, ServiceError> {
+        let key = service_key(shell, service);
+        let mut started = self.started.borrow_mut();
+        started.push((key.clone(), script.to_string()));
+        let pid = 1000 + started.len() as u32;
+        let status = if self.exiting_service.as_deref() == Some(key.as_str()) {
+            ServiceStatus::Exited
+        } else {
+            ServiceStatus::Running { pid }
+        };
+        self.statuses.borrow_mut().insert(key.clone(), status);
+        self.logs
+            .borrow_mut()
+            .insert(key, format!("log of {}\n", script));
+        Ok(pid)
+    }
+
+    fn wait_ready(
+        &self,
+        _env: &DevEnv,
+        shell: &ShellName,
+        service: &ServiceName,
+        check: &ReadyCheck,
+        _limit: Duration,
+    ) -> Result<Readiness, ServiceError> {
+        let key = service_key(shell, service);
+        self.probed.borrow_mut().push(match check {
+            ReadyCheck::Port(port) => format!("{}: port {}", key, port),
+            ReadyCheck::Command(script) => format!("{}: {}", key, script),
+        });
+        match self.status(shell, service)? {
+            ServiceStatus::Running { .. } => Ok(Readiness::Ready),
+            _ => Ok(Readiness::Exited),
+        }
+    }
+
+    fn stop(&self, shell: &ShellName, service: &ServiceName) -> Result<bool, ServiceError> {
+        let key = service_key(shell, service);
+        let previous = self
+            .statuses
+            .borrow_mut()
+            .insert(key.clone(), ServiceStatus::Stopped);
+        let was_running = matches!(previous, Some(ServiceStatus::Running { .. }));
+        if was_running {
+            self.stopped.borrow_mut().push(key);
+        }
+        Ok(was_running)
+    }
+
+    fn read_logs(
+        &self,
+        shell: &ShellName,
+        service: &ServiceName,
+    ) -> Result<Option<String>, ServiceError> {
+        Ok(self
+            .logs
+            .borrow()
+            .get(&service_key(shell, service))
+            .cloned())
+    }
+
+    fn follow_logs(&self, shell: &ShellName, service: &ServiceName) -> Result<(), ServiceError> {
+        self.followed.borrow_mut().push(service_key(shell, service));
+        Ok(())
+    }
+}
+
+impl FakeServices {
+    /// `(service, script)` of every start, in order.
+    pub(crate) fn started(&self) -> Vec<(String, String)> {
+        self.started.borrow().clone()
+    }
+
+    /// `<service>: <check>` of every readiness wait, in order.
+    pub(crate) fn probed(&self) -> Vec<String> {
+        self.probed.borrow().clone()
+    }
+
+    /// Services that were running when stopped, in order.
+    pub(crate) fn stopped(&self) -> Vec<String> {
+        self.stopped.borrow().clone()
+    }
+
+    pub(crate) fn followed(&self) -> Vec<String> {
+        self.followed.borrow().clone()
+    }
+}
+
+/// Reports the scripted changes one per call, then fails so that
+/// `watch` returns.
+#[derive(Default)]
+pub(crate) struct ScriptedWatcher {
+    changes: RefCell<Vec<Vec<String>>>,
+    watched: RefCell<Vec<Vec<String>>>,
+}
+
+impl ConfigWatcher for ScriptedWatcher {
+    fn wait_for_change(&self, files: &[String]) -> Result<Vec<String>, ConfigError> {
+        self.watched.borrow_mut().push(files.to_vec());
+        let mut changes = self.changes.borrow_mut();
+        if changes.is_empty() {
+            return Err(ConfigError::Io(std::io::Error::other("no more changes")));
+        }
+        Ok(changes.remove(0))
+    }
+}
+
+impl ScriptedWatcher {
+    /// The `files` of every wait, in order.
+    pub(crate) fn watched(&self) -> Vec<Vec<String>> {
+        self.watched.borrow().clone()
+    }
+}
+
+/// Knows the scripted packages (none by default), or fails to build.
+#[derive(Default)]
+pub(crate) struct StubPackageIndex {
+    packages: Vec<PackageInfo>,
+    failing: bool,
+    calls: RefCell<u32>,
+}
+
+impl PackageIndex for StubPackageIndex {
+    fn packages(&self) -> Result<Vec<PackageInfo>, NixError> {
+        *self.calls.borrow_mut() += 1;
+        if self.failing {
+            return Err(NixError::PackageIndex("nix search failed".to_string()));
+        }
+        Ok(self.packages.clone())
+    }
+}
+
+impl StubPackageIndex {
+    pub(crate) fn calls(&self) -> u32 {
+        *self.calls.borrow()
+    }
+}
+
+/// Delivers the scripted messages in order, then reports the
+/// connection closed; records everything sent.
+#[derive(Default)]
+pub(crate) struct ScriptedClient {
+    incoming: RefCell<VecDeque<ClientMessage>>,
+    sent: RefCell<Vec<ServerMessage>>,
+}
+
+impl LanguageClient for ScriptedClient {
+    fn receive(&self) -> Result<Option<ClientMessage>, LspError> {
+        Ok(self.incoming.borrow_mut().pop_front())
+    }
+
+    fn send(&self, message: ServerMessage) -> Result<(), LspError> {
+        self.sent.borrow_mut().push(message);
+        Ok(())
+    }
+}
+
+impl ScriptedClient {
+    /// Every message sent, in order.
+    pub(crate) fn sent(&self) -> Vec<ServerMessage> {
+        self.sent.borrow().clone()
+    }
+}
+
 #[derive(Default)]
 pub(crate) struct RecordingOutput {
     infos: RefCell<Vec<String>>,
@@ -317,6 +760,12 @@ pub(crate) struct Mocks {
     pub(crate) nix: FakeNix,
     pub(crate) nix_eval: StubEvaluator,
     pub(crate) resolver: StubResolver,
+    pub(crate) task_state: FakeTaskState,
+    pub(crate) reports: SpyReportWriter,
+    pub(crate) services: FakeServices,
+    pub(crate) watcher: ScriptedWatcher,
+    pub(crate) packages: StubPackageIndex,
+    pub(crate) lsp: ScriptedClient,
     pub(crate) out: RecordingOutput,
 }
 
@@ -329,6 +778,11 @@ impl Mocks {
         Self::build(None)
     }
 
+    pub(crate) fn with_extended_files(mut self, files: &[&str]) -> Self {
+        self.repo.extended = files.iter().map(|file| file.to_string()).collect();
+        self
+    }
+
     pub(crate) fn with_missing_env_files(mut self) -> Self {
         self.env = StubEnvChecker { all_present: false };
         self
@@ -340,6 +794,11 @@ impl Mocks {
         self
     }
 
+    pub(crate) fn with_existing_envrc(mut self) -> Self {
+        self.scaffolder.envrc_present = true;
+        self
+    }
+
     pub(crate) fn with_failing_packages(mut self, names: &[&str]) -> Self {
         self.nix_eval.failing = names.iter().map(|name| name.to_string()).collect();
         self
@@ -358,6 +817,82 @@ impl Mocks {
         self
     }
 
+    /// `run_task` exits 1 for any task that includes `command`.
+    pub(crate) fn with_failing_task_command(mut self, command: &str) -> Self {
+        self.nix.failing_task_command = Some(command.to_string());
+        self
+    }
+
+    /// Running a task that includes `command` edits every task's sources.
+    pub(crate) fn with_sources_written_by(mut self, command: &str) -> Self {
+        self.nix.sources_writer = Some((command.to_string(), Rc::clone(&self.task_state.revision)));
+        self
+    }
+
+    /// `generates` globs never match, so no task is up to date.
+    pub(crate) fn with_missing_task_outputs(mut self) -> Self {
+        self.task_state.outputs_missing = true;
+        self
+    }
+
+    /// `run_test` exits 1 for any test whose script includes `command`.
+    pub(crate) fn with_failing_test_command(mut self, command: &str) -> Self {
+        self.nix.failing_test_command = Some(command.to_string());
+        self
+    }
+
+    /// Services (`<shell>/<service>`) already running before the
+    /// use-case, with their pids.
+    pub(crate) fn with_running_services(self, services: &[(&str, u32)]) -> Self {
+        for (service, pid) in services {
+            self.services
+                .statuses
+                .borrow_mut()
+                .insert(service.to_string(), ServiceStatus::Running { pid: *pid });
+        }
+        self
+    }
+
+    /// The watcher reports these changes, one per wait, then fails.
+    pub(crate) fn with_config_changes(self, changes: &[&[&str]]) -> Self {
+        *self.watcher.changes.borrow_mut() = changes
+            .iter()
+            .map(|files| files.iter().map(|file| file.to_string()).collect())
+            .collect();
+        self
+    }
+
+    /// `service` (`<shell>/<service>`) exits as soon as it is started.
+    pub(crate) fn with_exiting_service(mut self, service: &str) -> Self {
+        self.services.exiting_service = Some(service.to_string());
+        self
+    }
+
+    /// The editor sends these messages, then closes the connection.
+    pub(crate) fn with_client_messages(self, messages: Vec<ClientMessage>) -> Self {
+        *self.lsp.incoming.borrow_mut() = messages.into();
+        self
+    }
+
+    /// The package index holds these `(attr, version, description)`,
+    /// which must be sorted by attr.
+    pub(crate) fn with_indexed_packages(mut self, packages: &[(&str, &str, &str)]) -> Self {
+        self.packages.packages = packages
+            .iter()
+            .map(|(attr, version, description)| PackageInfo {
+                attr: attr.to_string(),
+                version: version.to_string(),
+                description: description.to_string(),
+            })
+            .collect();
+        self
+    }
+
+    pub(crate) fn with_failing_package_index(mut self) -> Self {
+        self.packages.failing = true;
+        self
+    }
+
     pub(crate) fn with_flake_reader(mut self, reader: MockFlakeReader) -> Self {
         self.flake_reader = reader;
         self
@@ -373,7 +908,10 @@ impl Mocks {
 
     fn build(config: Option<DevShellDefinition>) -> Self {
         Self {
-            repo: MockRepo { config },
+            repo: MockRepo {
+                config,
+                extended: Vec::new(),
+            },
             flake_writer: SpyWriter::default(),
             flake_reader: MockFlakeReader::empty(),
             env: StubEnvChecker { all_present: true },
@@ -381,6 +919,12 @@ impl Mocks {
             nix: FakeNix::default(),
             nix_eval: StubEvaluator::default(),
             resolver: StubResolver::default(),
+            task_state: FakeTaskState::default(),
+            reports: SpyReportWriter::default(),
+            services: FakeServices::default(),
+            watcher: ScriptedWatcher::default(),
+            packages: StubPackageIndex::default(),
+            lsp: ScriptedClient::default(),
             out: RecordingOutput::default(),
         }
     }
@@ -395,6 +939,12 @@ impl Mocks {
             nix: &self.nix,
             nix_eval: &self.nix_eval,
             resolver: &self.resolver,
+            packages: &self.packages,
+            task_state: &self.task_state,
+            reports: &self.reports,
+            services: &self.services,
+            watcher: &self.watcher,
+            lsp: &self.lsp,
             out: &self.out,
         }
     }
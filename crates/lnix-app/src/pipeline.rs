@@ -5,7 +5,8 @@
 //! each use-case only adds its own tail (entering the shell, running
 //! tests, executing a command).
 
-use lnix_domain::{DevShellDefinition, render_flake};
+use lnix_domain::interface::persistence::PinnedResolution;
+use lnix_domain::{DevShell, DevShellDefinition, ShellName, render_flake};
 
 use crate::deps::Deps;
 use crate::error::ApplicationError;
@@ -17,6 +18,15 @@ pub(crate) struct LoadedConfig {
     override_url: Option<String>,
 }
 
+impl LoadedConfig {
+    /// The dev shell a use-case was asked to target.
+    pub(crate) fn shell(&self, name: &ShellName) -> Result<&DevShell, ApplicationError> {
+        self.config
+            .shell(name)
+            .ok_or_else(|| ApplicationError::ShellNotFound(name.to_string()))
+    }
+}
+
 /// Reads, validates, and resolves the config — everything needed before
 /// rendering, but without writing `flake.nix` yet.
 pub(crate) fn load_config(deps: &Deps) -> Result<LoadedConfig, ApplicationError> {
@@ -46,10 +56,12 @@ pub(crate) fn load_config(deps: &Deps) -> Result<LoadedConfig, ApplicationError>
 
 /// Fails when a dotenv file referenced by the config does not exist.
 fn validate_env_files(deps: &Deps, config: &DevShellDefinition) -> Result<(), ApplicationError> {
-    let Some(env) = &config.dev_shell.env else {
-        return Ok(());
-    };
-    for dotenv_path in &env.dotenv {
+    let dotenv_paths = config
+        .dev_shells
+        .values()
+        .filter_map(|dev_shell| dev_shell.env.as_ref())
+        .flat_map(|env| &env.dotenv);
+    for dotenv_path in dotenv_paths {
         if !deps.env.exists(dotenv_path) {
             return Err(lnix_domain::ConfigError::DotenvFileNotFound(dotenv_path.clone()).into());
         }
@@ -61,7 +73,8 @@ fn validate_env_files(deps: &Deps, config: &DevShellDefinition) -> Result<(), Ap
 /// preferring the rendered `flake.nix` as the source of truth and
 /// falling back to the version resolver for cache misses. Never
 /// rewrites `lazynix.yaml`; the generated `flake.nix` and `flake.lock`
-/// are the durable record.
+/// are the durable record. A version pinned in several shells is
+/// resolved once and shared.
 // NOTE: if the resolver errors mid-loop the caller's config is left
 // in a partially-mutated state; safe today because load_config aborts.
 fn resolve_pinned_packages(
@@ -69,7 +82,11 @@ fn resolve_pinned_packages(
     config: &mut DevShellDefinition,
 ) -> Result<(), ApplicationError> {
     let mut cached = deps.flake_reader.read_pinned_inputs()?;
-    for entry in &mut config.dev_shell.package.pinned {
+    let pinned_entries = config
+        .dev_shells
+        .values_mut()
+        .flat_map(|dev_shell| &mut dev_shell.package.pinned);
+    for entry in pinned_entries {
         if entry.version.as_str().contains('-') {
             deps.out.warn(&format!(
                 "Version '{}' for '{}' contains '-' which conflicts with the pinned-input separator; skipping flake.nix cache and re-resolving via nix-versions each run.",
@@ -77,9 +94,9 @@ fn resolve_pinned_packages(
             ));
         }
         let key = (entry.name.clone(), entry.version.clone());
-        if let Some(resolution) = cached.remove(&key) {
-            entry.resolved_commit = Some(resolution.commit);
-            entry.resolved_attr = Some(resolution.attr);
+        if let Some(resolution) = cached.get(&key) {
+            entry.resolved_commit = Some(resolution.commit.clone());
+            entry.resolved_attr = Some(resolution.attr.clone());
             continue;
         }
         deps.out.info(&format!(
@@ -87,8 +104,15 @@ fn resolve_pinned_packages(
             entry.name, entry.version
         ));
         let resolved = deps.resolver.resolve(&entry.name, &entry.version)?;
-        entry.resolved_commit = Some(resolved.commit);
-        entry.resolved_attr = Some(resolved.attr);
+        entry.resolved_commit = Some(resolved.commit.clone());
+        entry.resolved_attr = Some(resolved.attr.clone());
+        cached.insert(
+            key,
+            PinnedResolution {
+                commit: resolved.commit,
+                attr: resolved.attr,
+            },
+        );
     }
     Ok(())
 }
@@ -119,9 +143,7 @@ pub(crate) fn maybe_update_lock(deps: &Deps, update_lock: bool) -> Result<(), Ap
 mod tests {
     use super::*;
     use crate::mocks::*;
-    use lnix_domain::interface::persistence::{
-        ConfigRepository, PinnedResolution, PinnedResolutions,
-    };
+    use lnix_domain::interface::persistence::{ConfigRepository, PinnedResolutions};
     use std::collections::HashMap;
 
     fn config_with_pinned(entries: &[(&str, &str)]) -> DevShellDefinition {
@@ -135,6 +157,10 @@ mod tests {
         config_from_yaml(&yaml)
     }
 
+    fn default_shell(config: &DevShellDefinition) -> &lnix_domain::DevShell {
+        config.shell(&Default::default()).unwrap()
+    }
+
     fn resolutions_from(entries: &[(&str, &str, &str, &str)]) -> PinnedResolutions {
         let mut inputs: PinnedResolutions = HashMap::new();
         for (name, version, commit, attr) in entries {
@@ -159,7 +185,7 @@ mod tests {
         resolve_pinned_packages(&m.deps(), &mut config).unwrap();
 
         assert!(m.resolver.resolve_calls().is_empty());
-        let pinned = &config.dev_shell.package.pinned[0];
+        let pinned = &default_shell(&config).package.pinned[0];
         assert_eq!(pinned.resolved_commit.as_deref(), Some("5ed6275"));
         assert_eq!(pinned.resolved_attr.as_deref(), Some("go_1_21"));
     }
@@ -173,7 +199,7 @@ mod tests {
         resolve_pinned_packages(&m.deps(), &mut config).unwrap();
 
         assert_eq!(m.resolver.resolve_calls(), vec!["go".to_string()]);
-        let pinned = &config.dev_shell.package.pinned[0];
+        let pinned = &default_shell(&config).package.pinned[0];
         assert_eq!(pinned.resolved_commit.as_deref(), Some("e607cb5"));
         assert_eq!(pinned.resolved_attr.as_deref(), Some("go_1_21"));
     }
@@ -216,7 +242,7 @@ mod tests {
         resolve_pinned_packages(&m.deps(), &mut config).unwrap();
 
         assert!(m.resolver.resolve_calls().is_empty());
-        let pinned = &config.dev_shell.package.pinned[0];
+        let pinned = &default_shell(&config).package.pinned[0];
         assert_eq!(pinned.resolved_commit.as_deref(), Some("NEW_COMMIT"));
         assert_eq!(pinned.resolved_attr.as_deref(), Some("NEW_ATTR"));
     }
@@ -232,7 +258,7 @@ mod tests {
         assert_eq!(m.resolver.resolve_calls(), vec!["go".to_string()]);
         let warns = m.out.warns().join("\n");
         assert!(warns.contains("1.0.0-rc1"));
-        let pinned = &config.dev_shell.package.pinned[0];
+        let pinned = &default_shell(&config).package.pinned[0];
         assert_eq!(pinned.resolved_commit.as_deref(), Some("e607cb5"));
     }
 
@@ -250,11 +276,29 @@ mod tests {
         resolve_pinned_packages(&m.deps(), &mut config).unwrap();
 
         assert_eq!(m.resolver.resolve_calls(), vec!["rust".to_string()]);
-        let go = &config.dev_shell.package.pinned[0];
+        let go = &default_shell(&config).package.pinned[0];
         assert_eq!(go.resolved_commit.as_deref(), Some("5ed6275"));
         assert_eq!(go.resolved_attr.as_deref(), Some("go_1_21_cached"));
-        let rust = &config.dev_shell.package.pinned[1];
+        let rust = &default_shell(&config).package.pinned[1];
         assert_eq!(rust.resolved_commit.as_deref(), Some("e607cb5"));
         assert_eq!(rust.resolved_attr.as_deref(), Some("go_1_21"));
     }
+
+    #[test]
+    fn version_pinned_in_several_shells_is_resolved_once() {
+        let m = Mocks::with_config(config_from_yaml(
+            "devShells:\n  default:\n    package:\n      pinned:\n        - name: go\n          version: \"1.21.13\"\n  ci:\n    package:\n      pinned:\n        - name: go\n          version: \"1.21.13\"\n",
+        ))
+        .with_flake_reader(MockFlakeReader::empty());
+        let mut config = m.repo.read_config().unwrap();
+
+        resolve_pinned_packages(&m.deps(), &mut config).unwrap();
+
+        assert_eq!(m.resolver.resolve_calls(), vec!["go".to_string()]);
+        let ci = config.shell(&"ci".parse().unwrap()).unwrap();
+        assert_eq!(
+            ci.package.pinned[0].resolved_commit.as_deref(),
+            Some("e607cb5")
+        );
+    }
 }
@@ -1,19 +1,23 @@
 //! `lnix develop` — generate the flake and enter the dev shell.
 
+use lnix_domain::ShellName;
+
 use crate::deps::Deps;
 use crate::error::ApplicationError;
 use crate::pipeline;
 
 /// Renders `flake.nix`, optionally updates the lock, and enters
-/// `nix develop`. Returns the process exit code.
-pub fn develop(d: &Deps, update_lock: bool) -> Result<i32, ApplicationError> {
+/// `nix develop` for the named dev shell. Returns the process exit code.
+pub fn develop(d: &Deps, shell: &str, update_lock: bool) -> Result<i32, ApplicationError> {
+    let shell: ShellName = shell.parse()?;
     let loaded = pipeline::load_config(d)?;
+    loaded.shell(&shell)?;
     pipeline::write_flake(d, &loaded)?;
     pipeline::maybe_update_lock(d, update_lock)?;
 
     d.out.info("");
     d.out.info("Entering nix develop shell...");
-    d.nix.develop()?;
+    d.nix.develop(&shell)?;
     Ok(0)
 }
 
@@ -29,7 +33,7 @@ mod tests {
             "devShell:\n  package:\n    stable:\n      - name: bash\n",
         ));
 
-        let code = develop(&m.deps(), false).unwrap();
+        let code = develop(&m.deps(), "default", false).unwrap();
 
         assert_eq!(code, 0);
         assert!(m.flake_writer.written().unwrap().contains("bash"));
@@ -52,7 +56,7 @@ mod tests {
             "devShell:\n  package:\n    stable:\n      - name: bash\n",
         ));
 
-        let code = develop(&m.deps(), true).unwrap();
+        let code = develop(&m.deps(), "default", true).unwrap();
 
         assert_eq!(code, 0);
         assert_eq!(m.nix.flake_update_calls(), 1);
@@ -67,7 +71,7 @@ mod tests {
     fn missing_config_short_circuits_before_any_side_effect() {
         let m = Mocks::with_missing_config();
 
-        let result = develop(&m.deps(), false);
+        let result = develop(&m.deps(), "default", false);
 
         assert!(matches!(
             result,
@@ -84,7 +88,7 @@ mod tests {
         ))
         .with_missing_env_files();
 
-        let result = develop(&m.deps(), false);
+        let result = develop(&m.deps(), "default", false);
 
         assert!(matches!(
             result,
@@ -99,7 +103,7 @@ mod tests {
             "devShell:\n  package:\n    stable:\n      - name: bash\n    pinned:\n      - name: go\n        version: \"1.21.13\"\n",
         ));
 
-        develop(&m.deps(), false).unwrap();
+        develop(&m.deps(), "default", false).unwrap();
 
         let written = m
             .flake_writer
@@ -113,12 +117,35 @@ mod tests {
     fn warns_about_empty_package_list_via_output_port() {
         let m = Mocks::with_config(config_from_yaml("devShell:\n  package:\n    stable: []\n"));
 
-        develop(&m.deps(), false).unwrap();
+        develop(&m.deps(), "default", false).unwrap();
 
-        assert!(
-            m.out
-                .warns()
-                .contains(&"No packages specified in lazynix.yaml".to_string())
-        );
+        assert!(m.out.warns().contains(
+            &"No packages specified in lazynix.yaml for dev shell 'default'".to_string()
+        ));
+    }
+
+    #[test]
+    fn enters_the_requested_named_shell() {
+        let m = Mocks::with_config(config_from_yaml(
+            "devShells:\n  default:\n    package:\n      stable:\n        - name: bash\n  ci:\n    package:\n      stable:\n        - name: jq\n",
+        ));
+
+        develop(&m.deps(), "ci", false).unwrap();
+
+        assert_eq!(m.nix.last_shell().as_deref(), Some("ci"));
+        assert!(m.flake_writer.written().unwrap().contains("devShells.ci"));
+    }
+
+    #[test]
+    fn unknown_shell_fails_before_writing_flake() {
+        let m = Mocks::with_config(config_from_yaml(
+            "devShell:\n  package:\n    stable:\n      - name: bash\n",
+        ));
+
+        let result = develop(&m.deps(), "ci", false);
+
+        assert!(matches!(result, Err(ApplicationError::ShellNotFound(name)) if name == "ci"));
+        assert!(m.flake_writer.written().is_none());
+        assert_eq!(m.nix.develop_calls(), 0);
     }
 }
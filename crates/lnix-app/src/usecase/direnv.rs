@@ -0,0 +1,111 @@
+//! `lnix direnv init` — hand the dev shell over to direnv.
+
+use lnix_domain::{ShellName, render_envrc};
+
+use crate::deps::Deps;
+use crate::error::ApplicationError;
+
+/// Writes an `.envrc` next to `lazynix.yaml` that keeps `flake.nix` up
+/// to date and loads the named dev shell. The files it watches are
+/// those the config extends and references now; rerun with `force`
+/// after adding one.
+///
+/// Without `force`, refuses to overwrite an existing `.envrc`.
+pub fn direnv_init(d: &Deps, shell: &str, force: bool) -> Result<i32, ApplicationError> {
+    let shell: ShellName = shell.parse()?;
+    let config = d.repo.read_config()?;
+    if config.shell(&shell).is_none() {
+        return Err(ApplicationError::ShellNotFound(shell.to_string()));
+    }
+    if !force && d.scaffolder.envrc_exists() {
+        return Err(ApplicationError::FileExists(
+            d.scaffolder.envrc_path_display(),
+        ));
+    }
+
+    let mut files = d.repo.extended_files()?;
+    files.extend(config.referenced_files());
+    d.scaffolder.write_envrc(&render_envrc(&shell, &files))?;
+
+    let envrc_path = d.scaffolder.envrc_path_display();
+    d.out.info(&format!("✓ Created: {}", envrc_path));
+    d.out.info("");
+    d.out.info("Next steps:");
+    d.out.info(&format!(
+        "  1. Run 'direnv allow {}' to load the dev shell on entering the directory",
+        envrc_path
+    ));
+    d.out.info("  2. Add '.direnv/' to .gitignore");
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks::*;
+
+    const CONFIG: &str = "devShell:\n  package:\n    stable:\n      - name: bash\n  env:\n    dotenv:\n      - .env\n";
+
+    #[test]
+    fn writes_envrc_watching_referenced_files() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(CONFIG));
+
+        // Act
+        let code = direnv_init(&m.deps(), "default", false).unwrap();
+
+        // Assert
+        assert_eq!(code, 0);
+        let envrc = m
+            .scaffolder
+            .envrc_written()
+            .expect(".envrc should be written");
+        assert!(envrc.contains("lazynix_inputs=(lazynix.yaml lazynix-settings.yaml .env)"));
+        assert!(envrc.ends_with("use flake\n"));
+        assert_eq!(m.out.infos()[0], "✓ Created: ./.envrc");
+    }
+
+    #[test]
+    fn envrc_also_watches_extended_configs() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(CONFIG))
+            .with_extended_files(&["shared/base.yaml", "shared/lang/go.yaml"]);
+
+        // Act
+        direnv_init(&m.deps(), "default", false).unwrap();
+
+        // Assert
+        let envrc = m.scaffolder.envrc_written().unwrap();
+        assert!(envrc.contains(
+            "lazynix_inputs=(lazynix.yaml lazynix-settings.yaml shared/base.yaml shared/lang/go.yaml .env)"
+        ));
+    }
+
+    #[test]
+    fn refuses_existing_envrc_without_force() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(CONFIG)).with_existing_envrc();
+
+        // Act
+        let refused = direnv_init(&m.deps(), "default", false);
+        let forced = direnv_init(&m.deps(), "default", true);
+
+        // Assert
+        assert!(matches!(refused, Err(ApplicationError::FileExists(path)) if path == "./.envrc"));
+        assert!(forced.is_ok());
+        assert!(m.scaffolder.envrc_written().is_some());
+    }
+
+    #[test]
+    fn rejects_unknown_shell() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(CONFIG));
+
+        // Act
+        let result = direnv_init(&m.deps(), "ci", false);
+
+        // Assert
+        assert!(matches!(result, Err(ApplicationError::ShellNotFound(s)) if s == "ci"));
+        assert!(m.scaffolder.envrc_written().is_none());
+    }
+}
@@ -0,0 +1,97 @@
+//! `lnix env` — print the dev environment for other tools to load.
+
+use lnix_domain::interface::output::OutputPort;
+use lnix_domain::{EnvFormat, ShellName, env_script, render_env};
+
+use crate::deps::Deps;
+use crate::error::ApplicationError;
+use crate::pipeline;
+
+/// Forwards warnings but drops progress messages, which would otherwise
+/// mix into the printed environment.
+struct WarningsOnly<'a>(&'a dyn OutputPort);
+
+impl OutputPort for WarningsOnly<'_> {
+    fn info(&self, _message: &str) {}
+
+    fn warn(&self, message: &str) {
+        self.0.warn(message);
+    }
+}
+
+/// Renders `flake.nix`, evaluates the named dev shell, applies its
+/// `env:` (dotenv files, then env vars), and prints the variables it
+/// exports as `format`. Nothing else is printed, so the output can be
+/// redirected straight to a file.
+pub fn env(d: &Deps, shell: &str, format: &str) -> Result<i32, ApplicationError> {
+    let shell: ShellName = shell.parse()?;
+    let format: EnvFormat = format.parse()?;
+    let quiet = Deps {
+        out: &WarningsOnly(d.out),
+        ..*d
+    };
+
+    let loaded = pipeline::load_config(&quiet)?;
+    let setup = env_script(loaded.shell(&shell)?);
+    pipeline::write_flake(&quiet, &loaded)?;
+
+    let vars = d.nix.dev_env_vars(&shell, &setup)?;
+    d.out.info(&render_env(format, &vars));
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks::*;
+
+    const CONFIG: &str = "devShells:\n  default:\n    package:\n      stable:\n        - name: bash\n    env:\n      dotenv:\n        - .env\n      envvar:\n        - name: MODE\n          value: dev\n  ci:\n    package:\n      stable: []\n";
+
+    #[test]
+    fn prints_only_the_environment() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(CONFIG));
+
+        // Act
+        let code = env(&m.deps(), "default", "sh").unwrap();
+
+        // Assert
+        assert_eq!(code, 0);
+        assert!(m.flake_writer.written().is_some());
+        assert_eq!(
+            m.nix.env_setups(),
+            vec!["set -a\n. \"$PWD/.env\"\nset +a\nexport MODE=dev"]
+        );
+        assert_eq!(
+            m.out.infos(),
+            vec!["export PATH=/nix/store/fake/bin\nexport SHELL_NAME=default"]
+        );
+    }
+
+    #[test]
+    fn evaluates_the_named_shell_as_json() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(CONFIG));
+
+        // Act
+        env(&m.deps(), "ci", "json").unwrap();
+
+        // Assert
+        assert_eq!(m.nix.env_setups(), vec![""]);
+        assert!(m.out.infos()[0].contains("\"SHELL_NAME\": \"ci\""));
+    }
+
+    #[test]
+    fn rejects_unknown_format_before_evaluating() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(CONFIG));
+
+        // Act
+        let result = env(&m.deps(), "default", "yaml");
+
+        // Assert
+        assert!(matches!(result, Err(ApplicationError::InvalidInput(_))));
+        assert!(m.flake_writer.written().is_none());
+        assert!(m.nix.env_setups().is_empty());
+    }
+}
@@ -4,7 +4,8 @@ use std::collections::HashSet;
 
 use lnix_domain::{
     NixError, PackageName, PackageValidationError, PinnedPackageEntry, ValidationResult,
-    classify_nix_eval_error, format_validation_result, format_validation_result_verbose,
+    check_envvar_commands, classify_nix_eval_error, format_validation_result,
+    format_validation_result_verbose,
 };
 
 use crate::deps::Deps;
@@ -26,15 +27,70 @@ struct PinnedVerification {
 /// never rewrites `lazynix.yaml`. Exit code 1 when any package fails.
 pub fn lint(d: &Deps, verbose: bool, arch: Option<&str>) -> Result<i32, ApplicationError> {
     let config = d.repo.read_config()?;
+    for diagnostic in check_envvar_commands(&config) {
+        d.out.warn(&diagnostic.to_string());
+    }
 
-    let package = &config.dev_shell.package;
-    let channel_names = package
-        .stable
-        .iter()
-        .chain(package.unstable.iter())
-        .map(|entry| entry.name.clone());
-    let pinned_names = package.pinned.iter().map(|entry| entry.name.clone());
-    let packages: Vec<PackageName> = channel_names.chain(pinned_names).collect();
+    // A package shared by several dev shells is evaluated once. With
+    // `--arch`, packages restricted to other platforms are skipped.
+    // Packages taken `from` a custom input are not in nixpkgs and are
+    // left to `nix develop` to check. Each `with` sub-package is
+    // evaluated as `<name>.pkgs.<sub>`.
+    let mut packages: Vec<PackageName> = Vec::new();
+    let mut pinned: Vec<&PinnedPackageEntry> = Vec::new();
+    let mut skipped: Vec<String> = Vec::new();
+    for dev_shell in config.dev_shells.values() {
+        let package = &dev_shell.package;
+        let channel_entries = package
+            .stable
+            .iter()
+            .chain(&package.unstable)
+            .filter(|entry| entry.from.is_none())
+            .flat_map(|entry| {
+                let enabled = arch.is_none_or(|a| entry.is_enabled_on(a));
+                std::iter::once(entry.name.clone())
+                    .chain(entry.sub_package_attrs())
+                    .map(move |name| (name, enabled))
+            });
+        let pinned_entries = package.pinned.iter().map(|entry| {
+            (
+                entry.name.clone(),
+                arch.is_none_or(|a| entry.is_enabled_on(a)),
+            )
+        });
+        for (name, enabled) in channel_entries.chain(pinned_entries) {
+            if !enabled {
+                skipped.push(name.to_string());
+            } else if !packages.contains(&name) {
+                packages.push(name);
+            }
+        }
+        let enabled_pinned = package
+            .pinned
+            .iter()
+            .filter(|entry| arch.is_none_or(|a| entry.is_enabled_on(a)));
+        for entry in enabled_pinned {
+            if !pinned
+                .iter()
+                .any(|seen| seen.name == entry.name && seen.version == entry.version)
+            {
+                pinned.push(entry);
+            }
+        }
+    }
+    // Enabled in another shell means it is still evaluated.
+    skipped.retain(|name| !packages.iter().any(|package| package.as_str() == name));
+    skipped.sort();
+    skipped.dedup();
+    if let Some(arch) = arch
+        && !skipped.is_empty()
+    {
+        d.out.info(&format!(
+            "Skipping packages not enabled on {}: {}",
+            arch,
+            skipped.join(", ")
+        ));
+    }
 
     if packages.is_empty() {
         d.out.info("No packages to validate.");
@@ -55,7 +111,7 @@ pub fn lint(d: &Deps, verbose: bool, arch: Option<&str>) -> Result<i32, Applicat
         }
     }
 
-    let verification = verify_pinned_versions(d, &package.pinned, &name_eval_failed)?;
+    let verification = verify_pinned_versions(d, &pinned, &name_eval_failed)?;
     valid_packages.retain(|valid| !verification.failed_names.contains(valid));
     errors.extend(verification.errors);
 
@@ -85,7 +141,7 @@ pub fn lint(d: &Deps, verbose: bool, arch: Option<&str>) -> Result<i32, Applicat
 /// short-circuit as `Err`.
 fn verify_pinned_versions(
     d: &Deps,
-    pinned: &[PinnedPackageEntry],
+    pinned: &[&PinnedPackageEntry],
     name_eval_failed: &HashSet<String>,
 ) -> Result<PinnedVerification, ApplicationError> {
     let mut failed_names = Vec::new();
@@ -149,6 +205,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn packages_from_custom_inputs_are_not_evaluated_against_nixpkgs() {
+        let m = Mocks::with_config(config_from_yaml(
+            "inputs:\n  - name: fenix\n    url: github:nix-community/fenix\ndevShell:\n  package:\n    stable:\n      - name: hello\n      - name: ghost-pkg\n        from: fenix\n",
+        ))
+        .with_failing_packages(&["ghost-pkg"]);
+
+        let code = lint(&m.deps(), false, None).unwrap();
+
+        assert_eq!(code, 0);
+    }
+
+    #[test]
+    fn with_sub_packages_are_evaluated_in_the_package_set() {
+        let m = Mocks::with_config(config_from_yaml(
+            "devShell:\n  package:\n    stable:\n      - name: python312\n        with: [requests, ghost-pkg]\n",
+        ))
+        .with_failing_packages(&["python312.pkgs.ghost-pkg"]);
+
+        let code = lint(&m.deps(), false, None).unwrap();
+
+        assert_eq!(code, 1);
+        let report = m.out.infos().join("\n");
+        assert!(report.contains("python312.pkgs.ghost-pkg"));
+        assert!(!report.contains("python312.pkgs.requests"));
+    }
+
+    #[test]
+    fn warns_about_envvar_commands_without_a_providing_package() {
+        let m = Mocks::with_config(config_from_yaml(
+            "devShell:\n  package:\n    stable:\n      - name: hello\n  env:\n    envvar:\n      - name: GIT_SHA\n        command: git rev-parse HEAD\n",
+        ));
+
+        let code = lint(&m.deps(), false, None).unwrap();
+
+        assert_eq!(code, 0);
+        assert_eq!(
+            m.out.warns(),
+            vec![
+                "Env var 'GIT_SHA' runs 'git', which no package of dev shell 'default' appears to provide"
+                    .to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn arch_skips_packages_restricted_to_other_platforms() {
+        let m = Mocks::with_config(config_from_yaml(
+            "devShell:\n  package:\n    stable:\n      - name: hello\n      - name: ghost-pkg\n        platforms: [x86_64-linux]\n",
+        ))
+        .with_failing_packages(&["ghost-pkg"]);
+
+        let code = lint(&m.deps(), false, Some("aarch64-darwin")).unwrap();
+
+        assert_eq!(code, 0);
+        assert!(
+            m.out.infos().contains(
+                &"Skipping packages not enabled on aarch64-darwin: ghost-pkg".to_string()
+            )
+        );
+    }
+
     #[test]
     fn failing_package_yields_exit_1_and_categorized_report() {
         let m = Mocks::with_config(config_from_yaml(
@@ -0,0 +1,232 @@
+//! `lnix lsp` — a language server for `lazynix.yaml`, run by the
+//! editor and spoken to over stdio.
+
+use std::collections::HashMap;
+
+use lnix_domain::interface::gateway::PackageInfo;
+use lnix_domain::interface::lsp::{ClientMessage, ServerMessage, Severity};
+use lnix_domain::{complete, diagnose, hover, pin_actions};
+
+use crate::deps::Deps;
+use crate::error::ApplicationError;
+
+/// Serves the editor until it exits: diagnostics for every open
+/// document, completion, hover, and the pin quick fix.
+///
+/// Documents are checked as the editor holds them, not as they are on
+/// disk. The package index is only built when first needed; if that
+/// fails, the editor's log says why and packages do not complete.
+/// stdout belongs to the protocol, so nothing goes to [`Deps::out`].
+/// Exit code 0 when the editor shut the server down before exiting,
+/// 1 when it exited or hung up without doing so.
+pub fn lsp(d: &Deps) -> Result<i32, ApplicationError> {
+    let mut documents: HashMap<String, String> = HashMap::new();
+    let mut packages: Option<Vec<PackageInfo>> = None;
+    let mut shut_down = false;
+
+    while let Some(message) = d.lsp.receive()? {
+        let reply = match message {
+            ClientMessage::Initialize { id } => ServerMessage::Initialized { id },
+            ClientMessage::DidOpen { uri, text } | ClientMessage::DidChange { uri, text } => {
+                let diagnostics = diagnose(&text, &d.repo.parse_config(&text));
+                documents.insert(uri.clone(), text);
+                ServerMessage::Diagnostics { uri, diagnostics }
+            }
+            ClientMessage::DidClose { uri } => {
+                documents.remove(&uri);
+                ServerMessage::Diagnostics {
+                    uri,
+                    diagnostics: Vec::new(),
+                }
+            }
+            ClientMessage::Completion { id, uri, position } => {
+                let text = documents.get(&uri).map_or("", String::as_str);
+                let list = complete(text, position, package_index(d, &mut packages)?);
+                ServerMessage::Completions { id, list }
+            }
+            ClientMessage::Hover { id, uri, position } => {
+                let text = documents.get(&uri).map_or("", String::as_str);
+                let hover = hover(text, position, package_index(d, &mut packages)?);
+                ServerMessage::Hover { id, hover }
+            }
+            ClientMessage::CodeAction { id, uri, range } => {
+                let text = documents.get(&uri).map_or("", String::as_str);
+                let actions = pin_actions(text, range, package_index(d, &mut packages)?);
+                ServerMessage::CodeActions { id, uri, actions }
+            }
+            ClientMessage::Shutdown { id } => {
+                shut_down = true;
+                ServerMessage::ShutdownAck { id }
+            }
+            ClientMessage::Exit => return Ok(if shut_down { 0 } else { 1 }),
+            ClientMessage::UnknownRequest { id, method } => {
+                ServerMessage::MethodNotFound { id, method }
+            }
+            ClientMessage::InvalidRequest { id, reason } => {
+                ServerMessage::InvalidParams { id, reason }
+            }
+            ClientMessage::Malformed { reason } => ServerMessage::ParseError { reason },
+            ClientMessage::Ignored => continue,
+        };
+        d.lsp.send(reply)?;
+    }
+    Ok(1)
+}
+
+/// The package index, built on first use. A failure is logged to the
+/// editor once and leaves the index empty.
+fn package_index<'a>(
+    d: &Deps,
+    packages: &'a mut Option<Vec<PackageInfo>>,
+) -> Result<&'a [PackageInfo], ApplicationError> {
+    if packages.is_none() {
+        let loaded = match d.packages.packages() {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                d.lsp.send(ServerMessage::Log {
+                    severity: Severity::Warning,
+                    message: format!("Package names will not complete: {e}"),
+                })?;
+                Vec::new()
+            }
+        };
+        *packages = Some(loaded);
+    }
+    Ok(packages.as_deref().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks::*;
+    use lnix_domain::interface::lsp::{RequestId, TextPosition};
+
+    const URI: &str = "file:///project/lazynix.yaml";
+
+    fn id(n: u32) -> RequestId {
+        RequestId::new(n.to_string())
+    }
+
+    fn open(text: &str) -> ClientMessage {
+        ClientMessage::DidOpen {
+            uri: URI.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    fn diagnostic_counts(sent: &[ServerMessage]) -> Vec<usize> {
+        sent.iter()
+            .filter_map(|message| match message {
+                ServerMessage::Diagnostics { diagnostics, .. } => Some(diagnostics.len()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn publishes_diagnostics_as_documents_change_and_close() {
+        // Arrange
+        let m = Mocks::with_missing_config().with_client_messages(vec![
+            ClientMessage::Initialize { id: id(1) },
+            ClientMessage::Ignored,
+            ClientMessage::Malformed {
+                reason: "EOF while parsing".to_string(),
+            },
+            open("devShell:\n  package:\n    stable:\n      - name: \"bad name\"\n"),
+            ClientMessage::DidChange {
+                uri: URI.to_string(),
+                text: "devShell:\n  package:\n    stable:\n      - name: jq\n".to_string(),
+            },
+            ClientMessage::DidClose {
+                uri: URI.to_string(),
+            },
+            ClientMessage::Shutdown { id: id(2) },
+            ClientMessage::Exit,
+        ]);
+
+        // Act
+        let code = lsp(&m.deps()).unwrap();
+
+        // Assert
+        assert_eq!(code, 0);
+        let sent = m.lsp.sent();
+        assert_eq!(sent[0], ServerMessage::Initialized { id: id(1) });
+        assert!(matches!(&sent[1], ServerMessage::ParseError { .. }));
+        assert_eq!(diagnostic_counts(&sent), vec![1, 0, 0]);
+        assert_eq!(sent.last(), Some(&ServerMessage::ShutdownAck { id: id(2) }));
+        assert!(m.out.infos().is_empty());
+    }
+
+    #[test]
+    fn builds_the_package_index_once_for_completion_and_hover() {
+        // Arrange
+        let text = "devShell:\n  package:\n    stable:\n      - name: jq\n";
+        let m = Mocks::with_missing_config()
+            .with_indexed_packages(&[("jq", "1.7.1", "JSON processor")])
+            .with_client_messages(vec![
+                open(text),
+                ClientMessage::Completion {
+                    id: id(1),
+                    uri: URI.to_string(),
+                    position: TextPosition::new(3, 15),
+                },
+                ClientMessage::Hover {
+                    id: id(2),
+                    uri: URI.to_string(),
+                    position: TextPosition::new(3, 15),
+                },
+            ]);
+
+        // Act
+        let code = lsp(&m.deps()).unwrap();
+
+        // Assert
+        assert_eq!(code, 1);
+        assert_eq!(m.packages.calls(), 1);
+        let sent = m.lsp.sent();
+        assert!(matches!(
+            &sent[1],
+            ServerMessage::Completions { list, .. } if list.items[0].label == "jq"
+        ));
+        assert!(matches!(
+            &sent[2],
+            ServerMessage::Hover { hover: Some(hover), .. } if hover.markdown.contains("JSON processor")
+        ));
+    }
+
+    #[test]
+    fn logs_an_unavailable_index_and_answers_without_it() {
+        // Arrange
+        let m = Mocks::with_missing_config()
+            .with_failing_package_index()
+            .with_client_messages(vec![
+                ClientMessage::Completion {
+                    id: id(1),
+                    uri: URI.to_string(),
+                    position: TextPosition::new(0, 0),
+                },
+                ClientMessage::UnknownRequest {
+                    id: id(2),
+                    method: "textDocument/formatting".to_string(),
+                },
+                ClientMessage::Exit,
+            ]);
+
+        // Act
+        let code = lsp(&m.deps()).unwrap();
+
+        // Assert
+        assert_eq!(code, 1);
+        let sent = m.lsp.sent();
+        assert!(matches!(
+            &sent[0],
+            ServerMessage::Log { severity: Severity::Warning, message }
+                if message.contains("nix search failed")
+        ));
+        assert!(matches!(
+            &sent[1],
+            ServerMessage::Completions { list, .. } if list.items.is_empty()
+        ));
+        assert!(matches!(&sent[2], ServerMessage::MethodNotFound { .. }));
+    }
+}
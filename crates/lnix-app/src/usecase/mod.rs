@@ -1,25 +1,36 @@
-//! Use-cases: one module per subcommand.
+//! Use-cases: one module per subcommand, except that the service
+//! subcommands (`up`, `down`, `ps`, `logs`) share one.
 //!
 //! Every use-case receives [`crate::Deps`] and returns
 //! `Result<i32, crate::ApplicationError>` — the exit code on the happy
 //! rail, a categorized failure otherwise.
 
 mod develop;
+mod direnv;
+mod env;
 mod generate;
 mod init;
 mod lint;
+mod lsp;
 mod run;
+mod schema;
 mod search;
+mod service;
 mod task;
 mod test;
 mod update;
 
 pub use develop::develop;
+pub use direnv::direnv_init;
+pub use env::env;
 pub use generate::generate;
 pub use init::init;
 pub use lint::lint;
+pub use lsp::lsp;
 pub use run::run;
+pub use schema::schema;
 pub use search::search;
-pub use task::task;
-pub use test::test;
+pub use service::{down, logs, ps, up};
+pub use task::{TaskOptions, task};
+pub use test::{TestOptions, test};
 pub use update::update;
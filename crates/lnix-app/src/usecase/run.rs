@@ -1,13 +1,17 @@
 //! `lnix run` — run a command inside the dev environment.
 
+use lnix_domain::ShellName;
+
 use crate::deps::Deps;
 use crate::error::ApplicationError;
 use crate::pipeline;
 
 /// Renders `flake.nix` (unless the caller opted out) and runs
-/// `cmd_args` in `nix develop`. Returns the command's exit code.
+/// `cmd_args` in `nix develop` for the named dev shell. Returns the
+/// command's exit code.
 pub fn run(
     d: &Deps,
+    shell: &str,
     update_lock: bool,
     regenerate: bool,
     cmd_args: Vec<String>,
@@ -15,9 +19,11 @@ pub fn run(
     if cmd_args.is_empty() {
         return Err(ApplicationError::EmptyRunCommand);
     }
+    let shell: ShellName = shell.parse()?;
 
     if regenerate {
         let loaded = pipeline::load_config(d)?;
+        loaded.shell(&shell)?;
         pipeline::write_flake(d, &loaded)?;
     } else {
         d.out
@@ -29,7 +35,7 @@ pub fn run(
     d.out.info("");
     d.out
         .info(&format!("Running command: {}", cmd_args.join(" ")));
-    Ok(d.nix.develop_command(&cmd_args)?)
+    Ok(d.nix.develop_command(&shell, &cmd_args)?)
 }
 
 #[cfg(test)]
@@ -43,7 +49,14 @@ mod tests {
             "devShell:\n  package:\n    stable:\n      - name: bash\n",
         ));
 
-        let code = run(&m.deps(), false, true, vec!["echo".into(), "hi".into()]).unwrap();
+        let code = run(
+            &m.deps(),
+            "default",
+            false,
+            true,
+            vec!["echo".into(), "hi".into()],
+        )
+        .unwrap();
 
         assert_eq!(code, 0);
         assert!(m.flake_writer.written().is_some());
@@ -57,7 +70,7 @@ mod tests {
     fn no_regen_skips_config_loading_entirely() {
         let m = Mocks::with_missing_config();
 
-        let code = run(&m.deps(), false, false, vec!["true".into()]).unwrap();
+        let code = run(&m.deps(), "default", false, false, vec!["true".into()]).unwrap();
 
         assert_eq!(code, 0);
         assert!(m.flake_writer.written().is_none());
@@ -72,7 +85,7 @@ mod tests {
     fn rejects_empty_command() {
         let m = Mocks::with_missing_config();
 
-        let result = run(&m.deps(), false, true, vec![]);
+        let result = run(&m.deps(), "default", false, true, vec![]);
 
         assert!(matches!(result, Err(ApplicationError::EmptyRunCommand)));
     }
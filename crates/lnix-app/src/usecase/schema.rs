@@ -0,0 +1,41 @@
+//! `lnix schema` — print the JSON Schema of `lazynix.yaml` or
+//! `lazynix-settings.yaml` for editors to validate against.
+
+use lnix_domain::{SchemaTarget, render_schema};
+
+use crate::deps::Deps;
+use crate::error::ApplicationError;
+
+/// Prints the schema of `lazynix-settings.yaml` when `settings` is set,
+/// and of `lazynix.yaml` otherwise. Needs no config.
+pub fn schema(d: &Deps, settings: bool) -> Result<i32, ApplicationError> {
+    let target = if settings {
+        SchemaTarget::Settings
+    } else {
+        SchemaTarget::Config
+    };
+    d.out.info(&render_schema(target));
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks::*;
+
+    #[test]
+    fn prints_the_requested_schema_without_reading_the_config() {
+        // Arrange
+        let m = Mocks::with_missing_config();
+
+        // Act
+        schema(&m.deps(), false).unwrap();
+        schema(&m.deps(), true).unwrap();
+
+        // Assert
+        let infos = m.out.infos();
+        assert_eq!(infos.len(), 2);
+        assert!(infos[0].contains(r#""title": "lazynix.yaml""#));
+        assert!(infos[1].contains(r#""title": "lazynix-settings.yaml""#));
+    }
+}
@@ -0,0 +1,315 @@
+//! `lnix up`, `down`, `ps`, and `logs` — background services declared
+//! under `services:`.
+
+use lnix_domain::interface::gateway::{Readiness, ServiceStatus};
+use lnix_domain::{
+    DevShell, DevShellDefinition, ReadyCheck, ServiceDef, ShellName, format_service_table,
+    service_script,
+};
+
+use crate::deps::Deps;
+use crate::error::ApplicationError;
+use crate::pipeline;
+
+/// Renders `flake.nix`, evaluates the named dev shell once, and starts
+/// its services (those named in `names`, or all) in declaration order.
+/// Each one must pass its readiness probe before the next starts;
+/// services that are already running are left alone.
+pub fn up(d: &Deps, shell: &str, names: &[String]) -> Result<i32, ApplicationError> {
+    let shell: ShellName = shell.parse()?;
+    let loaded = pipeline::load_config(d)?;
+    let services = select_services(&loaded.shell(&shell)?.services, names)?;
+    pipeline::write_flake(d, &loaded)?;
+
+    let mut pending = Vec::new();
+    for service in services {
+        match d.services.status(&shell, &service.name)? {
+            ServiceStatus::Running { pid } => d.out.info(&format!(
+                "✓ {} is already running (pid {})",
+                service.name, pid
+            )),
+            _ => pending.push(service),
+        }
+    }
+    if pending.is_empty() {
+        return Ok(0);
+    }
+
+    let env = d.nix.dev_env(&shell)?;
+    for service in pending {
+        d.out.info(&format!("Starting {}...", service.name));
+        let pid = d.services.start(
+            &env,
+            &shell,
+            &service.name,
+            &service_script(service, &service.command),
+        )?;
+        let Some(probe) = &service.ready else {
+            d.out
+                .info(&format!("✓ {} started (pid {})", service.name, pid));
+            continue;
+        };
+        let check = match &probe.check {
+            ReadyCheck::Command(command) => ReadyCheck::Command(service_script(service, command)),
+            check => check.clone(),
+        };
+        match d
+            .services
+            .wait_ready(&env, &shell, &service.name, &check, probe.time_limit())?
+        {
+            Readiness::Ready => d
+                .out
+                .info(&format!("✓ {} is ready (pid {})", service.name, pid)),
+            Readiness::Exited => {
+                return Err(ApplicationError::ServiceExited(service.name.to_string()));
+            }
+            Readiness::TimedOut => {
+                return Err(ApplicationError::ServiceNotReady {
+                    service: service.name.to_string(),
+                    seconds: probe.time_limit().as_secs(),
+                });
+            }
+        }
+    }
+    Ok(0)
+}
+
+/// Stops the named dev shell's services (those named in `names`, or
+/// all) in reverse declaration order, so that a service outlives the
+/// ones started after it.
+pub fn down(d: &Deps, shell: &str, names: &[String]) -> Result<i32, ApplicationError> {
+    let shell: ShellName = shell.parse()?;
+    let config = d.repo.read_config()?;
+    let declared = &shell_of(&config, &shell)?.services;
+    for service in select_services(declared, names)?.into_iter().rev() {
+        if d.services.stop(&shell, &service.name)? {
+            d.out.info(&format!("✓ Stopped {}", service.name));
+        } else {
+            d.out.info(&format!("{} is not running", service.name));
+        }
+    }
+    Ok(0)
+}
+
+/// Lists the named dev shell's services with their status.
+pub fn ps(d: &Deps, shell: &str) -> Result<i32, ApplicationError> {
+    let shell: ShellName = shell.parse()?;
+    let config = d.repo.read_config()?;
+    let mut statuses = Vec::new();
+    for service in select_services(&shell_of(&config, &shell)?.services, &[])? {
+        statuses.push((
+            service.name.clone(),
+            d.services.status(&shell, &service.name)?,
+        ));
+    }
+    for line in format_service_table(&statuses) {
+        d.out.info(&line);
+    }
+    Ok(0)
+}
+
+/// Prints what a service of the named dev shell has logged since it
+/// was last started; with `follow`, keeps printing until interrupted.
+pub fn logs(d: &Deps, shell: &str, name: &str, follow: bool) -> Result<i32, ApplicationError> {
+    let shell: ShellName = shell.parse()?;
+    let config = d.repo.read_config()?;
+    let declared = &shell_of(&config, &shell)?.services;
+    let service = &select_services(declared, &[name.to_string()])?[0].name;
+    if follow {
+        d.services.follow_logs(&shell, service)?;
+        return Ok(0);
+    }
+    match d.services.read_logs(&shell, service)? {
+        Some(logs) => {
+            let logs = logs.strip_suffix('\n').unwrap_or(&logs);
+            if !logs.is_empty() {
+                d.out.info(logs);
+            }
+        }
+        None => d.out.info(&format!("{} has not been started yet", service)),
+    }
+    Ok(0)
+}
+
+/// [`crate::pipeline::LoadedConfig::shell`] for commands that only read
+/// the config.
+fn shell_of<'a>(
+    config: &'a DevShellDefinition,
+    shell: &ShellName,
+) -> Result<&'a DevShell, ApplicationError> {
+    config
+        .shell(shell)
+        .ok_or_else(|| ApplicationError::ShellNotFound(shell.to_string()))
+}
+
+/// The services named in `names`, or all of them, in declaration order.
+fn select_services<'a>(
+    declared: &'a [ServiceDef],
+    names: &[String],
+) -> Result<Vec<&'a ServiceDef>, ApplicationError> {
+    if declared.is_empty() {
+        return Err(ApplicationError::NoServices);
+    }
+    if let Some(unknown) = names
+        .iter()
+        .find(|name| !declared.iter().any(|s| s.name.as_str() == name.as_str()))
+    {
+        return Err(ApplicationError::ServiceNotFound(unknown.clone()));
+    }
+    Ok(declared
+        .iter()
+        .filter(|s| names.is_empty() || names.iter().any(|name| name == s.name.as_str()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks::*;
+
+    const SERVICES: &str = "devShell:\n  package:\n    stable:\n      - name: postgresql\n  services:\n    - name: db\n      command: postgres\n      ready:\n        port: 5432\n    - name: worker\n      command: ./worker\n      dir: worker\n      ready:\n        command: ./healthcheck\n    - name: mail\n      command: mailpit\n";
+
+    #[test]
+    fn up_starts_services_in_order_and_waits_for_each() {
+        let m = Mocks::with_config(config_from_yaml(SERVICES));
+
+        let code = up(&m.deps(), "default", &[]).unwrap();
+
+        assert_eq!(code, 0);
+        assert!(m.flake_writer.written().is_some());
+        assert_eq!(m.nix.dev_env_calls(), 1);
+        assert_eq!(
+            m.services.started(),
+            vec![
+                ("default/db".to_string(), "postgres".to_string()),
+                (
+                    "default/worker".to_string(),
+                    "cd worker || exit\n./worker".to_string()
+                ),
+                ("default/mail".to_string(), "mailpit".to_string()),
+            ]
+        );
+        assert_eq!(
+            m.services.probed(),
+            vec![
+                "default/db: port 5432",
+                "default/worker: cd worker || exit\n./healthcheck"
+            ]
+        );
+        let infos = m.out.infos();
+        assert!(infos.contains(&"✓ db is ready (pid 1001)".to_string()));
+        assert!(infos.contains(&"✓ mail started (pid 1003)".to_string()));
+    }
+
+    #[test]
+    fn up_leaves_running_services_alone() {
+        let m = Mocks::with_config(config_from_yaml(SERVICES))
+            .with_running_services(&[("default/db", 42), ("default/mail", 43)]);
+
+        up(&m.deps(), "default", &[]).unwrap();
+        let names = vec!["db".to_string()];
+        up(&m.deps(), "default", &names).unwrap();
+
+        assert_eq!(m.services.started().len(), 1);
+        assert_eq!(m.services.started()[0].0, "default/worker");
+        assert_eq!(m.nix.dev_env_calls(), 1);
+        assert!(
+            m.out
+                .infos()
+                .contains(&"✓ db is already running (pid 42)".to_string())
+        );
+    }
+
+    #[test]
+    fn up_fails_when_a_service_exits_while_starting() {
+        let m = Mocks::with_config(config_from_yaml(SERVICES)).with_exiting_service("default/db");
+
+        let result = up(&m.deps(), "default", &[]);
+
+        assert!(matches!(result, Err(ApplicationError::ServiceExited(s)) if s == "db"));
+        assert_eq!(m.services.started().len(), 1);
+    }
+
+    #[test]
+    fn rejects_unknown_services_and_shells_without_services() {
+        let m = Mocks::with_config(config_from_yaml(SERVICES));
+        let names = vec!["db".to_string(), "cache".to_string()];
+
+        let result = up(&m.deps(), "default", &names);
+
+        assert!(matches!(result, Err(ApplicationError::ServiceNotFound(s)) if s == "cache"));
+        assert!(m.flake_writer.written().is_none());
+
+        let m = Mocks::with_config(config_from_yaml(
+            "devShell:\n  package:\n    stable:\n      - name: bash\n",
+        ));
+        assert!(matches!(
+            ps(&m.deps(), "default"),
+            Err(ApplicationError::NoServices)
+        ));
+    }
+
+    #[test]
+    fn down_stops_services_in_reverse_order() {
+        let m = Mocks::with_config(config_from_yaml(SERVICES))
+            .with_running_services(&[("default/db", 42), ("default/mail", 43)]);
+
+        let code = down(&m.deps(), "default", &[]).unwrap();
+
+        assert_eq!(code, 0);
+        assert_eq!(m.services.stopped(), vec!["default/mail", "default/db"]);
+        assert!(m.out.infos().contains(&"worker is not running".to_string()));
+        assert_eq!(m.nix.dev_env_calls(), 0);
+    }
+
+    #[test]
+    fn ps_lists_every_service_with_its_status() {
+        let m = Mocks::with_config(config_from_yaml(SERVICES))
+            .with_running_services(&[("default/worker", 42)]);
+
+        ps(&m.deps(), "default").unwrap();
+
+        assert_eq!(
+            m.out.infos(),
+            vec![
+                "db      stopped",
+                "worker  running (pid 42)",
+                "mail    stopped"
+            ]
+        );
+    }
+
+    #[test]
+    fn logs_prints_or_follows_the_service_log() {
+        let m = Mocks::with_config(config_from_yaml(SERVICES));
+
+        logs(&m.deps(), "default", "db", false).unwrap();
+        up(&m.deps(), "default", &["db".to_string()]).unwrap();
+        logs(&m.deps(), "default", "db", false).unwrap();
+        logs(&m.deps(), "default", "db", true).unwrap();
+
+        let infos = m.out.infos();
+        assert!(infos.contains(&"db has not been started yet".to_string()));
+        assert_eq!(infos.last().unwrap(), "log of postgres");
+        assert_eq!(m.services.followed(), vec!["default/db"]);
+    }
+
+    #[test]
+    fn services_of_other_shells_with_the_same_name_are_left_alone() {
+        let m = Mocks::with_config(config_from_yaml(
+            "devShells:\n  default:\n    package:\n      stable:\n        - name: bash\n    services:\n      - name: db\n        command: postgres\n  ci:\n    package:\n      stable:\n        - name: bash\n    services:\n      - name: db\n        command: postgres -p 5433\n",
+        ))
+        .with_running_services(&[("default/db", 42)]);
+
+        up(&m.deps(), "ci", &[]).unwrap();
+        down(&m.deps(), "ci", &[]).unwrap();
+
+        assert_eq!(
+            m.services.started(),
+            vec![("ci/db".to_string(), "postgres -p 5433".to_string())]
+        );
+        assert_eq!(m.services.stopped(), vec!["ci/db"]);
+        ps(&m.deps(), "default").unwrap();
+        assert!(m.out.infos().contains(&"db  running (pid 42)".to_string()));
+    }
+}
@@ -1,44 +1,151 @@
 //! `lnix task` — run a named task defined in `lazynix.yaml`.
 
-use lnix_domain::{TaskName, interpolate_command, validate_config};
+use lnix_domain::{
+    ConfigError, ShellName, TaskDef, TaskName, format_task_usage, interpolate_args,
+    parallel_script, parse_task_args, resolve_task_order, task_prelude, validate_config,
+};
 
 use crate::deps::Deps;
 use crate::error::ApplicationError;
 
-/// Looks up `task_name`, interpolates `args` into its commands, and
-/// runs them sequentially in `nix develop`. Returns the task's exit
-/// code.
+/// How `lnix task` runs, beyond which task and arguments.
+#[derive(Debug, Clone, Default)]
+pub struct TaskOptions {
+    /// Print the planned order instead of running anything.
+    pub dry_run: bool,
+    /// Print the task's usage instead of running it.
+    pub help: bool,
+    /// Run tasks with `sources` even when they are up to date.
+    pub force: bool,
+}
+
+/// Looks up `task_name` in the named dev shell and runs it after its
+/// `deps` (each task once, dependencies first), stopping at the first
+/// task that fails. `args` are parsed against the requested task's
+/// `args:` declaration (see [`parse_task_args`]); dependencies only get
+/// their defaults. Each task runs in its own `dir` and `env` (see
+/// [`task_prelude`]); a `parallel` task runs its commands concurrently
+/// (see [`parallel_script`]). A task with `sources` is skipped while it
+/// is up to date (see [`is_up_to_date`]) unless `options.force` is
+/// set. With `dry_run`, prints the planned order instead; with `help`,
+/// the task's usage. Returns the exit code of the last task run.
 ///
 /// Unlike the flake-generating commands, tasks read the config as-is:
 /// no pinned resolution, no dotenv check, no flake regeneration.
-pub fn task(d: &Deps, task_name: &str, args: &[String]) -> Result<i32, ApplicationError> {
+pub fn task(
+    d: &Deps,
+    shell: &str,
+    task_name: &str,
+    args: &[String],
+    options: &TaskOptions,
+) -> Result<i32, ApplicationError> {
+    let shell: ShellName = shell.parse()?;
     let task_name: TaskName = task_name.parse()?;
 
     d.out.info("Reading configuration...");
     let config = d.repo.read_config()?;
 
     d.out.info("Validating configuration...");
-    for diagnostic in validate_config(&config).map_err(lnix_domain::ConfigError::from)? {
+    for diagnostic in validate_config(&config).map_err(ConfigError::from)? {
         d.out.warn(&diagnostic.to_string());
     }
 
-    let tasks = config
-        .dev_shell
+    let dev_shell = config
+        .shell(&shell)
+        .ok_or_else(|| ApplicationError::ShellNotFound(shell.to_string()))?;
+    let tasks = dev_shell
         .task
+        .as_ref()
         .ok_or(ApplicationError::NoTasksDefined)?;
-    let task_def = tasks
-        .get(&task_name)
-        .ok_or_else(|| ApplicationError::TaskNotFound(task_name.to_string()))?;
+    let Some(target) = tasks.get(&task_name) else {
+        return Err(ApplicationError::TaskNotFound(task_name.to_string()));
+    };
+    if options.help {
+        d.out.info(&format_task_usage(&task_name, target));
+        return Ok(0);
+    }
+    let order = resolve_task_order(tasks, &task_name).map_err(ConfigError::from)?;
 
-    let commands = interpolate_command(&task_def.commands, args);
+    // Arguments are checked for every task before the first one runs.
+    let mut scripts = Vec::new();
+    for name in &order {
+        let task_def = &tasks[name];
+        let cli: &[String] = if *name == task_name { args } else { &[] };
+        let parsed = parse_task_args(name, task_def, cli)?;
+        let task_commands = interpolate_args(&task_def.commands, &parsed);
+        let mut commands = task_prelude(task_def);
+        if task_def.parallel {
+            commands.push(parallel_script(&task_commands, task_def.on_failure));
+        } else {
+            commands.extend(task_commands);
+        }
+        scripts.push((name, task_def, commands));
+    }
 
-    d.out.info(&format!("Running task: {}", task_name));
-    if let Some(description) = &task_def.description {
-        d.out.info(&format!("Description: {}", description));
+    if options.dry_run {
+        d.out
+            .info(&format!("Execution order for task {}:", task_name));
+        for (position, (name, task_def, commands)) in scripts.iter().enumerate() {
+            let mut line = format!("  {}. {}", position + 1, name);
+            if task_def.parallel {
+                line.push_str(" (parallel)");
+            }
+            if !options.force && is_up_to_date(d, &shell, name, task_def, commands)? {
+                line.push_str(" (up to date)");
+            }
+            d.out.info(&line);
+        }
+        return Ok(0);
     }
-    d.out.info("");
 
-    Ok(d.nix.run_task(&commands)?)
+    for (name, task_def, commands) in scripts {
+        // Checked only now, as the tasks before this one may have
+        // written its sources.
+        if !options.force && is_up_to_date(d, &shell, name, task_def, &commands)? {
+            d.out.info(&format!("Task {} is up to date", name));
+            continue;
+        }
+        d.out.info(&format!("Running task: {}", name));
+        if let Some(description) = &task_def.description {
+            d.out.info(&format!("Description: {}", description));
+        }
+        d.out.info("");
+
+        let code = d.nix.run_task(&shell, &commands)?;
+        if code != 0 {
+            return Ok(code);
+        }
+        // Fingerprinted after the run, so a task that rewrites its own
+        // sources (a formatter) is up to date next time.
+        if !task_def.sources.is_empty() {
+            let fingerprint =
+                d.task_state
+                    .fingerprint(task_def.dir.as_deref(), &task_def.sources, &commands)?;
+            d.task_state.record(&shell, name, &fingerprint)?;
+        }
+    }
+    Ok(0)
+}
+
+/// A task is up to date when it declares `sources`, every `generates`
+/// glob matches a file, and the fingerprint of its sources and
+/// `commands` equals the one recorded after its last successful run.
+fn is_up_to_date(
+    d: &Deps,
+    shell: &ShellName,
+    name: &TaskName,
+    task_def: &TaskDef,
+    commands: &[String],
+) -> Result<bool, ApplicationError> {
+    if task_def.sources.is_empty() {
+        return Ok(false);
+    }
+    let dir = task_def.dir.as_deref();
+    if !d.task_state.outputs_exist(dir, &task_def.generates)? {
+        return Ok(false);
+    }
+    let fingerprint = d.task_state.fingerprint(dir, &task_def.sources, commands)?;
+    Ok(d.task_state.recorded(shell, name)?.as_deref() == Some(fingerprint.as_str()))
 }
 
 #[cfg(test)]
@@ -54,7 +161,14 @@ mod tests {
         let m = Mocks::with_config(config_from_yaml(TASK_CONFIG));
 
         // Act
-        let code = task(&m.deps(), "greet", &["world".to_string()]).unwrap();
+        let code = task(
+            &m.deps(),
+            "default",
+            "greet",
+            &["world".to_string()],
+            &TaskOptions::default(),
+        )
+        .unwrap();
 
         // Assert
         assert_eq!(code, 0);
@@ -73,7 +187,7 @@ mod tests {
         ));
 
         // Act
-        let result = task(&m.deps(), "greet", &[]);
+        let result = task(&m.deps(), "default", "greet", &[], &TaskOptions::default());
 
         // Assert
         assert!(matches!(result, Err(ApplicationError::NoTasksDefined)));
@@ -85,7 +199,13 @@ mod tests {
         let m = Mocks::with_config(config_from_yaml(TASK_CONFIG));
 
         // Act
-        let result = task(&m.deps(), "nonexistent", &[]);
+        let result = task(
+            &m.deps(),
+            "default",
+            "nonexistent",
+            &[],
+            &TaskOptions::default(),
+        );
 
         // Assert
         assert!(
@@ -99,9 +219,424 @@ mod tests {
         let m = Mocks::with_missing_config();
 
         // Act
-        let result = task(&m.deps(), "bad@name", &[]);
+        let result = task(
+            &m.deps(),
+            "default",
+            "bad@name",
+            &[],
+            &TaskOptions::default(),
+        );
 
         // Assert
         assert!(matches!(result, Err(ApplicationError::InvalidInput(_))));
     }
+
+    #[test]
+    fn errors_when_the_shell_is_not_found() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(TASK_CONFIG));
+
+        // Act
+        let result = task(&m.deps(), "ci", "greet", &[], &TaskOptions::default());
+
+        // Assert
+        assert!(matches!(result, Err(ApplicationError::ShellNotFound(name)) if name == "ci"));
+        assert!(m.nix.run_task_commands().is_none());
+    }
+
+    const DEPS_CONFIG: &str = "devShell:\n  package:\n    stable:\n      - name: bash\n  task:\n    codegen:\n      commands:\n        - make gen\n    build:\n      commands:\n        - make {{.CLI_ARGS}}\n      deps: [codegen]\n    test:\n      commands:\n        - make test {{.CLI_ARGS}}\n      deps: [codegen, build]\n";
+
+    #[test]
+    fn runs_dependencies_once_before_the_task() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(DEPS_CONFIG));
+
+        // Act
+        let code = task(
+            &m.deps(),
+            "default",
+            "test",
+            &["-j4".to_string()],
+            &TaskOptions::default(),
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(code, 0);
+        assert_eq!(
+            m.nix.run_task_calls(),
+            vec![
+                vec!["make gen".to_string()],
+                vec!["make ".to_string()],
+                vec!["make test -j4".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_at_the_first_failing_dependency() {
+        // Arrange
+        let m =
+            Mocks::with_config(config_from_yaml(DEPS_CONFIG)).with_failing_task_command("make gen");
+
+        // Act
+        let code = task(&m.deps(), "default", "test", &[], &TaskOptions::default()).unwrap();
+
+        // Assert
+        assert_eq!(code, 1);
+        assert_eq!(m.nix.run_task_calls().len(), 1);
+    }
+
+    #[test]
+    fn dry_run_prints_the_order_without_running() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(DEPS_CONFIG));
+
+        // Act
+        let code = task(
+            &m.deps(),
+            "default",
+            "test",
+            &[],
+            &TaskOptions {
+                dry_run: true,
+                ..TaskOptions::default()
+            },
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(code, 0);
+        assert!(m.nix.run_task_calls().is_empty());
+        let infos = m.out.infos();
+        let plan_start = infos
+            .iter()
+            .position(|line| line == "Execution order for task test:")
+            .unwrap();
+        assert_eq!(
+            infos[plan_start + 1..],
+            ["  1. codegen", "  2. build", "  3. test"]
+        );
+    }
+
+    #[test]
+    fn runs_the_task_in_its_own_dir_and_env() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(
+            "devShell:\n  package:\n    stable:\n      - name: nodejs\n  task:\n    web:\n      dir: frontend\n      env:\n        envvar:\n          - name: NODE_ENV\n            value: test\n      commands:\n        - npm test\n",
+        ));
+
+        // Act
+        let code = task(&m.deps(), "default", "web", &[], &TaskOptions::default()).unwrap();
+
+        // Assert
+        assert_eq!(code, 0);
+        assert_eq!(
+            m.nix.run_task_commands().unwrap(),
+            vec!["cd frontend", "export NODE_ENV=test", "npm test"]
+        );
+    }
+
+    const ARGS_CONFIG: &str = "devShell:\n  package:\n    stable:\n      - name: bash\n  task:\n    deploy:\n      commands:\n        - ./deploy {{.ARGS.target}} --env {{.ARGS.env}}\n      args:\n        - name: target\n          required: true\n        - name: env\n          default: staging\n";
+
+    #[test]
+    fn interpolates_declared_arguments_shell_quoted() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(ARGS_CONFIG));
+
+        // Act
+        let code = task(
+            &m.deps(),
+            "default",
+            "deploy",
+            &[
+                "--env".to_string(),
+                "prod eu".to_string(),
+                "web-1".to_string(),
+            ],
+            &TaskOptions::default(),
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(code, 0);
+        assert_eq!(
+            m.nix.run_task_commands().unwrap(),
+            vec!["./deploy web-1 --env 'prod eu'"]
+        );
+    }
+
+    #[test]
+    fn missing_required_argument_fails_before_running() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(ARGS_CONFIG));
+
+        // Act
+        let result = task(&m.deps(), "default", "deploy", &[], &TaskOptions::default());
+
+        // Assert
+        assert!(matches!(result, Err(ApplicationError::TaskArgs(_))));
+        assert!(m.nix.run_task_calls().is_empty());
+    }
+
+    #[test]
+    fn help_prints_the_task_usage() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(ARGS_CONFIG));
+
+        // Act
+        let code = task(
+            &m.deps(),
+            "default",
+            "deploy",
+            &[],
+            &TaskOptions {
+                help: true,
+                ..TaskOptions::default()
+            },
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(code, 0);
+        assert!(
+            m.out
+                .infos()
+                .iter()
+                .any(|line| line.starts_with("Usage: lnix task deploy <target> [env]"))
+        );
+        assert!(m.nix.run_task_calls().is_empty());
+    }
+
+    const PARALLEL_CONFIG: &str = "devShell:\n  package:\n    stable:\n      - name: ruff\n  task:\n    codegen:\n      commands:\n        - make gen\n    lint-all:\n      parallel: true\n      deps: [codegen]\n      commands:\n        - ruff check .\n        - cargo clippy\n";
+
+    #[test]
+    fn parallel_task_runs_its_commands_as_one_script() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(PARALLEL_CONFIG));
+
+        // Act
+        let code = task(
+            &m.deps(),
+            "default",
+            "lint-all",
+            &[],
+            &TaskOptions::default(),
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(code, 0);
+        let commands = m.nix.run_task_commands().unwrap();
+        assert_eq!(commands.len(), 1);
+        assert!(commands[0].contains("ruff check ."));
+        assert!(commands[0].contains("cargo clippy"));
+        assert_eq!(m.nix.run_task_calls()[0], vec!["make gen"]);
+    }
+
+    #[test]
+    fn dry_run_marks_parallel_tasks() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(PARALLEL_CONFIG));
+
+        // Act
+        task(
+            &m.deps(),
+            "default",
+            "lint-all",
+            &[],
+            &TaskOptions {
+                dry_run: true,
+                ..TaskOptions::default()
+            },
+        )
+        .unwrap();
+
+        // Assert
+        let infos = m.out.infos();
+        assert!(infos.contains(&"  1. codegen".to_string()));
+        assert!(infos.contains(&"  2. lint-all (parallel)".to_string()));
+    }
+
+    const SOURCES_CONFIG: &str = "devShell:\n  package:\n    stable:\n      - name: protobuf\n  task:\n    codegen:\n      sources: [\"proto/*.proto\"]\n      generates: [\"gen/*.go\"]\n      commands:\n        - protoc --go_out=gen proto/*.proto\n";
+
+    #[test]
+    fn skips_a_task_whose_sources_are_unchanged() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(SOURCES_CONFIG));
+        task(
+            &m.deps(),
+            "default",
+            "codegen",
+            &[],
+            &TaskOptions::default(),
+        )
+        .unwrap();
+
+        // Act
+        let code = task(
+            &m.deps(),
+            "default",
+            "codegen",
+            &[],
+            &TaskOptions::default(),
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(code, 0);
+        assert_eq!(m.nix.run_task_calls().len(), 1);
+        assert_eq!(m.task_state.recorded_tasks(), vec!["default/codegen"]);
+        assert!(
+            m.out
+                .infos()
+                .contains(&"Task codegen is up to date".to_string())
+        );
+    }
+
+    #[test]
+    fn reruns_when_sources_change_or_outputs_are_missing() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(SOURCES_CONFIG));
+        task(
+            &m.deps(),
+            "default",
+            "codegen",
+            &[],
+            &TaskOptions::default(),
+        )
+        .unwrap();
+
+        // Act
+        m.task_state.touch_sources();
+        task(
+            &m.deps(),
+            "default",
+            "codegen",
+            &[],
+            &TaskOptions::default(),
+        )
+        .unwrap();
+        let m = m.with_missing_task_outputs();
+        task(
+            &m.deps(),
+            "default",
+            "codegen",
+            &[],
+            &TaskOptions::default(),
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(m.nix.run_task_calls().len(), 3);
+    }
+
+    #[test]
+    fn force_runs_an_up_to_date_task() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(SOURCES_CONFIG));
+        task(
+            &m.deps(),
+            "default",
+            "codegen",
+            &[],
+            &TaskOptions::default(),
+        )
+        .unwrap();
+
+        // Act
+        task(
+            &m.deps(),
+            "default",
+            "codegen",
+            &[],
+            &TaskOptions {
+                force: true,
+                ..TaskOptions::default()
+            },
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(m.nix.run_task_calls().len(), 2);
+    }
+
+    #[test]
+    fn failed_run_is_not_recorded() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(SOURCES_CONFIG))
+            .with_failing_task_command("protoc --go_out=gen proto/*.proto");
+
+        // Act
+        let code = task(
+            &m.deps(),
+            "default",
+            "codegen",
+            &[],
+            &TaskOptions::default(),
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(code, 1);
+        assert!(m.task_state.recorded_tasks().is_empty());
+    }
+
+    #[test]
+    fn reruns_a_task_whose_sources_a_dependency_just_wrote() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(
+            "devShell:\n  package:\n    stable:\n      - name: protobuf\n  task:\n    codegen:\n      commands:\n        - make gen\n    build:\n      sources: [\"gen/*.go\"]\n      commands:\n        - make build\n      deps: [codegen]\n",
+        ))
+        .with_sources_written_by("make gen");
+        task(&m.deps(), "default", "build", &[], &TaskOptions::default()).unwrap();
+
+        // Act
+        task(&m.deps(), "default", "build", &[], &TaskOptions::default()).unwrap();
+
+        // Assert
+        assert_eq!(
+            m.nix.run_task_calls(),
+            vec![
+                vec!["make gen".to_string()],
+                vec!["make build".to_string()],
+                vec!["make gen".to_string()],
+                vec!["make build".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn dry_run_marks_up_to_date_tasks() {
+        // Arrange
+        let m = Mocks::with_config(config_from_yaml(SOURCES_CONFIG));
+        task(
+            &m.deps(),
+            "default",
+            "codegen",
+            &[],
+            &TaskOptions::default(),
+        )
+        .unwrap();
+
+        // Act
+        task(
+            &m.deps(),
+            "default",
+            "codegen",
+            &[],
+            &TaskOptions {
+                dry_run: true,
+                ..TaskOptions::default()
+            },
+        )
+        .unwrap();
+
+        // Assert
+        assert!(
+            m.out
+                .infos()
+                .contains(&"  1. codegen (up to date)".to_string())
+        );
+    }
 }
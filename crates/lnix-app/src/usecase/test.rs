@@ -1,28 +1,168 @@
 //! `lnix test` — generate the flake and run declared test commands.
 
+use std::num::NonZeroUsize;
+
+use lnix_domain::interface::gateway::{DevEnv, TestInvocation};
+use lnix_domain::{
+    ShellName, TestDef, TestOutcome, TestReport, format_test_result, format_test_summary,
+    matches_glob, render_report, select_tests, test_script,
+};
+
 use crate::deps::Deps;
 use crate::error::ApplicationError;
 use crate::pipeline;
 
-/// Renders `flake.nix` and runs the test commands in `nix develop`.
+/// How `lnix test` runs, beyond which shell it runs in.
+#[derive(Debug, Clone, Default)]
+pub struct TestOptions {
+    /// Update `flake.lock` first.
+    pub update_lock: bool,
+    /// Only run tests whose name matches one of these (`*` and `?`
+    /// wildcards); every test when empty.
+    pub patterns: Vec<String>,
+    /// Skip the remaining tests after the first failure.
+    pub fail_fast: bool,
+    /// Reports to write, as `<format>=<path>`.
+    pub reports: Vec<String>,
+    /// How many tests run at once; overrides the shell's
+    /// `test.parallel`.
+    pub jobs: Option<NonZeroUsize>,
+}
+
+/// Renders `flake.nix`, evaluates the named dev shell once, and runs
+/// its tests in it. One test at a time, output is streamed; with more
+/// jobs, each test's output is held back and printed in declaration
+/// order once it has finished. Reports are written after the run,
+/// whether or not the tests passed.
 ///
-/// Fails fast (before writing the flake) when no tests are declared.
-pub fn test(d: &Deps, update_lock: bool) -> Result<i32, ApplicationError> {
+/// Fails fast (before writing the flake) when no tests are declared, a
+/// pattern matches none of them, or a report spec is malformed. Exits
+/// 1 when a test failed (tests with `allowFailure` do not count).
+pub fn test(d: &Deps, shell: &str, options: &TestOptions) -> Result<i32, ApplicationError> {
+    let shell: ShellName = shell.parse()?;
+    let reports = options
+        .reports
+        .iter()
+        .map(|report| report.parse())
+        .collect::<Result<Vec<TestReport>, _>>()?;
     let loaded = pipeline::load_config(d)?;
 
-    if loaded.config.dev_shell.test.is_empty() {
+    let suite = &loaded.shell(&shell)?.test;
+    if suite.is_empty() {
         return Err(ApplicationError::NoTestCommands);
     }
+    if let Some(unmatched) = options
+        .patterns
+        .iter()
+        .find(|pattern| !suite.tests.iter().any(|t| matches_glob(pattern, &t.name)))
+    {
+        return Err(ApplicationError::NoMatchingTests(unmatched.clone()));
+    }
+    let selected: Vec<&TestDef> = select_tests(&suite.tests, &options.patterns)
+        .into_iter()
+        .map(|position| &suite.tests[position])
+        .collect();
+    let jobs = options.jobs.or(suite.parallel);
 
     pipeline::write_flake(d, &loaded)?;
-    pipeline::maybe_update_lock(d, update_lock)?;
+    pipeline::maybe_update_lock(d, options.update_lock)?;
+
+    let env = d.nix.dev_env(&shell)?;
+    d.out.info("");
+    d.out.info("Running tests...");
+    d.out.info("=================");
+
+    let outcomes = match jobs {
+        Some(jobs) if jobs.get() > 1 => {
+            run_concurrently(d, &env, &selected, jobs, options.fail_fast)?
+        }
+        _ => run_sequentially(d, &env, &selected, options.fail_fast)?,
+    };
+
+    d.out.info("");
+    d.out.info("=================");
+    d.out.info(&format_test_summary(&outcomes));
+
+    for report in &reports {
+        d.reports.write_report(
+            &report.path,
+            &render_report(report.format, &shell, &outcomes),
+        )?;
+        d.out.info(&format!("Wrote test report to {}", report.path));
+    }
+
+    let failed = outcomes.iter().any(|o| o.status.is_failure());
+    Ok(if failed { 1 } else { 0 })
+}
 
+fn announce(d: &Deps, test: &TestDef) {
     d.out.info("");
-    Ok(d.nix.test()?)
+    d.out.info(&format!("Running: {}", test.name));
+    d.out.info("---");
+}
+
+fn run_sequentially(
+    d: &Deps,
+    env: &DevEnv,
+    tests: &[&TestDef],
+    fail_fast: bool,
+) -> Result<Vec<TestOutcome>, ApplicationError> {
+    let mut outcomes: Vec<TestOutcome> = Vec::new();
+    for test in tests {
+        if fail_fast && outcomes.iter().any(|o| o.status.is_failure()) {
+            outcomes.push(TestOutcome::skipped(test));
+            continue;
+        }
+        announce(d, test);
+        let run = d
+            .nix
+            .run_test(env, &test_script(test), test.timeout.as_ref())?;
+        let outcome = TestOutcome::finished(test, run.exit_code, run.duration, run.output);
+        d.out.info(&format_test_result(&outcome));
+        outcomes.push(outcome);
+    }
+    Ok(outcomes)
+}
+
+fn run_concurrently(
+    d: &Deps,
+    env: &DevEnv,
+    tests: &[&TestDef],
+    jobs: NonZeroUsize,
+    fail_fast: bool,
+) -> Result<Vec<TestOutcome>, ApplicationError> {
+    let invocations: Vec<TestInvocation> = tests
+        .iter()
+        .map(|test| TestInvocation {
+            script: test_script(test),
+            timeout: test.timeout.clone(),
+            allow_failure: test.allow_failure,
+        })
+        .collect();
+    let executions = d.nix.run_tests(env, &invocations, jobs, fail_fast)?;
+
+    let mut outcomes = Vec::new();
+    for (test, execution) in tests.iter().zip(executions) {
+        let Some(run) = execution else {
+            outcomes.push(TestOutcome::skipped(test));
+            continue;
+        };
+        announce(d, test);
+        let output = run.output.strip_suffix('\n').unwrap_or(&run.output);
+        if !output.is_empty() {
+            d.out.info(output);
+        }
+        let outcome = TestOutcome::finished(test, run.exit_code, run.duration, run.output);
+        d.out.info(&format_test_result(&outcome));
+        outcomes.push(outcome);
+    }
+    Ok(outcomes)
 }
 
 #[cfg(test)]
 mod tests {
+    use lnix_domain::ParseError;
+
     use super::*;
     use crate::mocks::*;
 
@@ -32,11 +172,17 @@ mod tests {
             "devShell:\n  package:\n    stable:\n      - name: bash\n  test:\n    - cargo test\n",
         ));
 
-        let code = test(&m.deps(), false).unwrap();
+        let code = test(&m.deps(), "default", &TestOptions::default()).unwrap();
 
         assert_eq!(code, 0);
         assert!(m.flake_writer.written().is_some());
-        assert_eq!(m.nix.test_calls(), 1);
+        assert_eq!(m.nix.dev_env_calls(), 1);
+        assert_eq!(m.nix.run_test_scripts(), vec!["cargo test"]);
+        assert!(
+            m.out
+                .infos()
+                .contains(&"Test Results: 1 passed, 0 failed, 0 allowed to fail".to_string())
+        );
     }
 
     #[test]
@@ -45,10 +191,221 @@ mod tests {
             "devShell:\n  package:\n    stable:\n      - name: bash\n",
         ));
 
-        let result = test(&m.deps(), false);
+        let result = test(&m.deps(), "default", &TestOptions::default());
 
         assert!(matches!(result, Err(ApplicationError::NoTestCommands)));
         assert!(m.flake_writer.written().is_none());
-        assert_eq!(m.nix.test_calls(), 0);
+        assert_eq!(m.nix.dev_env_calls(), 0);
+    }
+
+    #[test]
+    fn runs_tests_declared_in_the_requested_shell() {
+        let m = Mocks::with_config(config_from_yaml(
+            "devShells:\n  default:\n    package:\n      stable:\n        - name: bash\n  ci:\n    package:\n      stable:\n        - name: bash\n    test:\n      - cargo test\n",
+        ));
+
+        let code = test(&m.deps(), "ci", &TestOptions::default()).unwrap();
+
+        assert_eq!(code, 0);
+        assert_eq!(m.nix.last_shell().as_deref(), Some("ci"));
+    }
+
+    const NAMED_TESTS: &str = "devShell:\n  package:\n    stable:\n      - name: bash\n  test:\n    - name: unit\n      command: cargo test --lib\n    - name: integration-api\n      command: cargo test --test api\n    - name: integration-cli\n      command: cargo test --test cli\n";
+
+    #[test]
+    fn selects_tests_by_name_or_glob() {
+        let m = Mocks::with_config(config_from_yaml(NAMED_TESTS));
+        let patterns = vec!["integration-*".to_string()];
+
+        let code = test(
+            &m.deps(),
+            "default",
+            &TestOptions {
+                patterns,
+                fail_fast: true,
+                ..TestOptions::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(code, 0);
+        assert_eq!(
+            m.nix.run_test_scripts(),
+            vec!["cargo test --test api", "cargo test --test cli"]
+        );
+    }
+
+    #[test]
+    fn runs_every_test_without_patterns() {
+        let m = Mocks::with_config(config_from_yaml(NAMED_TESTS));
+
+        test(&m.deps(), "default", &TestOptions::default()).unwrap();
+
+        assert_eq!(m.nix.dev_env_calls(), 1);
+        assert_eq!(m.nix.run_test_scripts().len(), 3);
+    }
+
+    #[test]
+    fn rejects_a_pattern_that_matches_no_test() {
+        let m = Mocks::with_config(config_from_yaml(NAMED_TESTS));
+        let patterns = vec!["unit".to_string(), "e2e*".to_string()];
+
+        let result = test(
+            &m.deps(),
+            "default",
+            &TestOptions {
+                patterns,
+                ..TestOptions::default()
+            },
+        );
+
+        assert!(matches!(result, Err(ApplicationError::NoMatchingTests(p)) if p == "e2e*"));
+        assert!(m.flake_writer.written().is_none());
+        assert_eq!(m.nix.dev_env_calls(), 0);
+    }
+
+    #[test]
+    fn keeps_running_after_a_failure_and_exits_1() {
+        let m = Mocks::with_config(config_from_yaml(NAMED_TESTS))
+            .with_failing_test_command("cargo test --lib");
+
+        let code = test(&m.deps(), "default", &TestOptions::default()).unwrap();
+
+        assert_eq!(code, 1);
+        assert_eq!(m.nix.run_test_scripts().len(), 3);
+        let infos = m.out.infos();
+        assert!(infos.contains(&"[FAIL] unit".to_string()));
+        assert!(infos.contains(&"[PASS] integration-cli".to_string()));
+    }
+
+    #[test]
+    fn allowed_failures_do_not_fail_the_run() {
+        let m = Mocks::with_config(config_from_yaml(
+            "devShell:\n  package:\n    stable:\n      - name: bash\n  test:\n    - name: flaky\n      command: ./flaky.sh\n      allowFailure: true\n",
+        ))
+        .with_failing_test_command("./flaky.sh");
+
+        let code = test(&m.deps(), "default", &TestOptions::default()).unwrap();
+
+        assert_eq!(code, 0);
+        assert!(
+            m.out
+                .infos()
+                .contains(&"[FAIL] flaky (allowed to fail)".to_string())
+        );
+    }
+
+    #[test]
+    fn writes_requested_reports_with_skipped_tests_after_fail_fast() {
+        let m = Mocks::with_config(config_from_yaml(NAMED_TESTS))
+            .with_failing_test_command("cargo test --lib");
+        let reports = vec![
+            "junit=out/junit.xml".to_string(),
+            "json=out.json".to_string(),
+        ];
+
+        let code = test(
+            &m.deps(),
+            "default",
+            &TestOptions {
+                fail_fast: true,
+                reports,
+                ..TestOptions::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(code, 1);
+        assert_eq!(m.nix.run_test_scripts(), vec!["cargo test --lib"]);
+        let written = m.reports.written();
+        assert_eq!(written.len(), 2);
+        assert_eq!(written[0].0, "out/junit.xml");
+        assert!(written[0].1.contains("<testcase name=\"unit\""));
+        assert!(written[0].1.contains("<failure message=\"exit code 1\"/>"));
+        assert!(written[0].1.contains("output of cargo test --lib"));
+        assert_eq!(written[1].0, "out.json");
+        let skipped = written[1].1.split("\"name\": \"integration-cli\"").nth(1);
+        assert!(skipped.is_some_and(|rest| rest.contains("\"status\": \"skipped\"")));
+    }
+
+    #[test]
+    fn rejects_a_malformed_report_before_running() {
+        let m = Mocks::with_config(config_from_yaml(NAMED_TESTS));
+        let reports = vec!["xml=out.xml".to_string()];
+
+        let result = test(
+            &m.deps(),
+            "default",
+            &TestOptions {
+                reports,
+                ..TestOptions::default()
+            },
+        );
+
+        assert!(matches!(
+            result,
+            Err(ApplicationError::InvalidInput(
+                ParseError::InvalidTestReport(_)
+            ))
+        ));
+        assert_eq!(m.nix.dev_env_calls(), 0);
+    }
+
+    const PARALLEL_TESTS: &str = "devShell:\n  package:\n    stable:\n      - name: bash\n  test:\n    parallel: 4\n    tests:\n      - name: unit\n        command: cargo test --lib\n      - name: integration\n        command: cargo test --test api\n";
+
+    #[test]
+    fn runs_tests_concurrently_when_the_suite_is_parallel() {
+        let m = Mocks::with_config(config_from_yaml(PARALLEL_TESTS));
+
+        let code = test(&m.deps(), "default", &TestOptions::default()).unwrap();
+
+        assert_eq!(code, 0);
+        assert_eq!(m.nix.run_tests_jobs(), Some(4));
+        assert_eq!(m.nix.dev_env_calls(), 1);
+    }
+
+    #[test]
+    fn jobs_override_the_suite_parallelism() {
+        let m = Mocks::with_config(config_from_yaml(PARALLEL_TESTS));
+
+        test(
+            &m.deps(),
+            "default",
+            &TestOptions {
+                jobs: NonZeroUsize::new(1),
+                ..TestOptions::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(m.nix.run_tests_jobs(), None);
+        assert_eq!(m.nix.run_test_scripts().len(), 2);
+
+        let m = Mocks::with_config(config_from_yaml(NAMED_TESTS));
+        test(
+            &m.deps(),
+            "default",
+            &TestOptions {
+                jobs: NonZeroUsize::new(2),
+                ..TestOptions::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(m.nix.run_tests_jobs(), Some(2));
+    }
+
+    #[test]
+    fn prints_buffered_output_in_declaration_order() {
+        let m = Mocks::with_config(config_from_yaml(PARALLEL_TESTS))
+            .with_failing_test_command("cargo test --lib");
+
+        let code = test(&m.deps(), "default", &TestOptions::default()).unwrap();
+
+        assert_eq!(code, 1);
+        let infos = m.out.infos();
+        let position = |line: &str| infos.iter().position(|l| l == line).unwrap();
+        assert!(position("Running: unit") < position("output of cargo test --lib"));
+        assert!(position("output of cargo test --lib") < position("[FAIL] unit"));
+        assert!(position("[FAIL] unit") < position("Running: integration"));
+        assert!(position("output of cargo test --test api") < position("[PASS] integration"));
     }
 }
@@ -0,0 +1,100 @@
+//! `--watch`: re-run a use-case whenever the configuration changes.
+
+use crate::deps::Deps;
+use crate::error::ApplicationError;
+
+/// Runs `run`, then again each time `lazynix.yaml`,
+/// `lazynix-settings.yaml`, a file `lazynix.yaml` extends, or a dotenv
+/// or alias file they reference changes, until interrupted. A failing
+/// run is reported as a warning rather than ending the watch, so a
+/// half-edited config can be fixed in place.
+pub fn watch(
+    d: &Deps,
+    run: impl Fn(&Deps) -> Result<i32, ApplicationError>,
+) -> Result<i32, ApplicationError> {
+    let mut files = Vec::new();
+    loop {
+        if let Err(e) = run(d) {
+            d.out.warn(&e.to_string());
+        }
+        // An unreadable config keeps the previous list, so fixing it
+        // still triggers a run.
+        if let (Ok(config), Ok(extended)) = (d.repo.read_config(), d.repo.extended_files()) {
+            files = extended;
+            for file in config.referenced_files() {
+                if !files.contains(&file) {
+                    files.push(file);
+                }
+            }
+        }
+        d.out.info("");
+        d.out.info("Watching for changes (press Ctrl-C to stop)...");
+        let changed = d.watcher.wait_for_change(&files)?;
+        d.out.info(&format!("Changed: {}", changed.join(", ")));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks::*;
+    use crate::usecase::generate;
+
+    const CONFIG: &str = "devShell:\n  package:\n    stable:\n      - name: bash\n  env:\n    dotenv:\n      - .env\n  shellAlias:\n    - aliases.sh\n";
+
+    #[test]
+    fn reruns_until_the_watcher_stops() {
+        let m = Mocks::with_config(config_from_yaml(CONFIG))
+            .with_config_changes(&[&["lazynix.yaml"], &[".env", "aliases.sh"]]);
+
+        let result = watch(&m.deps(), generate);
+
+        assert!(result.is_err(), "the scripted watcher ends the loop");
+        assert_eq!(m.flake_writer.write_count(), 3);
+        assert_eq!(
+            m.watcher.watched(),
+            vec![vec![".env", "aliases.sh"]; 3],
+            "referenced files are watched"
+        );
+        let infos = m.out.infos();
+        assert!(infos.contains(&"Changed: lazynix.yaml".to_string()));
+        assert!(infos.contains(&"Changed: .env, aliases.sh".to_string()));
+    }
+
+    #[test]
+    fn watches_the_files_the_config_extends() {
+        let m = Mocks::with_config(config_from_yaml(CONFIG))
+            .with_extended_files(&["shared/base.yaml"])
+            .with_config_changes(&[&["shared/base.yaml"]]);
+
+        let _ = watch(&m.deps(), generate);
+
+        assert_eq!(
+            m.watcher.watched()[0],
+            vec!["shared/base.yaml", ".env", "aliases.sh"]
+        );
+        assert!(
+            m.out
+                .infos()
+                .contains(&"Changed: shared/base.yaml".to_string())
+        );
+    }
+
+    #[test]
+    fn reports_a_failing_run_and_keeps_watching() {
+        let m = Mocks::with_config(config_from_yaml(CONFIG))
+            .with_missing_env_files()
+            .with_config_changes(&[&[".env"]]);
+
+        let _ = watch(&m.deps(), generate);
+
+        assert_eq!(m.watcher.watched().len(), 2);
+        assert_eq!(m.out.warns().len(), 2);
+        assert!(
+            m.out.warns()[0].contains(".env"),
+            "got: {:?}",
+            m.out.warns()
+        );
+        assert!(m.flake_writer.written().is_none());
+    }
+}
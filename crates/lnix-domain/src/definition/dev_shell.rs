@@ -1,22 +1,151 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::definition::env::Env;
+use crate::definition::input::FlakeInput;
+use crate::definition::nixpkgs_config::NixpkgsConfig;
+use crate::definition::overlay::Overlay;
 use crate::definition::package::Package;
+use crate::definition::service::ServiceDef;
 use crate::definition::task::TaskDef;
-use crate::values::TaskName;
+use crate::definition::test::TestSuite;
+use crate::error::ValidationError;
+use crate::values::{ShellName, TaskName};
 
 /// Root of the `lazynix.yaml` document.
-#[derive(Debug, Clone, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
+///
+/// The document may declare a single `devShell` section (the `default`
+/// shell), a `devShells` map of named shells, or both. They are merged
+/// into one map at parse time, so downstream code never has to care
+/// which spelling the user chose.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
+#[serde(try_from = "RawDevShellDefinition", into = "RawDevShellDefinition")]
 pub struct DevShellDefinition {
-    pub dev_shell: DevShell,
+    /// Other `lazynix.yaml` files this one builds on, relative to this
+    /// file. The config repository merges them in (see
+    /// [`crate::merge_definitions`]) and leaves this empty.
+    pub extends: Vec<String>,
+
+    /// Third-party flakes added to the generated flake's `inputs`.
+    /// Shared by every dev shell.
+    pub inputs: Vec<FlakeInput>,
+
+    /// Overlays applied to every nixpkgs import, in order.
+    pub overlays: Vec<Overlay>,
+
+    /// nixpkgs `config` options applied to every nixpkgs import, next
+    /// to each shell's `allowUnfree`.
+    pub nixpkgs_config: NixpkgsConfig,
+
+    /// Every dev shell, keyed by the name it renders under
+    /// (`devShells.<name>`). Never empty once `extends` is resolved.
+    pub dev_shells: BTreeMap<ShellName, DevShell>,
+}
+
+impl DevShellDefinition {
+    /// Looks up the dev shell rendered as `devShells.<name>`.
+    pub fn shell(&self, name: &ShellName) -> Option<&DevShell> {
+        self.dev_shells.get(name)
+    }
+
+    /// Every dotenv file (of shells, tasks, tests, and services) and
+    /// shell alias file the config points at, as written and without
+    /// duplicates, in the order they first appear.
+    pub fn referenced_files(&self) -> Vec<String> {
+        let mut files: Vec<String> = Vec::new();
+        for dev_shell in self.dev_shells.values() {
+            let tasks = dev_shell.task.iter().flat_map(|tasks| tasks.values());
+            let task_files = tasks.flat_map(|task| {
+                task.env
+                    .iter()
+                    .flat_map(|env| &env.dotenv)
+                    .chain(&task.dotenv)
+            });
+            let test_envs = dev_shell.test.tests.iter().filter_map(|t| t.env.as_ref());
+            let service_envs = dev_shell.services.iter().filter_map(|s| s.env.as_ref());
+            let dotenv = dev_shell
+                .env
+                .iter()
+                .chain(test_envs)
+                .chain(service_envs)
+                .flat_map(|env| &env.dotenv);
+            for file in dotenv.chain(task_files).chain(&dev_shell.shell_alias) {
+                if !files.contains(file) {
+                    files.push(file.clone());
+                }
+            }
+        }
+        files
+    }
+}
+
+/// The document as written, before `devShell` and `devShells` are merged.
+#[derive(Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+struct RawDevShellDefinition {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    extends: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    inputs: Vec<FlakeInput>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    overlays: Vec<Overlay>,
+
+    #[serde(default, skip_serializing_if = "NixpkgsConfig::is_default")]
+    nixpkgs_config: NixpkgsConfig,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dev_shell: Option<DevShell>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    dev_shells: BTreeMap<ShellName, DevShell>,
+}
+
+impl TryFrom<RawDevShellDefinition> for DevShellDefinition {
+    type Error = ValidationError;
+
+    fn try_from(raw: RawDevShellDefinition) -> Result<Self, Self::Error> {
+        let mut dev_shells = raw.dev_shells;
+        if let Some(dev_shell) = raw.dev_shell
+            && dev_shells.insert(ShellName::default(), dev_shell).is_some()
+        {
+            return Err(ValidationError::DuplicateDefaultShell);
+        }
+        // A file that extends others may rely on them for every shell.
+        if dev_shells.is_empty() && raw.extends.is_empty() {
+            return Err(ValidationError::NoDevShell);
+        }
+        Ok(Self {
+            extends: raw.extends,
+            inputs: raw.inputs,
+            overlays: raw.overlays,
+            nixpkgs_config: raw.nixpkgs_config,
+            dev_shells,
+        })
+    }
 }
 
-/// The `devShell` section: everything needed to render a dev shell flake.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+impl From<DevShellDefinition> for RawDevShellDefinition {
+    fn from(definition: DevShellDefinition) -> Self {
+        Self {
+            extends: definition.extends,
+            inputs: definition.inputs,
+            overlays: definition.overlays,
+            nixpkgs_config: definition.nixpkgs_config,
+            dev_shell: None,
+            dev_shells: definition.dev_shells,
+        }
+    }
+}
+
+/// One dev shell: everything needed to render a `devShells.<name>` entry.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
 #[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
 pub struct DevShell {
     #[serde(default = "default_allow_unfree")]
     pub allow_unfree: bool,
@@ -30,11 +159,15 @@ pub struct DevShell {
     pub env: Option<Env>,
 
     #[serde(default)]
-    pub test: Vec<String>,
+    pub test: TestSuite,
 
     #[serde(default)]
     pub task: Option<HashMap<TaskName, TaskDef>>,
 
+    /// Background processes `lnix up` starts, in this order.
+    #[serde(default)]
+    pub services: Vec<ServiceDef>,
+
     /// Shell alias files to load.
     /// Alias definitions are extracted from the specified files.
     #[serde(default)]
@@ -49,6 +182,10 @@ fn default_allow_unfree() -> bool {
 mod tests {
     use super::*;
 
+    fn default_shell(config: &DevShellDefinition) -> &DevShell {
+        config.shell(&ShellName::default()).unwrap()
+    }
+
     #[test]
     fn deserializes_minimal_yaml() {
         // Arrange
@@ -63,15 +200,159 @@ devShell:
         let config: DevShellDefinition = serde_yaml::from_str(yaml).unwrap();
 
         // Assert
-        assert!(
-            config.dev_shell.allow_unfree,
-            "allowUnfree defaults to true"
+        let dev_shell = default_shell(&config);
+        assert!(dev_shell.allow_unfree, "allowUnfree defaults to true");
+        assert_eq!(dev_shell.package.stable.len(), 1);
+        assert!(dev_shell.env.is_none());
+        assert!(dev_shell.task.is_none());
+        assert!(dev_shell.test.is_empty());
+        assert!(dev_shell.services.is_empty());
+        assert!(dev_shell.shell_alias.is_empty());
+    }
+
+    #[test]
+    fn lists_referenced_dotenv_and_alias_files_once() {
+        // Arrange
+        let yaml = r#"
+devShells:
+  default:
+    package:
+      stable: []
+    env:
+      dotenv: [.env]
+    shellAlias: [aliases.sh]
+    test:
+      - name: unit
+        command: cargo test
+        env:
+          dotenv: [.env.test]
+  ci:
+    package:
+      stable: []
+    env:
+      dotenv: [.env]
+    task:
+      deploy:
+        commands: [./deploy.sh]
+        dotenv: [.env.deploy]
+"#;
+        let config: DevShellDefinition = serde_yaml::from_str(yaml).unwrap();
+
+        // Act
+        let files = config.referenced_files();
+
+        // Assert
+        assert_eq!(
+            files,
+            vec![".env", ".env.deploy", ".env.test", "aliases.sh"]
+        );
+    }
+
+    #[test]
+    fn deserializes_named_dev_shells() {
+        // Arrange
+        let yaml = r#"
+devShells:
+  default:
+    package:
+      stable:
+        - name: bash
+  ci:
+    package:
+      stable:
+        - name: jq
+"#;
+
+        // Act
+        let config: DevShellDefinition = serde_yaml::from_str(yaml).unwrap();
+
+        // Assert
+        let names: Vec<&str> = config.dev_shells.keys().map(|n| n.as_str()).collect();
+        assert_eq!(names, vec!["ci", "default"]);
+        let ci = config.shell(&"ci".parse().unwrap()).unwrap();
+        assert_eq!(ci.package.stable[0].name.as_str(), "jq");
+    }
+
+    #[test]
+    fn merges_dev_shell_into_dev_shells_as_default() {
+        // Arrange
+        let yaml = r#"
+devShell:
+  package:
+    stable:
+      - name: bash
+devShells:
+  docs:
+    package:
+      stable:
+        - name: mdbook
+"#;
+
+        // Act
+        let config: DevShellDefinition = serde_yaml::from_str(yaml).unwrap();
+
+        // Assert
+        assert_eq!(config.dev_shells.len(), 2);
+        assert_eq!(
+            default_shell(&config).package.stable[0].name.as_str(),
+            "bash"
         );
-        assert_eq!(config.dev_shell.package.stable.len(), 1);
-        assert!(config.dev_shell.env.is_none());
-        assert!(config.dev_shell.task.is_none());
-        assert!(config.dev_shell.test.is_empty());
-        assert!(config.dev_shell.shell_alias.is_empty());
+    }
+
+    #[test]
+    fn rejects_default_shell_defined_twice() {
+        // Arrange
+        let yaml = r#"
+devShell:
+  package:
+    stable:
+      - name: bash
+devShells:
+  default:
+    package:
+      stable:
+        - name: zsh
+"#;
+
+        // Act
+        let result = serde_yaml::from_str::<DevShellDefinition>(yaml);
+
+        // Assert
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("defined twice"), "got: {message}");
+    }
+
+    #[test]
+    fn rejects_document_without_any_dev_shell() {
+        // Arrange
+        let yaml = "devShells: {}
+";
+
+        // Act
+        let result = serde_yaml::from_str::<DevShellDefinition>(yaml);
+
+        // Assert
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("No dev shell defined"), "got: {message}");
+    }
+
+    #[test]
+    fn rejects_invalid_shell_name_at_parse_time() {
+        // Arrange
+        let yaml = r#"
+devShells:
+  "ci.docs":
+    package:
+      stable:
+        - name: bash
+"#;
+
+        // Act
+        let result = serde_yaml::from_str::<DevShellDefinition>(yaml);
+
+        // Assert
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("Invalid dev shell name"), "got: {message}");
     }
 
     #[test]
@@ -93,12 +374,36 @@ devShell:
         let config: DevShellDefinition = serde_yaml::from_str(yaml).unwrap();
 
         // Assert
-        let tasks = config.dev_shell.task.unwrap();
+        let tasks = default_shell(&config).task.clone().unwrap();
         let task = tasks.get(&"my-task_123".parse().unwrap()).unwrap();
         assert_eq!(task.description.as_deref(), Some("Valid task"));
         assert_eq!(task.commands, vec!["echo \"test\""]);
     }
 
+    #[test]
+    fn deserializes_test_section_with_parallel_limit() {
+        // Arrange
+        let yaml = r#"
+devShell:
+  package:
+    stable:
+      - name: bash
+  test:
+    parallel: 4
+    tests:
+      - name: unit
+        command: cargo test --lib
+"#;
+
+        // Act
+        let config: DevShellDefinition = serde_yaml::from_str(yaml).unwrap();
+
+        // Assert
+        let suite = &default_shell(&config).test;
+        assert_eq!(suite.parallel.map(|n| n.get()), Some(4));
+        assert_eq!(suite.tests[0].name, "unit");
+    }
+
     #[test]
     fn rejects_invalid_task_name_at_parse_time() {
         // Arrange
@@ -1,10 +1,13 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::error::ValidationError;
 use crate::values::EnvVarName;
 
 /// The `env` section: dotenv files and inline environment variables.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
 #[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
 pub struct Env {
     #[serde(default)]
     pub dotenv: Vec<String>,
@@ -13,12 +16,114 @@ pub struct Env {
     pub envvar: Vec<EnvVar>,
 }
 
-/// A single environment variable exported into the dev shell.
-#[derive(Debug, Clone, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
+/// A single environment variable exported into (or removed from) the
+/// dev shell.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
+#[serde(try_from = "RawEnvVar", into = "RawEnvVar")]
 pub struct EnvVar {
     pub name: EnvVarName,
+
+    /// Empty for [`EnvVarOp::Unset`], which takes no value, and when
+    /// the value comes from `command`.
     pub value: String,
+
+    /// Shell command whose output becomes the value, run each time the
+    /// dev shell starts.
+    pub command: Option<String>,
+
+    /// Let `$OTHER_VAR` references in `value` expand when the shell
+    /// starts. Off by default: the value is exported verbatim.
+    pub expand: bool,
+
+    pub op: EnvVarOp,
+
+    /// Joins `value` to the inherited value for `prepend` / `append`.
+    pub separator: String,
+}
+
+/// How the variable combines with a value inherited from the host.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnvVarOp {
+    /// Replace any inherited value.
+    #[default]
+    Set,
+    /// Put `value` in front of the inherited value, e.g. on `PATH`.
+    Prepend,
+    /// Put `value` after the inherited value.
+    Append,
+    /// Remove the variable from the shell.
+    Unset,
+}
+
+const DEFAULT_SEPARATOR: &str = ":";
+
+/// The entry as written: at most one of `value` and `command` (none for
+/// `op: unset`), and `separator` falls back to `:`.
+#[derive(Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+struct RawEnvVar {
+    name: EnvVarName,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    command: Option<String>,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    expand: bool,
+
+    #[serde(default, skip_serializing_if = "is_set")]
+    op: EnvVarOp,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    separator: Option<String>,
+}
+
+fn is_set(op: &EnvVarOp) -> bool {
+    *op == EnvVarOp::Set
+}
+
+impl TryFrom<RawEnvVar> for EnvVar {
+    type Error = ValidationError;
+
+    fn try_from(raw: RawEnvVar) -> Result<Self, Self::Error> {
+        let name = raw.name.to_string();
+        let (value, command) = match (raw.op, raw.value, raw.command) {
+            (EnvVarOp::Unset, None, None) => (String::new(), None),
+            (EnvVarOp::Unset, _, _) => return Err(ValidationError::UnsetWithValue(name)),
+            (_, Some(_), Some(_)) => return Err(ValidationError::AmbiguousEnvVarSource(name)),
+            (_, Some(value), None) => (value, None),
+            (_, None, Some(command)) => (String::new(), Some(command)),
+            (_, None, None) => return Err(ValidationError::MissingEnvVarValue(name)),
+        };
+        Ok(Self {
+            name: raw.name,
+            value,
+            command,
+            expand: raw.expand,
+            op: raw.op,
+            separator: raw
+                .separator
+                .unwrap_or_else(|| DEFAULT_SEPARATOR.to_string()),
+        })
+    }
+}
+
+impl From<EnvVar> for RawEnvVar {
+    fn from(envvar: EnvVar) -> Self {
+        Self {
+            name: envvar.name,
+            value: (envvar.op != EnvVarOp::Unset && envvar.command.is_none())
+                .then_some(envvar.value),
+            command: envvar.command,
+            expand: envvar.expand,
+            op: envvar.op,
+            separator: (envvar.separator != DEFAULT_SEPARATOR).then_some(envvar.separator),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -44,6 +149,7 @@ envvar:
         assert_eq!(env.envvar.len(), 1);
         assert_eq!(env.envvar[0].name.as_str(), "MY_VAR");
         assert_eq!(env.envvar[0].value, "hello");
+        assert!(!env.envvar[0].expand);
     }
 
     #[test]
@@ -65,4 +171,75 @@ envvar:
             "got: {message}"
         );
     }
+
+    #[test]
+    fn deserializes_ops_with_default_separator() {
+        // Arrange
+        let yaml = r#"
+envvar:
+  - name: PATH
+    value: ./node_modules/.bin
+    op: prepend
+  - name: PYTHONPATH
+    value: ./src
+    op: append
+    separator: ";"
+  - name: PYTHONHOME
+    op: unset
+"#;
+
+        // Act
+        let env: Env = serde_yaml::from_str(yaml).unwrap();
+
+        // Assert
+        assert_eq!(env.envvar[0].op, EnvVarOp::Prepend);
+        assert_eq!(env.envvar[0].separator, ":");
+        assert_eq!(env.envvar[1].op, EnvVarOp::Append);
+        assert_eq!(env.envvar[1].separator, ";");
+        assert_eq!(env.envvar[2].op, EnvVarOp::Unset);
+        assert_eq!(env.envvar[2].value, "");
+    }
+
+    #[test]
+    fn requires_value_unless_unset() {
+        // Arrange
+        let missing = "envvar:\n  - name: PATH\n    op: prepend\n";
+        let extra = "envvar:\n  - name: PYTHONHOME\n    value: /usr\n    op: unset\n";
+
+        // Act
+        let missing_error = serde_yaml::from_str::<Env>(missing).unwrap_err();
+        let extra_error = serde_yaml::from_str::<Env>(extra).unwrap_err();
+
+        // Assert
+        assert!(
+            missing_error.to_string().contains("needs a `value`"),
+            "got: {missing_error}"
+        );
+        assert!(
+            extra_error
+                .to_string()
+                .contains("cannot also set a `value`"),
+            "got: {extra_error}"
+        );
+    }
+
+    #[test]
+    fn accepts_command_instead_of_value() {
+        // Arrange
+        let yaml = "envvar:\n  - name: GIT_SHA\n    command: git rev-parse HEAD\n";
+        let both = "envvar:\n  - name: GIT_SHA\n    value: abc\n    command: git rev-parse HEAD\n";
+
+        // Act
+        let env: Env = serde_yaml::from_str(yaml).unwrap();
+        let both_error = serde_yaml::from_str::<Env>(both).unwrap_err();
+
+        // Assert
+        assert_eq!(env.envvar[0].command.as_deref(), Some("git rev-parse HEAD"));
+        assert!(
+            both_error
+                .to_string()
+                .contains("either `value` or `command`"),
+            "got: {both_error}"
+        );
+    }
 }
@@ -0,0 +1,62 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::values::{FlakeUrl, InputName};
+
+/// A third-party flake added to the generated flake's `inputs`, whose
+/// packages can then be referenced with `from: <name>`.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct FlakeInput {
+    pub name: InputName,
+
+    pub url: FlakeUrl,
+
+    /// Inputs of this flake redirected to one of ours, e.g.
+    /// `nixpkgs: nixpkgs` renders `<name>.inputs.nixpkgs.follows = "nixpkgs"`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub follows: BTreeMap<InputName, InputName>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_input_with_follows() {
+        // Arrange
+        let yaml = r#"
+name: fenix
+url: github:nix-community/fenix
+follows:
+  nixpkgs: nixpkgs
+"#;
+
+        // Act
+        let input: FlakeInput = serde_yaml::from_str(yaml).unwrap();
+
+        // Assert
+        assert_eq!(input.name.as_str(), "fenix");
+        assert_eq!(input.url.as_str(), "github:nix-community/fenix");
+        assert_eq!(
+            input.follows[&"nixpkgs".parse().unwrap()].as_str(),
+            "nixpkgs"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_url_at_parse_time() {
+        // Arrange
+        let yaml = "name: fenix\nurl: \"github:a/b c\"\n";
+
+        // Act
+        let result = serde_yaml::from_str::<FlakeInput>(yaml);
+
+        // Assert
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("Invalid flake URL"), "got: {message}");
+    }
+}
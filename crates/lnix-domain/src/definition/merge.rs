@@ -0,0 +1,410 @@
+use crate::definition::dev_shell::{DevShell, DevShellDefinition};
+use crate::definition::env::Env;
+use crate::definition::nixpkgs_config::NixpkgsConfig;
+use crate::definition::package::{Package, PackageEntry, PinnedPackageEntry};
+use crate::definition::service::ServiceDef;
+use crate::definition::test::TestSuite;
+use crate::error::ConfigError;
+
+/// Layers `overlay` on top of `base`, the way an `extends:` entry is
+/// applied: `base` is the extended file, `overlay` the file extending it.
+///
+/// `inputs` are overridden by name and `overlays` concatenated (base
+/// first) and de-duplicated. In `nixpkgsConfig`, lists are concatenated
+/// and flags enabled by either layer stay enabled. Shells are merged by
+/// name. Within a shell:
+/// - package lists, `shellHook`, `shellAlias`, and `env.dotenv` are
+///   concatenated (base first) and de-duplicated
+/// - `env.envvar`, `task`, `test`, and `services` are overridden by key
+///   (a test's or service's key is its name)
+/// - `allowUnfree` holds only when both layers allow it
+/// - `test.parallel` is taken from the overlay when it sets one
+/// - a package listed in both layers gets the overlay's `platforms`
+///   (none meaning every system)
+///
+/// The same package pinned to two different versions cannot be
+/// reconciled and is reported as [`ConfigError::MergeConflict`].
+pub fn merge_definitions(
+    base: DevShellDefinition,
+    overlay: DevShellDefinition,
+) -> Result<DevShellDefinition, ConfigError> {
+    let mut dev_shells = base.dev_shells;
+    for (name, overlay_shell) in overlay.dev_shells {
+        let merged = match dev_shells.remove(&name) {
+            Some(base_shell) => merge_shells(base_shell, overlay_shell)?,
+            None => overlay_shell,
+        };
+        dev_shells.insert(name, merged);
+    }
+    let mut inputs = base.inputs;
+    for input in overlay.inputs {
+        match inputs.iter_mut().find(|seen| seen.name == input.name) {
+            Some(seen) => *seen = input,
+            None => inputs.push(input),
+        }
+    }
+    let mut overlays = base.overlays;
+    for entry in overlay.overlays {
+        if !overlays.contains(&entry) {
+            overlays.push(entry);
+        }
+    }
+    Ok(DevShellDefinition {
+        extends: overlay.extends,
+        inputs,
+        overlays,
+        nixpkgs_config: merge_nixpkgs_config(base.nixpkgs_config, overlay.nixpkgs_config),
+        dev_shells,
+    })
+}
+
+fn merge_nixpkgs_config(base: NixpkgsConfig, overlay: NixpkgsConfig) -> NixpkgsConfig {
+    let mut permitted_insecure_packages = base.permitted_insecure_packages;
+    for name in overlay.permitted_insecure_packages {
+        if !permitted_insecure_packages.contains(&name) {
+            permitted_insecure_packages.push(name);
+        }
+    }
+    let mut allow_unfree_packages = base.allow_unfree_packages;
+    for name in overlay.allow_unfree_packages {
+        if !allow_unfree_packages.contains(&name) {
+            allow_unfree_packages.push(name);
+        }
+    }
+    NixpkgsConfig {
+        allow_broken: base.allow_broken || overlay.allow_broken,
+        permitted_insecure_packages,
+        allow_unfree_packages,
+        cuda_support: base.cuda_support || overlay.cuda_support,
+        rocm_support: base.rocm_support || overlay.rocm_support,
+    }
+}
+
+fn merge_shells(base: DevShell, overlay: DevShell) -> Result<DevShell, ConfigError> {
+    let task = match (base.task, overlay.task) {
+        (Some(mut tasks), Some(overrides)) => {
+            tasks.extend(overrides);
+            Some(tasks)
+        }
+        (tasks, None) | (None, tasks) => tasks,
+    };
+    let env = match (base.env, overlay.env) {
+        (Some(base_env), Some(overlay_env)) => Some(merge_env(base_env, overlay_env)),
+        (env, None) | (None, env) => env,
+    };
+    Ok(DevShell {
+        allow_unfree: base.allow_unfree && overlay.allow_unfree,
+        package: merge_packages(base.package, overlay.package)?,
+        shell_hook: concat_unique(base.shell_hook, overlay.shell_hook),
+        env,
+        test: merge_tests(base.test, overlay.test),
+        task,
+        services: merge_services(base.services, overlay.services),
+        shell_alias: concat_unique(base.shell_alias, overlay.shell_alias),
+    })
+}
+
+fn merge_packages(base: Package, overlay: Package) -> Result<Package, ConfigError> {
+    let stable = merge_channel(base.stable, overlay.stable);
+    let unstable = merge_channel(base.unstable, overlay.unstable);
+    let mut pinned: Vec<PinnedPackageEntry> = base.pinned;
+    for entry in overlay.pinned {
+        match pinned.iter_mut().find(|seen| seen.name == entry.name) {
+            Some(seen) if seen.version != entry.version => {
+                return Err(ConfigError::MergeConflict(format!(
+                    "package '{}' is pinned to both {} and {}",
+                    entry.name, seen.version, entry.version
+                )));
+            }
+            Some(seen) => seen.platforms = entry.platforms,
+            None => pinned.push(entry),
+        }
+    }
+    Ok(Package {
+        stable,
+        unstable,
+        pinned,
+    })
+}
+
+/// De-duplicates by package (and source input). An entry listed in both
+/// layers combines their `with` sub-packages and takes the overlay's
+/// `platforms`.
+fn merge_channel(mut base: Vec<PackageEntry>, overlay: Vec<PackageEntry>) -> Vec<PackageEntry> {
+    for entry in overlay {
+        match base
+            .iter_mut()
+            .find(|seen| seen.name == entry.name && seen.from == entry.from)
+        {
+            Some(seen) => {
+                for sub in entry.with_packages {
+                    if !seen.with_packages.contains(&sub) {
+                        seen.with_packages.push(sub);
+                    }
+                }
+                seen.platforms = entry.platforms;
+            }
+            None => base.push(entry),
+        }
+    }
+    base
+}
+
+fn merge_tests(mut base: TestSuite, overlay: TestSuite) -> TestSuite {
+    for test in overlay.tests {
+        match base.tests.iter_mut().find(|seen| seen.name == test.name) {
+            Some(seen) => *seen = test,
+            None => base.tests.push(test),
+        }
+    }
+    base.parallel = overlay.parallel.or(base.parallel);
+    base
+}
+
+fn merge_services(mut base: Vec<ServiceDef>, overlay: Vec<ServiceDef>) -> Vec<ServiceDef> {
+    for service in overlay {
+        match base.iter_mut().find(|seen| seen.name == service.name) {
+            Some(seen) => *seen = service,
+            None => base.push(service),
+        }
+    }
+    base
+}
+
+fn merge_env(base: Env, overlay: Env) -> Env {
+    let mut envvar = base.envvar;
+    for var in overlay.envvar {
+        match envvar.iter_mut().find(|seen| seen.name == var.name) {
+            Some(seen) => *seen = var,
+            None => envvar.push(var),
+        }
+    }
+    Env {
+        dotenv: concat_unique(base.dotenv, overlay.dotenv),
+        envvar,
+    }
+}
+
+fn concat_unique(mut base: Vec<String>, overlay: Vec<String>) -> Vec<String> {
+    for item in overlay {
+        if !base.contains(&item) {
+            base.push(item);
+        }
+    }
+    base
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::values::ShellName;
+
+    fn definition_from_yaml(yaml: &str) -> DevShellDefinition {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn default_shell(config: &DevShellDefinition) -> &DevShell {
+        config.shell(&ShellName::default()).unwrap()
+    }
+
+    #[test]
+    fn concatenates_and_deduplicates_package_lists() {
+        // Arrange
+        let base = definition_from_yaml(
+            "devShell:\n  package:\n    stable:\n      - name: git\n      - name: jq\n",
+        );
+        let overlay = definition_from_yaml(
+            "devShell:\n  package:\n    stable:\n      - name: jq\n      - name: python312\n",
+        );
+
+        // Act
+        let merged = merge_definitions(base, overlay).unwrap();
+
+        // Assert
+        let names: Vec<&str> = default_shell(&merged)
+            .package
+            .stable
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["git", "jq", "python312"]);
+    }
+
+    #[test]
+    fn combines_with_sub_packages_of_a_shared_package() {
+        // Arrange
+        let base = definition_from_yaml(
+            "devShell:\n  package:\n    stable:\n      - name: python312\n        with: [requests]\n",
+        );
+        let overlay = definition_from_yaml(
+            "devShell:\n  package:\n    stable:\n      - name: python312\n        with: [numpy, requests]\n",
+        );
+
+        // Act
+        let merged = merge_definitions(base, overlay).unwrap();
+
+        // Assert
+        let stable = &default_shell(&merged).package.stable;
+        assert_eq!(stable.len(), 1);
+        let subs: Vec<&str> = stable[0]
+            .with_packages
+            .iter()
+            .map(|sub| sub.as_str())
+            .collect();
+        assert_eq!(subs, vec!["requests", "numpy"]);
+    }
+
+    #[test]
+    fn takes_the_overlay_platforms_of_a_shared_package() {
+        // Arrange
+        let base = definition_from_yaml(
+            "devShell:\n  package:\n    stable:\n      - name: valgrind\n        platforms: [x86_64-linux]\n      - name: gdb\n        platforms: [x86_64-linux]\n    pinned:\n      - name: go\n        version: 1.22.0\n",
+        );
+        let overlay = definition_from_yaml(
+            "devShell:\n  package:\n    stable:\n      - name: valgrind\n        platforms: [x86_64-linux, aarch64-linux]\n      - name: gdb\n    pinned:\n      - name: go\n        version: 1.22.0\n        platforms: [aarch64-darwin]\n",
+        );
+
+        // Act
+        let merged = merge_definitions(base, overlay).unwrap();
+
+        // Assert
+        let package = &default_shell(&merged).package;
+        assert!(package.stable[0].is_enabled_on("aarch64-linux"));
+        assert!(!package.stable[0].is_enabled_on("aarch64-darwin"));
+        assert!(package.stable[1].platforms.is_empty());
+        assert_eq!(package.pinned.len(), 1);
+        assert!(package.pinned[0].is_enabled_on("aarch64-darwin"));
+        assert!(!package.pinned[0].is_enabled_on("x86_64-linux"));
+    }
+
+    #[test]
+    fn overrides_env_vars_and_tasks_by_key() {
+        // Arrange
+        let base = definition_from_yaml(
+            r#"
+devShell:
+  package:
+    stable: []
+  env:
+    envvar:
+      - name: LOG_LEVEL
+        value: info
+      - name: REGION
+        value: eu
+  task:
+    lint:
+      commands:
+        - shellcheck *.sh
+    fmt:
+      commands:
+        - shfmt -w .
+"#,
+        );
+        let overlay = definition_from_yaml(
+            r#"
+devShell:
+  package:
+    stable: []
+  env:
+    envvar:
+      - name: LOG_LEVEL
+        value: debug
+  task:
+    lint:
+      commands:
+        - cargo clippy
+"#,
+        );
+
+        // Act
+        let merged = merge_definitions(base, overlay).unwrap();
+
+        // Assert
+        let shell = default_shell(&merged);
+        let envvar = &shell.env.as_ref().unwrap().envvar;
+        assert_eq!(envvar.len(), 2);
+        assert_eq!(envvar[0].name.as_str(), "LOG_LEVEL");
+        assert_eq!(envvar[0].value, "debug");
+        assert_eq!(envvar[1].value, "eu");
+        let tasks = shell.task.as_ref().unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(
+            tasks[&"lint".parse().unwrap()].commands,
+            vec!["cargo clippy"]
+        );
+    }
+
+    #[test]
+    fn overrides_services_by_name_keeping_their_order() {
+        // Arrange
+        let base = definition_from_yaml(
+            "devShell:\n  package:\n    stable: []\n  services:\n    - name: db\n      command: postgres\n    - name: cache\n      command: redis-server\n",
+        );
+        let overlay = definition_from_yaml(
+            "devShell:\n  package:\n    stable: []\n  services:\n    - name: db\n      command: postgres -p 5433\n    - name: mail\n      command: mailpit\n",
+        );
+
+        // Act
+        let merged = merge_definitions(base, overlay).unwrap();
+
+        // Assert
+        let commands: Vec<&str> = default_shell(&merged)
+            .services
+            .iter()
+            .map(|service| service.command.as_str())
+            .collect();
+        assert_eq!(
+            commands,
+            vec!["postgres -p 5433", "redis-server", "mailpit"]
+        );
+    }
+
+    #[test]
+    fn keeps_shells_that_only_one_layer_defines() {
+        // Arrange
+        let base = definition_from_yaml("devShell:\n  package:\n    stable:\n      - name: git\n");
+        let overlay = definition_from_yaml(
+            "devShells:\n  docs:\n    package:\n      stable:\n        - name: mdbook\n",
+        );
+
+        // Act
+        let merged = merge_definitions(base, overlay).unwrap();
+
+        // Assert
+        let names: Vec<&str> = merged.dev_shells.keys().map(ShellName::as_str).collect();
+        assert_eq!(names, vec!["default", "docs"]);
+    }
+
+    #[test]
+    fn rejects_package_pinned_to_different_versions() {
+        // Arrange
+        let base = definition_from_yaml(
+            "devShell:\n  package:\n    pinned:\n      - name: go\n        version: \"1.21.13\"\n",
+        );
+        let overlay = definition_from_yaml(
+            "devShell:\n  package:\n    pinned:\n      - name: go\n        version: \"1.22.0\"\n",
+        );
+
+        // Act
+        let result = merge_definitions(base, overlay);
+
+        // Assert
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("'go'"), "got: {message}");
+        assert!(message.contains("1.21.13"), "got: {message}");
+    }
+
+    #[test]
+    fn allow_unfree_holds_only_when_both_layers_allow_it() {
+        // Arrange
+        let base =
+            definition_from_yaml("devShell:\n  allowUnfree: false\n  package:\n    stable: []\n");
+        let overlay = definition_from_yaml("devShell:\n  package:\n    stable: []\n");
+
+        // Act
+        let merged = merge_definitions(base, overlay).unwrap();
+
+        // Assert
+        assert!(!default_shell(&merged).allow_unfree);
+    }
+}
@@ -6,14 +6,28 @@
 
 mod dev_shell;
 mod env;
+mod input;
+mod merge;
+mod nixpkgs_config;
+mod overlay;
 mod package;
+mod rebase;
+mod service;
 mod settings;
 mod task;
+mod test;
 mod validate;
 
 pub use dev_shell::{DevShell, DevShellDefinition};
-pub use env::{Env, EnvVar};
+pub use env::{Env, EnvVar, EnvVarOp};
+pub use input::FlakeInput;
+pub use merge::merge_definitions;
+pub use nixpkgs_config::NixpkgsConfig;
+pub use overlay::Overlay;
 pub use package::{Package, PackageEntry, PinnedPackageEntry};
+pub use rebase::rebase_paths;
+pub use service::{ReadyCheck, ReadyProbe, ServiceDef};
 pub use settings::Settings;
-pub use task::TaskDef;
+pub use task::{FailurePolicy, TaskArg, TaskDef};
+pub use test::{TestDef, TestSuite};
 pub use validate::validate_config;
@@ -0,0 +1,77 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::values::PackageName;
+
+/// The `nixpkgsConfig` section: nixpkgs `config` options beyond
+/// `allowUnfree`, applied to every nixpkgs import of the generated
+/// flake. Unset options keep the nixpkgs defaults and are not rendered.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct NixpkgsConfig {
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_broken: bool,
+
+    /// Insecure derivations to allow, by `<pname>-<version>` as nixpkgs
+    /// reports them (e.g. `openssl-1.1.1w`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub permitted_insecure_packages: Vec<PackageName>,
+
+    /// Unfree packages to allow by name, rendered as an
+    /// `allowUnfreePredicate`. Only takes effect with `allowUnfree: false`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow_unfree_packages: Vec<PackageName>,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cuda_support: bool,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub rocm_support: bool,
+}
+
+impl NixpkgsConfig {
+    pub(crate) fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_every_option() {
+        // Arrange
+        let yaml = r#"
+allowBroken: true
+permittedInsecurePackages:
+  - openssl-1.1.1w
+allowUnfreePackages:
+  - vscode
+cudaSupport: true
+"#;
+
+        // Act
+        let config: NixpkgsConfig = serde_yaml::from_str(yaml).unwrap();
+
+        // Assert
+        assert!(config.allow_broken);
+        assert_eq!(
+            config.permitted_insecure_packages[0].as_str(),
+            "openssl-1.1.1w"
+        );
+        assert_eq!(config.allow_unfree_packages[0].as_str(), "vscode");
+        assert!(config.cuda_support);
+        assert!(!config.rocm_support);
+    }
+
+    #[test]
+    fn empty_section_is_the_default() {
+        // Arrange / Act
+        let config: NixpkgsConfig = serde_yaml::from_str("{}").unwrap();
+
+        // Assert
+        assert!(config.is_default());
+    }
+}
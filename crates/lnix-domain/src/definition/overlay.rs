@@ -0,0 +1,115 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ValidationError;
+use crate::values::{InputName, NixFilePath, OverlayName};
+
+/// A nixpkgs overlay applied to every nixpkgs import of the generated
+/// flake (stable, unstable, and pinned).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema, Serialize)]
+#[serde(try_from = "RawOverlay", into = "RawOverlay")]
+pub enum Overlay {
+    /// `<from>.overlays.<name>`, exported by a custom input.
+    Input { from: InputName, name: OverlayName },
+    /// `import <path>`, a local `.nix` file next to `flake.nix`.
+    File(NixFilePath),
+}
+
+/// The overlay as written: exactly one of `from` or `path` is set.
+#[derive(Deserialize, JsonSchema, Serialize)]
+#[schemars(deny_unknown_fields)]
+struct RawOverlay {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    from: Option<InputName>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<OverlayName>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    path: Option<NixFilePath>,
+}
+
+impl TryFrom<RawOverlay> for Overlay {
+    type Error = ValidationError;
+
+    fn try_from(raw: RawOverlay) -> Result<Self, Self::Error> {
+        match (raw.from, raw.name, raw.path) {
+            (Some(from), name, None) => Ok(Self::Input {
+                from,
+                name: name.unwrap_or_default(),
+            }),
+            (None, None, Some(path)) => Ok(Self::File(path)),
+            _ => Err(ValidationError::AmbiguousOverlay),
+        }
+    }
+}
+
+impl From<Overlay> for RawOverlay {
+    fn from(overlay: Overlay) -> Self {
+        match overlay {
+            Overlay::Input { from, name } => Self {
+                from: Some(from),
+                name: Some(name),
+                path: None,
+            },
+            Overlay::File(path) => Self {
+                from: None,
+                name: None,
+                path: Some(path),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_input_overlay_with_default_name() {
+        // Arrange
+        let yaml = "from: rust-overlay\n";
+
+        // Act
+        let overlay: Overlay = serde_yaml::from_str(yaml).unwrap();
+
+        // Assert
+        assert_eq!(
+            overlay,
+            Overlay::Input {
+                from: "rust-overlay".parse().unwrap(),
+                name: OverlayName::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn deserializes_file_overlay() {
+        // Arrange
+        let yaml = "path: ./nix/overlay.nix\n";
+
+        // Act
+        let overlay: Overlay = serde_yaml::from_str(yaml).unwrap();
+
+        // Assert
+        assert_eq!(overlay, Overlay::File("./nix/overlay.nix".parse().unwrap()));
+    }
+
+    #[test]
+    fn rejects_overlay_with_both_or_neither_source() {
+        // Arrange
+        let ambiguous = [
+            "from: rust-overlay\npath: ./overlay.nix\n",
+            "name: default\n",
+            "path: ./overlay.nix\nname: default\n",
+        ];
+
+        // Act & Assert
+        for yaml in ambiguous {
+            let message = serde_yaml::from_str::<Overlay>(yaml)
+                .unwrap_err()
+                .to_string();
+            assert!(message.contains("exactly one of"), "got: {message}");
+        }
+    }
+}
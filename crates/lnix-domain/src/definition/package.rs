@@ -1,10 +1,12 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::values::{PackageName, PackageVersion};
+use crate::values::{InputName, NixSystem, PackageName, PackageVersion};
 
 /// Packages requested for the dev shell, grouped by nixpkgs channel.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
 #[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
 pub struct Package {
     #[serde(default)]
     pub stable: Vec<PackageEntry>,
@@ -16,31 +18,86 @@ pub struct Package {
     pub pinned: Vec<PinnedPackageEntry>,
 }
 
-/// A package resolved from a channel (stable or unstable).
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// A package resolved from a channel (stable or unstable), or from a
+/// custom flake input when `from` is set.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
 #[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
 pub struct PackageEntry {
+    /// Attribute path of the package: relative to the channel, or to
+    /// `packages.<system>` of the `from` input.
     pub name: PackageName,
+
+    /// Name of an entry of `inputs` the package comes from instead of
+    /// the channel it is listed under.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<InputName>,
+
+    /// Sub-packages bundled through `withPackages` (e.g. `requests` for
+    /// `python312`), as attribute paths in the package's own set.
+    #[serde(rename = "with", default, skip_serializing_if = "Vec::is_empty")]
+    pub with_packages: Vec<PackageName>,
+
+    /// Systems the package is installed on. Empty means every system.
+    #[serde(default, alias = "systems", skip_serializing_if = "Vec::is_empty")]
+    pub platforms: Vec<NixSystem>,
+}
+
+impl PackageEntry {
+    /// Whether the package is installed on `system`.
+    pub fn is_enabled_on(&self, system: &str) -> bool {
+        is_enabled_on(&self.platforms, system)
+    }
+
+    /// Each `with` sub-package as a nixpkgs attribute path,
+    /// `<name>.pkgs.<sub>`: the set `withPackages` hands to its function.
+    pub fn sub_package_attrs(&self) -> Vec<PackageName> {
+        self.with_packages
+            .iter()
+            .map(|sub| {
+                PackageName::try_from(format!("{}.pkgs.{}", self.name, sub))
+                    .expect("valid names joined by single dots stay valid")
+            })
+            .collect()
+    }
 }
 
 /// A package pinned to a specific version via nix-versions.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
 #[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
 pub struct PinnedPackageEntry {
     pub name: PackageName,
     pub version: PackageVersion,
 
+    /// Systems the package is installed on. Empty means every system.
+    #[serde(default, alias = "systems", skip_serializing_if = "Vec::is_empty")]
+    pub platforms: Vec<NixSystem>,
+
     /// nixpkgs commit hash. Deserialized for backwards compatibility
     /// only; never serialized because `flake.nix` owns the SSoT.
     #[serde(default, skip_serializing)]
+    #[schemars(description = "Ignored; written by older LazyNix versions.")]
     pub resolved_commit: Option<String>,
 
     /// Nix attribute path (e.g., `go_1_21`). Deserialized for
     /// backwards compatibility only; never serialized.
     #[serde(default, skip_serializing)]
+    #[schemars(description = "Ignored; written by older LazyNix versions.")]
     pub resolved_attr: Option<String>,
 }
 
+impl PinnedPackageEntry {
+    /// Whether the package is installed on `system`.
+    pub fn is_enabled_on(&self, system: &str) -> bool {
+        is_enabled_on(&self.platforms, system)
+    }
+}
+
+fn is_enabled_on(platforms: &[NixSystem], system: &str) -> bool {
+    platforms.is_empty() || platforms.iter().any(|p| p.as_str() == system)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,6 +166,7 @@ version: ""
         let pinned = PinnedPackageEntry {
             name: "go".parse().unwrap(),
             version: "1.21.13".parse().unwrap(),
+            platforms: Vec::new(),
             resolved_commit: Some("5ed6275".to_string()),
             resolved_attr: None,
         };
@@ -126,6 +184,7 @@ version: ""
         let pinned = PinnedPackageEntry {
             name: "go".parse().unwrap(),
             version: "1.21.13".parse().unwrap(),
+            platforms: Vec::new(),
             resolved_commit: None,
             resolved_attr: Some("go_1_21".to_string()),
         };
@@ -143,6 +202,7 @@ version: ""
         let pinned = PinnedPackageEntry {
             name: "go".parse().unwrap(),
             version: "1.21.13".parse().unwrap(),
+            platforms: Vec::new(),
             resolved_commit: Some("5ed6275".to_string()),
             resolved_attr: Some("go_1_21".to_string()),
         };
@@ -189,4 +249,84 @@ resolvedAttr: "go_1_21"
         assert_eq!(pinned.resolved_commit.as_deref(), Some("5ed6275"));
         assert_eq!(pinned.resolved_attr.as_deref(), Some("go_1_21"));
     }
+
+    #[test]
+    fn deserializes_platforms_and_systems_alias() {
+        // Arrange
+        let yaml = r#"
+stable:
+  - name: inotify-tools
+    platforms: [x86_64-linux, aarch64-linux]
+pinned:
+  - name: go
+    version: "1.21.13"
+    systems: [aarch64-darwin]
+"#;
+
+        // Act
+        let package: Package = serde_yaml::from_str(yaml).unwrap();
+
+        // Assert
+        let inotify = &package.stable[0];
+        assert!(inotify.is_enabled_on("x86_64-linux"));
+        assert!(!inotify.is_enabled_on("aarch64-darwin"));
+        assert!(package.pinned[0].is_enabled_on("aarch64-darwin"));
+        assert!(!package.pinned[0].is_enabled_on("x86_64-linux"));
+    }
+
+    #[test]
+    fn entry_without_platforms_is_enabled_everywhere() {
+        // Arrange
+        let entry: PackageEntry = serde_yaml::from_str("name: git\n").unwrap();
+
+        // Act & Assert
+        assert!(entry.is_enabled_on("x86_64-linux"));
+        assert!(entry.is_enabled_on("aarch64-darwin"));
+    }
+
+    #[test]
+    fn rejects_invalid_platform_at_parse_time() {
+        // Arrange
+        let yaml = "name: git\nplatforms: [linux]\n";
+
+        // Act
+        let result = serde_yaml::from_str::<PackageEntry>(yaml);
+
+        // Assert
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("Invalid system"), "got: {message}");
+    }
+
+    #[test]
+    fn deserializes_package_from_custom_input() {
+        // Arrange
+        let yaml = "name: stable.toolchain\nfrom: fenix\n";
+
+        // Act
+        let entry: PackageEntry = serde_yaml::from_str(yaml).unwrap();
+
+        // Assert
+        assert_eq!(entry.name.as_str(), "stable.toolchain");
+        assert_eq!(entry.from.as_ref().map(InputName::as_str), Some("fenix"));
+    }
+
+    #[test]
+    fn deserializes_with_sub_packages() {
+        // Arrange
+        let yaml = "name: python312\nwith: [requests, numpy]\n";
+
+        // Act
+        let entry: PackageEntry = serde_yaml::from_str(yaml).unwrap();
+
+        // Assert
+        let attrs: Vec<String> = entry
+            .sub_package_attrs()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            attrs,
+            vec!["python312.pkgs.requests", "python312.pkgs.numpy"]
+        );
+    }
 }
@@ -0,0 +1,151 @@
+use crate::definition::dev_shell::DevShellDefinition;
+use crate::definition::env::Env;
+use crate::definition::overlay::Overlay;
+use crate::values::NixFilePath;
+
+/// Rewrites the relative paths of `definition`, a file that another
+/// one `extends`, so that they are relative to the extending file's
+/// directory instead of its own: `dir` is where it lives, relative to
+/// that directory.
+///
+/// Covers overlay `path`s, `shellAlias` and every `dotenv`, and the
+/// `dir` of tasks, tests, and services. A task without a `dir` has its
+/// `sources` and `generates` globs rebased instead, as they would
+/// otherwise be relative to the extending file. Absolute and `~/`
+/// paths, and commands, are left as written.
+pub fn rebase_paths(definition: &mut DevShellDefinition, dir: &str) {
+    let dir = dir.trim_end_matches('/');
+    if dir.is_empty() || dir == "." {
+        return;
+    }
+    for overlay in &mut definition.overlays {
+        if let Overlay::File(path) = overlay
+            && let Ok(rebased) = NixFilePath::try_from(rebase(path.as_str(), dir))
+        {
+            *path = rebased;
+        }
+    }
+    for dev_shell in definition.dev_shells.values_mut() {
+        rebase_all(&mut dev_shell.shell_alias, dir);
+        rebase_env(&mut dev_shell.env, dir);
+        for task in dev_shell
+            .task
+            .iter_mut()
+            .flat_map(|tasks| tasks.values_mut())
+        {
+            rebase_env(&mut task.env, dir);
+            rebase_all(&mut task.dotenv, dir);
+            if task.dir.is_some() {
+                rebase_dir(&mut task.dir, dir);
+            } else {
+                rebase_all(&mut task.sources, dir);
+                rebase_all(&mut task.generates, dir);
+            }
+        }
+        for test in &mut dev_shell.test.tests {
+            rebase_env(&mut test.env, dir);
+            rebase_dir(&mut test.dir, dir);
+        }
+        for service in &mut dev_shell.services {
+            rebase_env(&mut service.env, dir);
+            rebase_dir(&mut service.dir, dir);
+        }
+    }
+}
+
+fn rebase_dir(path: &mut Option<String>, dir: &str) {
+    if let Some(path) = path {
+        *path = rebase(path, dir);
+    }
+}
+
+fn rebase_env(env: &mut Option<Env>, dir: &str) {
+    if let Some(env) = env {
+        rebase_all(&mut env.dotenv, dir);
+    }
+}
+
+fn rebase_all(paths: &mut [String], dir: &str) {
+    for path in paths {
+        *path = rebase(path, dir);
+    }
+}
+
+/// `path` joined onto `dir`, unless it is absolute or `~/`-relative.
+fn rebase(path: &str, dir: &str) -> String {
+    if path.starts_with('/') || path.starts_with("~/") {
+        return path.to_string();
+    }
+    let mut path = path;
+    while let Some(rest) = path.strip_prefix("./") {
+        path = rest;
+    }
+    match path {
+        "" | "." => dir.to_string(),
+        _ => format!("{dir}/{path}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::values::ShellName;
+
+    #[test]
+    fn rebases_relative_paths_onto_the_extended_files_dir() {
+        // Arrange
+        let mut definition: DevShellDefinition = serde_yaml::from_str(
+            r#"
+overlays:
+  - path: ./nix/overlay.nix
+devShell:
+  package:
+    stable: []
+  shellAlias: [aliases.sh, ~/.aliases]
+  env:
+    dotenv: [.env, /etc/lazynix.env]
+  task:
+    gen:
+      commands: [make gen]
+      dotenv: [.env.gen]
+      sources: ["api/*.graphql"]
+    web:
+      commands: [npm test]
+      dir: web
+      sources: ["src/**"]
+  test:
+    - name: unit
+      command: cargo test
+  services:
+    - name: db
+      command: postgres
+      dir: ./db
+"#,
+        )
+        .unwrap();
+
+        // Act
+        rebase_paths(&mut definition, "shared");
+
+        // Assert
+        assert_eq!(
+            definition.overlays,
+            vec![Overlay::File("shared/nix/overlay.nix".parse().unwrap())]
+        );
+        let shell = &definition.dev_shells[&ShellName::default()];
+        assert_eq!(shell.shell_alias, vec!["shared/aliases.sh", "~/.aliases"]);
+        assert_eq!(
+            shell.env.as_ref().unwrap().dotenv,
+            vec!["shared/.env", "/etc/lazynix.env"]
+        );
+        let tasks = shell.task.as_ref().unwrap();
+        let gen_task = &tasks[&"gen".parse().unwrap()];
+        assert_eq!(gen_task.dotenv, vec!["shared/.env.gen"]);
+        assert_eq!(gen_task.sources, vec!["shared/api/*.graphql"]);
+        let web = &tasks[&"web".parse().unwrap()];
+        assert_eq!(web.dir.as_deref(), Some("shared/web"));
+        assert_eq!(web.sources, vec!["src/**"]);
+        assert_eq!(shell.test.tests[0].dir, None);
+        assert_eq!(shell.services[0].dir.as_deref(), Some("shared/db"));
+    }
+}
@@ -0,0 +1,161 @@
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::definition::env::Env;
+use crate::error::ValidationError;
+use crate::values::{ServiceName, Timeout};
+
+/// How long `lnix up` waits for a service to become ready when its
+/// probe sets no `timeout`.
+const DEFAULT_READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// One entry of the `services:` list: a long-running process that
+/// `lnix up` starts in the background inside the dev environment and
+/// `lnix down` stops.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ServiceDef {
+    pub name: ServiceName,
+
+    /// Run with bash; it should stay in the foreground. Its data
+    /// directory is passed as `$LAZYNIX_SERVICE_DIR`.
+    pub command: String,
+
+    /// How `lnix up` tells that the service accepts work. Without one,
+    /// the service counts as ready once started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ready: Option<ReadyProbe>,
+
+    /// Directory the command runs in, relative to `lazynix.yaml`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+
+    /// Dotenv files and env vars for this service only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<Env>,
+}
+
+/// A service's readiness probe: a check, retried until it passes or
+/// `timeout` runs out.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema, Serialize)]
+#[serde(try_from = "RawReadyProbe", into = "RawReadyProbe")]
+pub struct ReadyProbe {
+    pub check: ReadyCheck,
+    pub timeout: Option<Timeout>,
+}
+
+impl ReadyProbe {
+    /// How long to keep probing: `timeout`, or 30 seconds.
+    pub fn time_limit(&self) -> Duration {
+        self.timeout
+            .as_ref()
+            .map_or(DEFAULT_READY_TIMEOUT, Timeout::as_duration)
+    }
+}
+
+/// What a [`ReadyProbe`] checks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadyCheck {
+    /// A TCP connection to this port on localhost succeeds.
+    Port(u16),
+    /// This command, run inside the dev environment, exits 0.
+    Command(String),
+}
+
+/// The probe as written: exactly one of `port` or `command` is set.
+#[derive(Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+struct RawReadyProbe {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    port: Option<u16>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    command: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timeout: Option<Timeout>,
+}
+
+impl TryFrom<RawReadyProbe> for ReadyProbe {
+    type Error = ValidationError;
+
+    fn try_from(raw: RawReadyProbe) -> Result<Self, Self::Error> {
+        let check = match (raw.port, raw.command) {
+            (Some(port), None) => ReadyCheck::Port(port),
+            (None, Some(command)) => ReadyCheck::Command(command),
+            _ => return Err(ValidationError::AmbiguousReadyProbe),
+        };
+        Ok(Self {
+            check,
+            timeout: raw.timeout,
+        })
+    }
+}
+
+impl From<ReadyProbe> for RawReadyProbe {
+    fn from(probe: ReadyProbe) -> Self {
+        let (port, command) = match probe.check {
+            ReadyCheck::Port(port) => (Some(port), None),
+            ReadyCheck::Command(command) => (None, Some(command)),
+        };
+        Self {
+            port,
+            command,
+            timeout: probe.timeout,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_port_and_command_probes() {
+        // Arrange
+        let yaml = r#"
+- name: postgres
+  command: postgres -D "$LAZYNIX_SERVICE_DIR"
+  ready:
+    port: 5432
+    timeout: 1m
+- name: redis
+  command: redis-server
+  ready:
+    command: redis-cli ping
+"#;
+
+        // Act
+        let services: Vec<ServiceDef> = serde_yaml::from_str(yaml).unwrap();
+
+        // Assert
+        let postgres = services[0].ready.as_ref().unwrap();
+        assert_eq!(postgres.check, ReadyCheck::Port(5432));
+        assert_eq!(postgres.time_limit(), Duration::from_secs(60));
+        let redis = services[1].ready.as_ref().unwrap();
+        assert_eq!(
+            redis.check,
+            ReadyCheck::Command("redis-cli ping".to_string())
+        );
+        assert_eq!(redis.time_limit(), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn rejects_probe_without_exactly_one_check() {
+        // Arrange
+        let yamls = [
+            "name: db\ncommand: postgres\nready:\n  timeout: 10s\n",
+            "name: db\ncommand: postgres\nready:\n  port: 5432\n  command: pg_isready\n",
+        ];
+
+        // Act & Assert
+        for yaml in yamls {
+            let message = serde_yaml::from_str::<ServiceDef>(yaml)
+                .unwrap_err()
+                .to_string();
+            assert!(message.contains("exactly one of"), "got: {message}");
+        }
+    }
+}
@@ -1,12 +1,14 @@
 use crate::values::RegistryUrl;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Optional per-user settings from `lazynix-settings.yaml`.
 ///
 /// URL validation is handled by [`RegistryUrl`] during deserialization,
 /// so a successfully parsed `Settings` is always valid.
-#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Deserialize, JsonSchema, Serialize, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct Settings {
     pub override_stable_package: Option<RegistryUrl>,
 }
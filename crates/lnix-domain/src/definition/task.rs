@@ -1,11 +1,98 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-/// A user-defined task: a description and the commands to run.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+use crate::definition::env::Env;
+use crate::values::{TaskArgName, TaskName};
+
+/// A user-defined task: a description, the commands to run (in order or
+/// concurrently), the tasks that must run before it, the environment it
+/// runs in, the arguments it accepts, and the files that decide whether
+/// it is up to date.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
 #[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
 pub struct TaskDef {
     #[serde(default)]
     pub description: Option<String>,
 
     pub commands: Vec<String>,
+
+    /// Tasks of the same dev shell that run first, in this order, each
+    /// after its own `deps`. A task runs once even when several others
+    /// depend on it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deps: Vec<TaskName>,
+
+    /// Env vars and dotenv files for this task only, on top of the dev
+    /// shell's own `env`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<Env>,
+
+    /// Dotenv files for this task only; shorthand for `env.dotenv`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dotenv: Vec<String>,
+
+    /// Directory the task runs in, relative to `lazynix.yaml`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+
+    /// Arguments taken from the command line, in positional order or
+    /// as `--<name> <value>`. Words left over fill `{{.CLI_ARGS}}`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<TaskArg>,
+
+    /// Run `commands` concurrently instead of one after another, with
+    /// each line of output prefixed by the command it came from.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub parallel: bool,
+
+    /// What a `parallel` task does when one of its commands fails.
+    #[serde(default, skip_serializing_if = "is_fail_fast")]
+    pub on_failure: FailurePolicy,
+
+    /// Globs (relative to `dir`) of the files the task reads. When set,
+    /// the task is skipped while they and its commands are unchanged
+    /// since its last successful run.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<String>,
+
+    /// Globs (relative to `dir`) of the files the task produces; the
+    /// task is never up to date while one of them matches nothing.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub generates: Vec<String>,
+}
+
+/// How a `parallel` task reacts to a failing command.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FailurePolicy {
+    /// Stop the other commands as soon as one fails.
+    #[default]
+    FailFast,
+    /// Let every command finish, then report all failures.
+    WaitAll,
+}
+
+fn is_fail_fast(policy: &FailurePolicy) -> bool {
+    *policy == FailurePolicy::FailFast
+}
+
+/// A task argument, referenced in commands as `{{.ARGS.<name>}}`.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct TaskArg {
+    pub name: TaskArgName,
+
+    /// Used when the argument is not given on the command line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+
+    /// Fail when the argument is neither given nor defaulted.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub required: bool,
+
+    /// One-line description shown by `lnix task <name> --help`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
 }
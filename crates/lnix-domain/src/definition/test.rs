@@ -0,0 +1,346 @@
+use std::fmt;
+use std::num::NonZeroUsize;
+
+use schemars::JsonSchema;
+use serde::de::value::{MapAccessDeserializer, SeqAccessDeserializer};
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::definition::env::Env;
+use crate::values::Timeout;
+
+/// A shell's `test:` section: its tests, plus how many may run at once.
+///
+/// Written either as the list of tests or, to set `parallel`, as a map
+/// with `parallel` and `tests`.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema, Serialize)]
+#[serde(from = "RawTestSuite", into = "RawTestSuite")]
+pub struct TestSuite {
+    /// How many tests `lnix test` runs concurrently (`--jobs`
+    /// overrides it). One at a time when unset.
+    pub parallel: Option<NonZeroUsize>,
+
+    pub tests: Vec<TestDef>,
+}
+
+impl TestSuite {
+    pub fn is_empty(&self) -> bool {
+        self.tests.is_empty()
+    }
+}
+
+/// The suite as written: a bare list or a map with settings.
+///
+/// Dispatched on the YAML shape rather than `untagged`, so a mistake
+/// inside the map is reported as itself instead of "did not match any
+/// variant".
+#[derive(JsonSchema, Serialize)]
+#[serde(untagged)]
+enum RawTestSuite {
+    List(Vec<TestDef>),
+    Detailed(DetailedTestSuite),
+}
+
+impl<'de> Deserialize<'de> for RawTestSuite {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SuiteVisitor;
+
+        impl<'de> Visitor<'de> for SuiteVisitor {
+            type Value = RawTestSuite;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a list of tests or a map with `tests`")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+                Vec::deserialize(SeqAccessDeserializer::new(seq)).map(RawTestSuite::List)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                DetailedTestSuite::deserialize(MapAccessDeserializer::new(map))
+                    .map(RawTestSuite::Detailed)
+            }
+        }
+
+        deserializer.deserialize_any(SuiteVisitor)
+    }
+}
+
+#[derive(Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+struct DetailedTestSuite {
+    /// How many tests `lnix test` runs at once (its `--jobs` wins when
+    /// both are set).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    parallel: Option<NonZeroUsize>,
+
+    #[serde(default)]
+    tests: Vec<TestDef>,
+}
+
+impl From<RawTestSuite> for TestSuite {
+    fn from(raw: RawTestSuite) -> Self {
+        match raw {
+            RawTestSuite::List(tests) => Self {
+                parallel: None,
+                tests,
+            },
+            RawTestSuite::Detailed(detailed) => Self {
+                parallel: detailed.parallel,
+                tests: detailed.tests,
+            },
+        }
+    }
+}
+
+impl From<TestSuite> for RawTestSuite {
+    fn from(suite: TestSuite) -> Self {
+        match suite.parallel {
+            None => Self::List(suite.tests),
+            parallel => Self::Detailed(DetailedTestSuite {
+                parallel,
+                tests: suite.tests,
+            }),
+        }
+    }
+}
+
+/// One entry of the `test:` list: a command run by `lnix test`.
+///
+/// Written either as a plain command string or as a map with a `name`
+/// and per-test settings; a plain string is its own name.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
+#[serde(from = "RawTestDef", into = "RawTestDef")]
+pub struct TestDef {
+    /// Shown in results and matched by `lnix test <name-or-glob>`.
+    pub name: String,
+
+    pub command: String,
+
+    /// The test fails when it runs longer than this.
+    pub timeout: Option<Timeout>,
+
+    /// Directory the command runs in, relative to `lazynix.yaml`.
+    pub dir: Option<String>,
+
+    /// Dotenv files and env vars for this test only.
+    pub env: Option<Env>,
+
+    /// Report a failure without failing the run.
+    pub allow_failure: bool,
+}
+
+impl TestDef {
+    /// Whether this entry can be written back as a plain string.
+    fn is_plain(&self) -> bool {
+        self.name == self.command
+            && self.timeout.is_none()
+            && self.dir.is_none()
+            && self.env.is_none()
+            && !self.allow_failure
+    }
+}
+
+/// The test as written: a bare command or a detailed map, told apart
+/// by shape like [`RawTestSuite`].
+#[derive(JsonSchema, Serialize)]
+#[serde(untagged)]
+enum RawTestDef {
+    Command(String),
+    Detailed(DetailedTestDef),
+}
+
+impl<'de> Deserialize<'de> for RawTestDef {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TestVisitor;
+
+        impl<'de> Visitor<'de> for TestVisitor {
+            type Value = RawTestDef;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a test command or a map with `command`")
+            }
+
+            fn visit_str<E: de::Error>(self, command: &str) -> Result<Self::Value, E> {
+                Ok(RawTestDef::Command(command.to_string()))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                DetailedTestDef::deserialize(MapAccessDeserializer::new(map))
+                    .map(RawTestDef::Detailed)
+            }
+        }
+
+        deserializer.deserialize_any(TestVisitor)
+    }
+}
+
+#[derive(Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct DetailedTestDef {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+
+    command: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timeout: Option<Timeout>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dir: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    env: Option<Env>,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    allow_failure: bool,
+}
+
+impl From<RawTestDef> for TestDef {
+    fn from(raw: RawTestDef) -> Self {
+        match raw {
+            RawTestDef::Command(command) => Self {
+                name: command.clone(),
+                command,
+                timeout: None,
+                dir: None,
+                env: None,
+                allow_failure: false,
+            },
+            RawTestDef::Detailed(detailed) => Self {
+                name: detailed.name.unwrap_or_else(|| detailed.command.clone()),
+                command: detailed.command,
+                timeout: detailed.timeout,
+                dir: detailed.dir,
+                env: detailed.env,
+                allow_failure: detailed.allow_failure,
+            },
+        }
+    }
+}
+
+impl From<TestDef> for RawTestDef {
+    fn from(test: TestDef) -> Self {
+        if test.is_plain() {
+            return Self::Command(test.command);
+        }
+        Self::Detailed(DetailedTestDef {
+            name: (test.name != test.command).then_some(test.name),
+            command: test.command,
+            timeout: test.timeout,
+            dir: test.dir,
+            env: test.env,
+            allow_failure: test.allow_failure,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_string_is_its_own_name() {
+        // Arrange
+        let yaml = "cargo test --workspace\n";
+
+        // Act
+        let test: TestDef = serde_yaml::from_str(yaml).unwrap();
+
+        // Assert
+        assert_eq!(test.name, "cargo test --workspace");
+        assert_eq!(test.command, "cargo test --workspace");
+        assert!(test.timeout.is_none());
+        assert!(!test.allow_failure);
+    }
+
+    #[test]
+    fn deserializes_detailed_test() {
+        // Arrange
+        let yaml = r#"
+name: e2e
+command: npm run e2e
+timeout: 10m
+dir: web
+env:
+  envvar:
+    - name: CI
+      value: "1"
+allowFailure: true
+"#;
+
+        // Act
+        let test: TestDef = serde_yaml::from_str(yaml).unwrap();
+
+        // Assert
+        assert_eq!(test.name, "e2e");
+        assert_eq!(test.command, "npm run e2e");
+        assert_eq!(test.timeout.unwrap().as_secs(), 600);
+        assert_eq!(test.dir.as_deref(), Some("web"));
+        assert_eq!(test.env.unwrap().envvar[0].name.as_str(), "CI");
+        assert!(test.allow_failure);
+    }
+
+    #[test]
+    fn serializes_plain_tests_back_as_strings() {
+        // Arrange
+        let tests: Vec<TestDef> =
+            serde_yaml::from_str("- pytest\n- name: lint\n  command: ruff check .\n").unwrap();
+
+        // Act
+        let yaml = serde_yaml::to_string(&tests).unwrap();
+
+        // Assert
+        assert_eq!(yaml, "- pytest\n- name: lint\n  command: ruff check .\n");
+    }
+
+    #[test]
+    fn suite_is_a_list_or_a_map_with_parallel() {
+        // Arrange
+        let list = "- pytest\n";
+        let detailed = "parallel: 4\ntests:\n  - pytest\n  - ruff check .\n";
+
+        // Act
+        let list: TestSuite = serde_yaml::from_str(list).unwrap();
+        let detailed: TestSuite = serde_yaml::from_str(detailed).unwrap();
+
+        // Assert
+        assert_eq!(list.parallel, None);
+        assert_eq!(list.tests.len(), 1);
+        assert_eq!(detailed.parallel, NonZeroUsize::new(4));
+        assert_eq!(detailed.tests[1].name, "ruff check .");
+        assert_eq!(
+            serde_yaml::to_string(&detailed).unwrap(),
+            "parallel: 4\ntests:\n- pytest\n- ruff check .\n"
+        );
+        assert!(serde_yaml::from_str::<TestSuite>("parallel: 0\ntests: []\n").is_err());
+    }
+
+    #[test]
+    fn reports_the_detailed_entry_error_instead_of_no_matching_variant() {
+        // Arrange
+        let bad_timeout = "tests:\n  - name: e2e\n    command: npm run e2e\n    timeout: 10x\n";
+        let misspelled = "- command: pytest\n  allowfailure: true\n";
+        let no_command = "- name: unit\n";
+
+        // Act
+        let bad_timeout = serde_yaml::from_str::<TestSuite>(bad_timeout).unwrap_err();
+        let misspelled = serde_yaml::from_str::<TestSuite>(misspelled).unwrap_err();
+        let no_command = serde_yaml::from_str::<TestSuite>(no_command).unwrap_err();
+
+        // Assert
+        let bad_timeout = bad_timeout.to_string();
+        assert!(bad_timeout.contains("10x"), "got: {bad_timeout}");
+        assert!(!bad_timeout.contains("did not match any variant"));
+        let misspelled = misspelled.to_string();
+        assert!(
+            misspelled.contains("unknown field `allowfailure`"),
+            "got: {misspelled}"
+        );
+        let no_command = no_command.to_string();
+        assert!(
+            no_command.contains("missing field `command`"),
+            "got: {no_command}"
+        );
+    }
+}
@@ -1,5 +1,19 @@
+use std::collections::HashSet;
+
 use crate::definition::dev_shell::DevShellDefinition;
+use crate::definition::overlay::Overlay;
 use crate::error::{Diagnostic, ValidationError};
+use crate::service::flake::pinned::{PINNED_BINDING_PREFIX, PINNED_INPUT_PREFIX};
+use crate::service::task::resolve_task_order;
+use crate::values::TaskName;
+
+/// Inputs the generated flake always declares; custom inputs may
+/// `follows` them.
+const BUILTIN_INPUTS: [&str; 3] = ["nixpkgs", "nixpkgs-unstable", "flake-utils"];
+
+/// Names already bound inside the generated `outputs` function, which a
+/// custom input would shadow (or be shadowed by).
+const BOUND_NAMES: [&str; 4] = ["self", "system", "stablePackages", "unstablePackages"];
 
 /// Checks cross-field constraints that value objects cannot express.
 ///
@@ -10,27 +24,126 @@ use crate::error::{Diagnostic, ValidationError};
 /// printing them, so this function stays free of I/O; the caller
 /// decides how to display them.
 pub fn validate_config(config: &DevShellDefinition) -> Result<Vec<Diagnostic>, ValidationError> {
-    if let Some(tasks) = &config.dev_shell.task {
-        for (task_name, task_def) in tasks {
-            if task_def.commands.is_empty() {
-                return Err(ValidationError::EmptyTaskCommands(
-                    task_name.as_str().to_string(),
+    let mut allow_unfree_values = config.dev_shells.values().map(|shell| shell.allow_unfree);
+    let first_allow_unfree = allow_unfree_values.next();
+    if allow_unfree_values.any(|value| Some(value) != first_allow_unfree) {
+        return Err(ValidationError::ConflictingAllowUnfree);
+    }
+    validate_inputs(config)?;
+
+    let mut diagnostics = Vec::new();
+    if first_allow_unfree == Some(true) && !config.nixpkgs_config.allow_unfree_packages.is_empty() {
+        diagnostics.push(Diagnostic::UnfreePackagesIgnored);
+    }
+    for (shell_name, dev_shell) in &config.dev_shells {
+        if let Some(tasks) = &dev_shell.task {
+            for (task_name, task_def) in tasks {
+                if task_def.commands.is_empty() {
+                    return Err(ValidationError::EmptyTaskCommands(
+                        task_name.as_str().to_string(),
+                    ));
+                }
+                let mut arg_names = HashSet::new();
+                for arg in &task_def.args {
+                    if !arg_names.insert(&arg.name) {
+                        return Err(ValidationError::DuplicateTaskArg {
+                            task: task_name.to_string(),
+                            arg: arg.name.to_string(),
+                        });
+                    }
+                }
+            }
+            // Sorted so that a cycle is always reported from the same task.
+            let mut task_names: Vec<&TaskName> = tasks.keys().collect();
+            task_names.sort();
+            for task_name in task_names {
+                resolve_task_order(tasks, task_name)?;
+            }
+        }
+
+        let mut test_names = HashSet::new();
+        for test in &dev_shell.test.tests {
+            if !test_names.insert(&test.name) {
+                return Err(ValidationError::DuplicateTestName(test.name.clone()));
+            }
+        }
+
+        let mut service_names = HashSet::new();
+        for service in &dev_shell.services {
+            if !service_names.insert(&service.name) {
+                return Err(ValidationError::DuplicateServiceName(
+                    service.name.to_string(),
                 ));
             }
         }
-    }
 
-    let mut diagnostics = Vec::new();
-    let package = &config.dev_shell.package;
-    let has_no_packages =
-        package.stable.is_empty() && package.unstable.is_empty() && package.pinned.is_empty();
-    if has_no_packages {
-        diagnostics.push(Diagnostic::NoPackages);
+        let package = &dev_shell.package;
+        let has_no_packages =
+            package.stable.is_empty() && package.unstable.is_empty() && package.pinned.is_empty();
+        if has_no_packages {
+            diagnostics.push(Diagnostic::NoPackages(shell_name.clone()));
+        }
     }
 
     Ok(diagnostics)
 }
 
+/// Custom inputs must not collide with the names the generated flake
+/// uses itself, and every `from` / `follows` (of packages and overlays)
+/// must point at a known input.
+fn validate_inputs(config: &DevShellDefinition) -> Result<(), ValidationError> {
+    let mut declared = HashSet::new();
+    for input in &config.inputs {
+        let name = input.name.as_str();
+        let reserved = BUILTIN_INPUTS.contains(&name)
+            || BOUND_NAMES.contains(&name)
+            || name.starts_with(PINNED_INPUT_PREFIX)
+            || name.starts_with(PINNED_BINDING_PREFIX);
+        if reserved {
+            return Err(ValidationError::ReservedInputName(name.to_string()));
+        }
+        if !declared.insert(name) {
+            return Err(ValidationError::DuplicateInput(name.to_string()));
+        }
+    }
+
+    for input in &config.inputs {
+        for target in input.follows.values() {
+            let target = target.as_str();
+            if !BUILTIN_INPUTS.contains(&target) && !declared.contains(target) {
+                return Err(ValidationError::UnknownFollowsTarget {
+                    input: input.name.to_string(),
+                    target: target.to_string(),
+                });
+            }
+        }
+    }
+
+    for overlay in &config.overlays {
+        if let Overlay::Input { from, .. } = overlay
+            && !declared.contains(from.as_str())
+        {
+            return Err(ValidationError::UnknownOverlayInput(from.to_string()));
+        }
+    }
+
+    let entries = config
+        .dev_shells
+        .values()
+        .flat_map(|shell| shell.package.stable.iter().chain(&shell.package.unstable));
+    for entry in entries {
+        if let Some(input) = &entry.from
+            && !declared.contains(input.as_str())
+        {
+            return Err(ValidationError::UnknownPackageInput {
+                package: entry.name.to_string(),
+                input: input.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -71,7 +184,10 @@ devShell:
         let result = validate_config(&config);
 
         // Assert
-        assert_eq!(result.unwrap(), vec![Diagnostic::NoPackages]);
+        assert_eq!(
+            result.unwrap(),
+            vec![Diagnostic::NoPackages("default".parse().unwrap())]
+        );
     }
 
     #[test]
@@ -97,4 +213,277 @@ devShell:
             ValidationError::EmptyTaskCommands("empty-task".to_string())
         );
     }
+
+    #[test]
+    fn rejects_task_dependency_cycle() {
+        // Arrange
+        let yaml = r#"
+devShell:
+  package:
+    stable:
+      - name: bash
+  task:
+    build:
+      commands: [make]
+      deps: [test]
+    test:
+      commands: [make test]
+      deps: [build]
+"#;
+        let config: DevShellDefinition = serde_yaml::from_str(yaml).unwrap();
+
+        // Act
+        let result = validate_config(&config);
+
+        // Assert
+        assert_eq!(
+            result.unwrap_err(),
+            ValidationError::TaskCycle("build -> test -> build".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_test_names() {
+        // Arrange
+        let yaml = r#"
+devShell:
+  package:
+    stable:
+      - name: bash
+  test:
+    - name: unit
+      command: cargo test --lib
+    - name: unit
+      command: cargo test --doc
+"#;
+        let config: DevShellDefinition = serde_yaml::from_str(yaml).unwrap();
+
+        // Act
+        let result = validate_config(&config);
+
+        // Assert
+        assert_eq!(
+            result.unwrap_err(),
+            ValidationError::DuplicateTestName("unit".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_service_names() {
+        // Arrange
+        let yaml = r#"
+devShell:
+  package:
+    stable:
+      - name: postgresql
+  services:
+    - name: db
+      command: postgres
+    - name: db
+      command: postgres -p 5433
+"#;
+        let config: DevShellDefinition = serde_yaml::from_str(yaml).unwrap();
+
+        // Act
+        let result = validate_config(&config);
+
+        // Assert
+        assert_eq!(
+            result.unwrap_err(),
+            ValidationError::DuplicateServiceName("db".to_string())
+        );
+    }
+
+    #[test]
+    fn reports_no_packages_per_shell() {
+        // Arrange
+        let yaml = r#"
+devShells:
+  default:
+    package:
+      stable:
+        - name: bash
+  docs:
+    package: {}
+"#;
+        let config: DevShellDefinition = serde_yaml::from_str(yaml).unwrap();
+
+        // Act
+        let result = validate_config(&config);
+
+        // Assert
+        assert_eq!(
+            result.unwrap(),
+            vec![Diagnostic::NoPackages("docs".parse().unwrap())]
+        );
+    }
+
+    #[test]
+    fn rejects_shells_that_disagree_on_allow_unfree() {
+        // Arrange
+        let yaml = r#"
+devShells:
+  default:
+    package:
+      stable:
+        - name: bash
+  ci:
+    allowUnfree: false
+    package:
+      stable:
+        - name: bash
+"#;
+        let config: DevShellDefinition = serde_yaml::from_str(yaml).unwrap();
+
+        // Act
+        let result = validate_config(&config);
+
+        // Assert
+        assert_eq!(result.unwrap_err(), ValidationError::ConflictingAllowUnfree);
+    }
+
+    #[test]
+    fn accepts_packages_from_declared_inputs() {
+        // Arrange
+        let yaml = r#"
+inputs:
+  - name: fenix
+    url: github:nix-community/fenix
+    follows:
+      nixpkgs: nixpkgs
+devShell:
+  package:
+    stable:
+      - name: stable.toolchain
+        from: fenix
+"#;
+        let config: DevShellDefinition = serde_yaml::from_str(yaml).unwrap();
+
+        // Act
+        let result = validate_config(&config);
+
+        // Assert
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn rejects_reserved_input_names() {
+        // Arrange
+        let reserved = [
+            "nixpkgs",
+            "self",
+            "stablePackages",
+            "nixpkgs--go",
+            "pinnedPkgs-go",
+        ];
+
+        for name in reserved {
+            let yaml = format!(
+                "inputs:\n  - name: {name}\n    url: github:a/b\ndevShell:\n  package:\n    stable: []\n"
+            );
+            let config: DevShellDefinition = serde_yaml::from_str(&yaml).unwrap();
+
+            // Act
+            let result = validate_config(&config);
+
+            // Assert
+            assert_eq!(
+                result.unwrap_err(),
+                ValidationError::ReservedInputName(name.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_package_from_undeclared_input() {
+        // Arrange
+        let yaml = r#"
+devShell:
+  package:
+    stable:
+      - name: stable.toolchain
+        from: fenix
+"#;
+        let config: DevShellDefinition = serde_yaml::from_str(yaml).unwrap();
+
+        // Act
+        let result = validate_config(&config);
+
+        // Assert
+        assert_eq!(
+            result.unwrap_err(),
+            ValidationError::UnknownPackageInput {
+                package: "stable.toolchain".to_string(),
+                input: "fenix".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_follows_of_unknown_input() {
+        // Arrange
+        let yaml = r#"
+inputs:
+  - name: fenix
+    url: github:nix-community/fenix
+    follows:
+      nixpkgs: my-nixpkgs
+devShell:
+  package:
+    stable: []
+"#;
+        let config: DevShellDefinition = serde_yaml::from_str(yaml).unwrap();
+
+        // Act
+        let result = validate_config(&config);
+
+        // Assert
+        assert!(matches!(
+            result,
+            Err(ValidationError::UnknownFollowsTarget { target, .. }) if target == "my-nixpkgs"
+        ));
+    }
+
+    #[test]
+    fn rejects_overlay_from_undeclared_input() {
+        // Arrange
+        let yaml = r#"
+overlays:
+  - from: rust-overlay
+devShell:
+  package:
+    stable: []
+"#;
+        let config: DevShellDefinition = serde_yaml::from_str(yaml).unwrap();
+
+        // Act
+        let result = validate_config(&config);
+
+        // Assert
+        assert_eq!(
+            result.unwrap_err(),
+            ValidationError::UnknownOverlayInput("rust-overlay".to_string())
+        );
+    }
+
+    #[test]
+    fn warns_when_allow_unfree_makes_unfree_packages_moot() {
+        // Arrange
+        let yaml = r#"
+nixpkgsConfig:
+  allowUnfreePackages:
+    - vscode
+devShell:
+  package:
+    stable:
+      - name: vscode
+"#;
+        let config: DevShellDefinition = serde_yaml::from_str(yaml).unwrap();
+
+        // Act
+        let diagnostics = validate_config(&config).unwrap();
+
+        // Assert
+        assert_eq!(diagnostics, vec![Diagnostic::UnfreePackagesIgnored]);
+    }
 }
@@ -8,6 +8,8 @@
 
 use thiserror::Error;
 
+use crate::values::{EnvVarName, ShellName};
+
 /// Raised when a raw string cannot be converted into a value object.
 ///
 /// These errors surface during YAML deserialization (via `serde(try_from)`),
@@ -27,6 +29,16 @@ pub enum ParseError {
     )]
     InvalidTaskName(String),
 
+    #[error(
+        "Invalid service name: '{0}'. Service names should contain only alphanumeric characters, hyphens, and underscores"
+    )]
+    InvalidServiceName(String),
+
+    #[error(
+        "Invalid task argument name: '{0}'. Argument names must start with a letter and contain only alphanumeric characters, hyphens, and underscores"
+    )]
+    InvalidTaskArgName(String),
+
     #[error(
         "Invalid environment variable name: '{0}'. Variable names must match [a-zA-Z_][a-zA-Z0-9_]*"
     )]
@@ -36,6 +48,49 @@ pub enum ParseError {
         "Invalid registry URL: '{0}'. Expected format: 'github:OWNER/REPO/BRANCH' (e.g., 'github:NixOS/nixpkgs/nixos-25.06')"
     )]
     InvalidRegistryUrl(String),
+
+    #[error(
+        "Invalid dev shell name: '{0}'. Shell names must start with a letter or underscore and contain only alphanumeric characters, hyphens, and underscores"
+    )]
+    InvalidShellName(String),
+
+    #[error(
+        "Invalid system: '{0}'. Expected a Nix system such as 'x86_64-linux' or 'aarch64-darwin'"
+    )]
+    InvalidSystem(String),
+
+    #[error(
+        "Invalid input name: '{0}'. Input names must start with a letter or underscore and contain only alphanumeric characters, hyphens, and underscores"
+    )]
+    InvalidInputName(String),
+
+    #[error(
+        "Invalid flake URL: '{0}'. Flake URLs must be non-empty and cannot contain whitespace, quotes, backslashes, or '$'"
+    )]
+    InvalidFlakeUrl(String),
+
+    #[error(
+        "Invalid overlay name: '{0}'. Overlay names must start with a letter or underscore and contain only alphanumeric characters, hyphens, and underscores"
+    )]
+    InvalidOverlayName(String),
+
+    #[error(
+        "Invalid path: '{0}'. Expected a relative path such as './nix/overlay.nix' containing only alphanumeric characters, '.', '_', '-', '+', and '/'"
+    )]
+    InvalidNixFilePath(String),
+
+    #[error(
+        "Invalid timeout: '{0}'. Expected a whole number followed by 's', 'm', or 'h' (e.g., '90s', '5m')"
+    )]
+    InvalidTimeout(String),
+
+    #[error(
+        "Invalid test report: '{0}'. Expected '<format>=<path>' with format 'junit', 'tap', or 'json' (e.g., 'junit=reports/tests.xml')"
+    )]
+    InvalidTestReport(String),
+
+    #[error("Invalid env format: '{0}'. Expected 'json', 'dotenv', 'sh', or 'fish'")]
+    InvalidEnvFormat(String),
 }
 
 /// Raised by [`crate::validate_config`] for constraints that span
@@ -45,22 +100,131 @@ pub enum ParseError {
 pub enum ValidationError {
     #[error("Task '{0}' has an empty commands list. At least one command must be specified")]
     EmptyTaskCommands(String),
+
+    #[error("No dev shell defined. Add a `devShell` section or a `devShells` map to lazynix.yaml")]
+    NoDevShell,
+
+    #[error(
+        "The default dev shell is defined twice: use either `devShell` or `devShells.default`, not both"
+    )]
+    DuplicateDefaultShell,
+
+    #[error(
+        "Dev shells disagree on allowUnfree. The nixpkgs imports are shared by every shell, so allowUnfree must be the same in all of them"
+    )]
+    ConflictingAllowUnfree,
+
+    #[error(
+        "Input name '{0}' is reserved. LazyNix declares nixpkgs, nixpkgs-unstable, flake-utils, and the pinned-package inputs itself"
+    )]
+    ReservedInputName(String),
+
+    #[error("Input '{0}' is declared more than once")]
+    DuplicateInput(String),
+
+    #[error(
+        "Package '{package}' is taken from input '{input}', which is not declared under `inputs`"
+    )]
+    UnknownPackageInput { package: String, input: String },
+
+    #[error("Input '{input}' follows '{target}', which is not a known input")]
+    UnknownFollowsTarget { input: String, target: String },
+
+    #[error(
+        "An overlay must set exactly one of `from` (a declared input) or `path` (a local .nix file)"
+    )]
+    AmbiguousOverlay,
+
+    #[error("Overlay is taken from input '{0}', which is not declared under `inputs`")]
+    UnknownOverlayInput(String),
+
+    #[error(
+        "Environment variable '{0}' needs a `value` or a `command`; only `op: unset` may omit both"
+    )]
+    MissingEnvVarValue(String),
+
+    #[error("Environment variable '{0}' is unset, so it cannot also set a `value` or `command`")]
+    UnsetWithValue(String),
+
+    #[error("Environment variable '{0}' must set either `value` or `command`, not both")]
+    AmbiguousEnvVarSource(String),
+
+    #[error("Task '{task}' depends on '{dependency}', which is not defined")]
+    UnknownTaskDependency { task: String, dependency: String },
+
+    /// Carries the cycle, e.g. `build -> codegen -> build`.
+    #[error("Task dependencies form a cycle: {0}")]
+    TaskCycle(String),
+
+    #[error("Task '{task}' declares argument '{arg}' more than once")]
+    DuplicateTaskArg { task: String, arg: String },
+
+    #[error("Test '{0}' is declared more than once. Give each test a distinct `name`")]
+    DuplicateTestName(String),
+
+    #[error("Service '{0}' is declared more than once")]
+    DuplicateServiceName(String),
+
+    #[error("A readiness probe must set exactly one of `port` or `command`")]
+    AmbiguousReadyProbe,
 }
 
-/// Non-fatal finding from [`crate::validate_config`].
+/// Command-line arguments that do not fit a task's declared `args:`,
+/// raised by [`crate::parse_task_args`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TaskArgError {
+    #[error("Task '{task}' has no argument '--{arg}'. Run `lnix task {task} --help` for usage")]
+    Unknown { task: String, arg: String },
+
+    #[error("Argument '--{arg}' of task '{task}' needs a value")]
+    MissingValue { task: String, arg: String },
+
+    #[error("Task '{task}' requires argument '{arg}'. Run `lnix task {task} --help` for usage")]
+    MissingRequired { task: String, arg: String },
+}
+
+/// Non-fatal finding from [`crate::validate_config`] or the lint checks
+/// in [`crate::service::lint`].
 ///
 /// Diagnostics are returned as values so the domain stays free of I/O;
 /// the caller decides how (and whether) to display them.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Diagnostic {
-    /// The config declares no packages at all.
-    NoPackages,
+    /// The named dev shell declares no packages at all.
+    NoPackages(ShellName),
+    /// `nixpkgsConfig.allowUnfreePackages` is set, but `allowUnfree: true`
+    /// already admits every unfree package, so the list has no effect.
+    UnfreePackagesIgnored,
+    /// An env var `command` starts with a binary that none of the dev
+    /// shell's packages seems to provide.
+    CommandNotInPackages {
+        shell: ShellName,
+        envvar: EnvVarName,
+        binary: String,
+    },
 }
 
 impl std::fmt::Display for Diagnostic {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Diagnostic::NoPackages => write!(f, "No packages specified in lazynix.yaml"),
+            Diagnostic::NoPackages(shell) => write!(
+                f,
+                "No packages specified in lazynix.yaml for dev shell '{}'",
+                shell
+            ),
+            Diagnostic::UnfreePackagesIgnored => write!(
+                f,
+                "nixpkgsConfig.allowUnfreePackages has no effect while allowUnfree is true; set allowUnfree: false to allow only the listed packages"
+            ),
+            Diagnostic::CommandNotInPackages {
+                shell,
+                envvar,
+                binary,
+            } => write!(
+                f,
+                "Env var '{}' runs '{}', which no package of dev shell '{}' appears to provide",
+                envvar, binary, shell
+            ),
         }
     }
 }
@@ -83,6 +247,28 @@ pub enum ConfigError {
 
     #[error("Dotenv file not found: {0}")]
     DotenvFileNotFound(String),
+
+    #[error("Extended config not found: {0}")]
+    ExtendsNotFound(String),
+
+    /// The `extends:` chain leads back to a file already being read.
+    /// Carries the chain, e.g. `a.yaml -> b.yaml -> a.yaml`.
+    #[error("Cyclic extends: {0}")]
+    ExtendsCycle(String),
+
+    #[error("Cannot merge extended config: {0}")]
+    MergeConflict(String),
+}
+
+/// Why a `lazynix.yaml` document does not deserialize, raised through
+/// [`crate::interface::persistence::ConfigRepository::parse_config`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{message}")]
+pub struct YamlError {
+    /// The parser's message, without its location.
+    pub message: String,
+    /// One-based line and column where the parser gave up, when known.
+    pub location: Option<(usize, usize)>,
 }
 
 /// Failures persisting or reading `flake.nix` content, raised through
@@ -100,6 +286,67 @@ pub enum FlakeError {
     Read(std::io::Error),
 }
 
+/// Failure writing a `lnix test --report` file, raised through
+/// [`crate::interface::persistence::ReportWriter`].
+#[derive(Error, Debug)]
+pub enum ReportError {
+    #[error("Failed to write test report {path}: {source}")]
+    Write {
+        path: String,
+        source: std::io::Error,
+    },
+}
+
+/// Failures fingerprinting task sources or recording fingerprints,
+/// raised through [`crate::interface::persistence::TaskStateStore`].
+#[derive(Error, Debug)]
+pub enum TaskStateError {
+    #[error("Invalid glob '{pattern}': {reason}")]
+    InvalidPattern { pattern: String, reason: String },
+
+    /// I/O error while hashing source files. As with [`FlakeError`],
+    /// `#[from]` goes to `Write`, so `Read` is constructed explicitly.
+    #[error("Failed to read task sources: {0}")]
+    Read(std::io::Error),
+
+    #[error("Failed to write task state: {0}")]
+    Write(#[from] std::io::Error),
+}
+
+/// Failures managing background services, raised through
+/// [`crate::interface::gateway::ServiceSupervisor`].
+#[derive(Error, Debug)]
+pub enum ServiceError {
+    #[error("Failed to start service '{service}': {source}")]
+    Start {
+        service: String,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to stop service '{service}': {source}")]
+    Stop {
+        service: String,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to read the state of service '{service}': {source}")]
+    State {
+        service: String,
+        source: std::io::Error,
+    },
+}
+
+/// Failures talking to the editor, raised through
+/// [`crate::interface::lsp::LanguageClient`].
+#[derive(Error, Debug)]
+pub enum LspError {
+    #[error("Failed to talk to the language client: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Malformed message from the language client: {0}")]
+    Protocol(String),
+}
+
 /// Failures executing `nix`, raised through the gateways in
 /// [`crate::interface::gateway`].
 #[derive(Error, Debug)]
@@ -116,6 +363,9 @@ pub enum NixError {
     #[error("Failed to convert command output to UTF-8: {0}")]
     InvalidUtf8(#[from] std::string::FromUtf8Error),
 
+    #[error("Unexpected output from nix print-dev-env: {0}")]
+    InvalidDevEnv(String),
+
     #[error("Version resolution failed for '{spec}': {message}")]
     VersionResolution {
         /// The `name@version` spec that was being resolved.
@@ -123,4 +373,7 @@ pub enum NixError {
         /// Why resolution failed (stderr or parse detail).
         message: String,
     },
+
+    #[error("Failed to build the package index: {0}")]
+    PackageIndex(String),
 }
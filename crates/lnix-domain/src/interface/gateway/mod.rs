@@ -2,13 +2,20 @@
 //!
 //! Two execution shapes exist and are kept as separate traits because
 //! their semantics differ: [`NixRunner`] inherits stdio and returns
-//! only exit codes (interactive), while [`NixEvaluator`] and
-//! [`VersionResolver`] capture output for the domain to interpret.
+//! exit codes (interactive; test runs also keep a copy of the output),
+//! while [`NixEvaluator`] and [`VersionResolver`] capture output for
+//! the domain to interpret. [`ServiceSupervisor`] is a third shape:
+//! the processes it starts are detached and outlive `lnix` itself.
+//! [`PackageIndex`] captures output too, but keeps it between runs.
 
 mod nix_evaluator;
 mod nix_runner;
+mod package_index;
+mod service_supervisor;
 mod version_resolver;
 
 pub use nix_evaluator::{EvalOutcome, NixEvaluator};
-pub use nix_runner::NixRunner;
+pub use nix_runner::{DevEnv, NixRunner, TestExecution, TestInvocation};
+pub use package_index::{PackageIndex, PackageInfo};
+pub use service_supervisor::{Readiness, ServiceStatus, ServiceSupervisor};
 pub use version_resolver::{ResolvedVersion, VersionResolver};
@@ -1,26 +1,102 @@
 //! Port for interactive `nix` invocations.
 
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+use std::time::Duration;
+
 use crate::error::NixError;
+use crate::values::{ShellName, Timeout};
+
+/// A dev shell evaluated once by [`NixRunner::dev_env`]: the bash
+/// script `nix print-dev-env` prints, which recreates the shell's
+/// environment (and runs its `shellHook`) when sourced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DevEnv(String);
+
+impl DevEnv {
+    pub fn new(script: String) -> Self {
+        Self(script)
+    }
+
+    pub fn script(&self) -> &str {
+        &self.0
+    }
+}
+
+/// One test for [`NixRunner::run_tests`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestInvocation {
+    pub script: String,
+    pub timeout: Option<Timeout>,
+    /// A failure of this test does not stop a fail-fast run.
+    pub allow_failure: bool,
+}
+
+/// How one [`NixRunner::run_test`] call went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestExecution {
+    /// `124` when the timeout stopped the test.
+    pub exit_code: i32,
+    pub duration: Duration,
+    /// The test's stdout and stderr, interleaved.
+    pub output: String,
+}
 
-/// Runs `nix` commands with inherited stdio, returning exit codes.
+/// Runs `nix` commands with inherited stdio, returning exit codes (and,
+/// for tests, timing and a copy of the output).
 ///
 /// Arguments are arbitrary shell command strings by design — they are
 /// user-authored commands to run *inside* the dev shell, not values
-/// with domain invariants.
+/// with domain invariants. The target shell, by contrast, is a
+/// [`ShellName`], so it can be embedded in the `.#<name>` installable
+/// without quoting.
 pub trait NixRunner {
-    /// Enters `nix develop` interactively.
-    fn develop(&self) -> Result<(), NixError>;
+    /// Enters `nix develop .#<shell>` interactively.
+    fn develop(&self, shell: &ShellName) -> Result<(), NixError>;
+
+    /// Runs `args` via `nix develop .#<shell> -c`, returning the
+    /// command's exit code.
+    fn develop_command(&self, shell: &ShellName, args: &[String]) -> Result<i32, NixError>;
+
+    /// Evaluates `shell` once for [`NixRunner::run_test`].
+    fn dev_env(&self, shell: &ShellName) -> Result<DevEnv, NixError>;
+
+    /// The variables `shell` exports, for `lnix env`: those Nix provides
+    /// (`PATH` followed by the inherited one, as `nix develop` does),
+    /// after running `setup` with bash on top of them. Variables that
+    /// `setup` leaves as inherited are not included.
+    fn dev_env_vars(
+        &self,
+        shell: &ShellName,
+        setup: &str,
+    ) -> Result<BTreeMap<String, String>, NixError>;
 
-    /// Runs `args` via `nix develop -c`, returning the command's exit code.
-    fn develop_command(&self, args: &[String]) -> Result<i32, NixError>;
+    /// Runs `script` with bash inside `env`, stopping it after
+    /// `timeout`. The output reaches the terminal as it is produced and
+    /// is also captured.
+    fn run_test(
+        &self,
+        env: &DevEnv,
+        script: &str,
+        timeout: Option<&Timeout>,
+    ) -> Result<TestExecution, NixError>;
 
-    /// Runs the declared test commands (via `LAZYNIX_TEST_MODE`),
-    /// returning the exit code.
-    fn test(&self) -> Result<i32, NixError>;
+    /// Runs `tests` inside `env`, up to `jobs` at a time, with each
+    /// test's output kept rather than printed. Executions are
+    /// index-aligned with `tests`. With `fail_fast`, no test starts
+    /// after one that is not allowed to fail has failed; those that
+    /// never started are `None`.
+    fn run_tests(
+        &self,
+        env: &DevEnv,
+        tests: &[TestInvocation],
+        jobs: NonZeroUsize,
+        fail_fast: bool,
+    ) -> Result<Vec<Option<TestExecution>>, NixError>;
 
-    /// Runs `commands` sequentially (joined with `&&`) inside the dev
-    /// shell, returning the exit code.
-    fn run_task(&self, commands: &[String]) -> Result<i32, NixError>;
+    /// Runs `commands` sequentially (joined with `&&`) with bash inside
+    /// `shell`, returning the exit code.
+    fn run_task(&self, shell: &ShellName, commands: &[String]) -> Result<i32, NixError>;
 
     /// Runs `nix flake update`.
     fn flake_update(&self) -> Result<(), NixError>;
@@ -0,0 +1,20 @@
+//! Port for the package index `lnix lsp` completes and describes
+//! package names from.
+
+use crate::error::NixError;
+
+/// One package of the index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageInfo {
+    /// Attribute path, as written under `name:` (e.g. `python312`).
+    pub attr: String,
+    pub version: String,
+    pub description: String,
+}
+
+/// The packages of the project's stable nixpkgs.
+pub trait PackageIndex {
+    /// Every package, sorted by attribute. The first call may have to
+    /// build the index, which takes a while.
+    fn packages(&self) -> Result<Vec<PackageInfo>, NixError>;
+}
@@ -0,0 +1,83 @@
+//! Port for background services started by `lnix up`.
+
+use std::time::Duration;
+
+use crate::definition::ReadyCheck;
+use crate::error::ServiceError;
+use crate::interface::gateway::DevEnv;
+use crate::values::{ServiceName, ShellName};
+
+/// What the supervisor knows about a service from its pid file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceStatus {
+    /// Started by `lnix up` and still alive.
+    Running { pid: u32 },
+    /// Started by `lnix up`, but has exited since.
+    Exited,
+    /// Never started, or stopped by `lnix down`.
+    Stopped,
+}
+
+/// How [`ServiceSupervisor::wait_ready`] ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Readiness {
+    Ready,
+    /// The service exited before its probe passed.
+    Exited,
+    /// The probe kept failing for the whole time limit.
+    TimedOut,
+}
+
+/// Starts, watches, and stops services running in the background,
+/// detached from the terminal, keeping track of them across `lnix`
+/// invocations.
+///
+/// A service is identified by its dev shell and name, so same-named
+/// services of two shells never share a process or files. Each gets a
+/// data directory, exported to its command (and probe) as
+/// `$LAZYNIX_SERVICE_DIR`, which survives restarts.
+pub trait ServiceSupervisor {
+    fn status(
+        &self,
+        shell: &ShellName,
+        service: &ServiceName,
+    ) -> Result<ServiceStatus, ServiceError>;
+
+    /// Runs `script` with bash inside `env`, with stdout and stderr
+    /// going to the service's log, and records its pid. Returns the
+    /// pid.
+    fn start(
+        &self,
+        env: &DevEnv,
+        shell: &ShellName,
+        service: &ServiceName,
+        script: &str,
+    ) -> Result<u32, ServiceError>;
+
+    /// Retries `check` (a command check runs inside `env`) until it
+    /// passes, the service exits, or `limit` runs out.
+    fn wait_ready(
+        &self,
+        env: &DevEnv,
+        shell: &ShellName,
+        service: &ServiceName,
+        check: &ReadyCheck,
+        limit: Duration,
+    ) -> Result<Readiness, ServiceError>;
+
+    /// Stops the service together with every process it started, and
+    /// forgets its pid. Returns whether it was running.
+    fn stop(&self, shell: &ShellName, service: &ServiceName) -> Result<bool, ServiceError>;
+
+    /// Everything the service has logged since it was last started;
+    /// `None` when it never ran.
+    fn read_logs(
+        &self,
+        shell: &ShellName,
+        service: &ServiceName,
+    ) -> Result<Option<String>, ServiceError>;
+
+    /// Prints the log, then whatever the service logs next, until
+    /// interrupted.
+    fn follow_logs(&self, shell: &ShellName, service: &ServiceName) -> Result<(), ServiceError>;
+}
@@ -0,0 +1,233 @@
+//! Port for the editor that `lnix lsp` serves, and the Language Server
+//! Protocol messages exchanged with it.
+//!
+//! Only the requests LazyNix answers are modelled; the JSON-RPC framing
+//! and the JSON shapes belong to the adapter.
+
+use crate::error::LspError;
+
+/// A zero-based position as LSP counts it: `character` is in UTF-16
+/// code units.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TextPosition {
+    pub line: u32,
+    pub character: u32,
+}
+
+impl TextPosition {
+    pub fn new(line: u32, character: u32) -> Self {
+        Self { line, character }
+    }
+}
+
+/// A half-open range between two [`TextPosition`]s.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextRange {
+    pub start: TextPosition,
+    pub end: TextPosition,
+}
+
+impl TextRange {
+    pub fn new(start: TextPosition, end: TextPosition) -> Self {
+        Self { start, end }
+    }
+
+    /// Whether the two ranges share a position (touching counts).
+    pub fn touches(&self, other: &TextRange) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+}
+
+/// The JSON-RPC id of a request, kept as its raw JSON text so that the
+/// response carries it back unchanged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(String);
+
+impl RequestId {
+    pub fn new(raw_json: String) -> Self {
+        Self(raw_json)
+    }
+
+    pub fn as_json(&self) -> &str {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem in an open document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LspDiagnostic {
+    pub range: TextRange,
+    pub severity: Severity,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    Task,
+    Package,
+}
+
+/// One completion candidate, replacing `range` with `label`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletionItem {
+    pub label: String,
+    pub kind: CompletionKind,
+    /// Shown next to the label (a package's version).
+    pub detail: Option<String>,
+    /// Shown in the documentation pane (a description).
+    pub documentation: Option<String>,
+    pub range: TextRange,
+}
+
+/// The answer to a completion request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompletionList {
+    /// More candidates match than were returned; the editor should ask
+    /// again as the user types.
+    pub is_incomplete: bool,
+    pub items: Vec<CompletionItem>,
+}
+
+/// Markdown to show while hovering over `range`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HoverInfo {
+    pub range: TextRange,
+    pub markdown: String,
+}
+
+/// Replaces `range` with `new_text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: TextRange,
+    pub new_text: String,
+}
+
+/// A quick fix: edits to the document the request was about, applied
+/// together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeAction {
+    pub title: String,
+    pub edits: Vec<TextEdit>,
+}
+
+/// What the editor sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientMessage {
+    Initialize {
+        id: RequestId,
+    },
+    /// A document was opened; `text` is its full contents.
+    DidOpen {
+        uri: String,
+        text: String,
+    },
+    /// A document changed; `text` is its full new contents.
+    DidChange {
+        uri: String,
+        text: String,
+    },
+    DidClose {
+        uri: String,
+    },
+    Completion {
+        id: RequestId,
+        uri: String,
+        position: TextPosition,
+    },
+    Hover {
+        id: RequestId,
+        uri: String,
+        position: TextPosition,
+    },
+    CodeAction {
+        id: RequestId,
+        uri: String,
+        range: TextRange,
+    },
+    Shutdown {
+        id: RequestId,
+    },
+    Exit,
+    /// A request this server does not implement.
+    UnknownRequest {
+        id: RequestId,
+        method: String,
+    },
+    /// A request whose parameters could not be read.
+    InvalidRequest {
+        id: RequestId,
+        reason: String,
+    },
+    /// A notification this server has no use for.
+    Ignored,
+    /// A body that is not a JSON-RPC message. Its frame was read, so
+    /// the server can answer it and carry on.
+    Malformed {
+        reason: String,
+    },
+}
+
+/// What the server sends back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerMessage {
+    /// Answers `initialize` with the server's capabilities.
+    Initialized {
+        id: RequestId,
+    },
+    /// Replaces the diagnostics shown for `uri`.
+    Diagnostics {
+        uri: String,
+        diagnostics: Vec<LspDiagnostic>,
+    },
+    Completions {
+        id: RequestId,
+        list: CompletionList,
+    },
+    Hover {
+        id: RequestId,
+        hover: Option<HoverInfo>,
+    },
+    CodeActions {
+        id: RequestId,
+        uri: String,
+        actions: Vec<CodeAction>,
+    },
+    /// Answers `shutdown`.
+    ShutdownAck {
+        id: RequestId,
+    },
+    /// Answers an [`ClientMessage::UnknownRequest`].
+    MethodNotFound {
+        id: RequestId,
+        method: String,
+    },
+    /// Answers an [`ClientMessage::InvalidRequest`].
+    InvalidParams {
+        id: RequestId,
+        reason: String,
+    },
+    /// Answers a [`ClientMessage::Malformed`], whose id is unknown.
+    ParseError {
+        reason: String,
+    },
+    /// A message for the editor's log.
+    Log {
+        severity: Severity,
+        message: String,
+    },
+}
+
+/// The editor on the other end of `lnix lsp`.
+pub trait LanguageClient {
+    /// Waits for the next message; `None` once the editor has closed
+    /// the connection.
+    fn receive(&self) -> Result<Option<ClientMessage>, LspError>;
+
+    fn send(&self, message: ServerMessage) -> Result<(), LspError>;
+}
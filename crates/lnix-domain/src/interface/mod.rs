@@ -7,7 +7,9 @@
 //! - [`persistence`] — the project's own files (repositories).
 //! - [`gateway`] — external processes (`nix`, `nix-versions`).
 //! - [`output`] — the user-facing display sink.
+//! - [`lsp`] — the editor `lnix lsp` talks to.
 
 pub mod gateway;
+pub mod lsp;
 pub mod output;
 pub mod persistence;
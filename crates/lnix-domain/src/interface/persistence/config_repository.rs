@@ -1,7 +1,7 @@
 //! Port for reading the project's configuration files.
 
 use crate::definition::{DevShellDefinition, Settings};
-use crate::error::ConfigError;
+use crate::error::{ConfigError, YamlError};
 
 /// Reads `lazynix.yaml` and `lazynix-settings.yaml`.
 ///
@@ -15,4 +15,13 @@ pub trait ConfigRepository {
 
     /// Reads `lazynix-settings.yaml`, or `None` when the file is absent.
     fn read_settings(&self) -> Result<Option<Settings>, ConfigError>;
+
+    /// The files `lazynix.yaml` `extends`, directly or through another
+    /// extended file, relative to the config directory and in the order
+    /// they are read.
+    fn extended_files(&self) -> Result<Vec<String>, ConfigError>;
+
+    /// Deserializes `text` as a `lazynix.yaml` document on its own,
+    /// without following `extends`, for an editor buffer.
+    fn parse_config(&self, text: &str) -> Result<DevShellDefinition, YamlError>;
 }
@@ -0,0 +1,16 @@
+//! Port for `--watch`.
+
+use crate::error::ConfigError;
+
+/// Waits for the project's configuration to change.
+///
+/// Like [`super::ConfigRepository`], implementations own the location
+/// of `lazynix.yaml` and `lazynix-settings.yaml`; other files are
+/// written as in `lazynix.yaml`, relative to the config directory.
+pub trait ConfigWatcher {
+    /// Blocks until `lazynix.yaml`, `lazynix-settings.yaml`, or one of
+    /// `files` is created, modified, or removed, then until changes
+    /// settle (an editor may write a file in several steps). Returns
+    /// the files that changed.
+    fn wait_for_change(&self, files: &[String]) -> Result<Vec<String>, ConfigError>;
+}
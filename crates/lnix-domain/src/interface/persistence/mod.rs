@@ -1,14 +1,22 @@
 //! Repository ports for the project's own files
-//! (`lazynix.yaml`, `lazynix-settings.yaml`, `flake.nix`, dotenv files).
+//! (`lazynix.yaml`, `lazynix-settings.yaml`, `flake.nix`, dotenv files,
+//! task state under `.lazynix/`), plus the test reports `lnix test`
+//! writes on request and the watch on those files behind `--watch`.
 
 mod config_repository;
+mod config_watcher;
 mod env_file;
 mod flake_reader;
 mod flake_writer;
+mod report_writer;
 mod scaffolder;
+mod task_state;
 
 pub use config_repository::ConfigRepository;
+pub use config_watcher::ConfigWatcher;
 pub use env_file::EnvFilePresenceChecker;
 pub use flake_reader::{FlakeReader, PinnedResolution, PinnedResolutions};
 pub use flake_writer::FlakeWriter;
+pub use report_writer::ReportWriter;
 pub use scaffolder::ProjectScaffolder;
+pub use task_state::TaskStateStore;
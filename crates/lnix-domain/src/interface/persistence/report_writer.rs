@@ -0,0 +1,11 @@
+//! Port for writing `lnix test --report` files.
+
+use crate::error::ReportError;
+
+/// Writes rendered test reports ([`crate::render_report`]).
+pub trait ReportWriter {
+    /// Writes `contents` to `path` as given on the command line,
+    /// creating missing parent directories and replacing any existing
+    /// file.
+    fn write_report(&self, path: &str, contents: &str) -> Result<(), ReportError>;
+}
@@ -2,7 +2,8 @@
 
 use crate::error::{ConfigError, FlakeError};
 
-/// Writes the bundled starter files for `lnix init`.
+/// Writes the bundled starter files for `lnix init`, and the `.envrc`
+/// for `lnix direnv init`.
 ///
 /// Templates are raw text with comments, so they bypass the typed
 /// [`crate::DevShellDefinition`] round-trip on purpose: serializing a parsed
@@ -25,4 +26,13 @@ pub trait ProjectScaffolder {
 
     /// Writes the starter `flake.nix`, replacing any existing file.
     fn write_flake_template(&self) -> Result<(), FlakeError>;
+
+    /// Whether an `.envrc` already exists next to `lazynix.yaml`.
+    fn envrc_exists(&self) -> bool;
+
+    /// Display path of the `.envrc`, for user-facing messages.
+    fn envrc_path_display(&self) -> String;
+
+    /// Writes `contents` as the `.envrc`, replacing any existing file.
+    fn write_envrc(&self, contents: &str) -> Result<(), ConfigError>;
 }
@@ -0,0 +1,43 @@
+//! Port for task up-to-date checks.
+
+use crate::error::TaskStateError;
+use crate::values::{ShellName, TaskName};
+
+/// Fingerprints a task's `sources` and remembers the fingerprint of its
+/// last successful run.
+///
+/// Globs and `dir` are written as in `lazynix.yaml`; implementations
+/// resolve them against the config directory they own. A fingerprint is
+/// opaque to callers: equal fingerprints mean nothing changed.
+pub trait TaskStateStore {
+    /// Fingerprints the files matching `sources` under `dir`, together
+    /// with `commands`, so editing the task also invalidates it.
+    fn fingerprint(
+        &self,
+        dir: Option<&str>,
+        sources: &[String],
+        commands: &[String],
+    ) -> Result<String, TaskStateError>;
+
+    /// Whether every glob in `generates` under `dir` matches a file.
+    fn outputs_exist(
+        &self,
+        dir: Option<&str>,
+        generates: &[String],
+    ) -> Result<bool, TaskStateError>;
+
+    /// The fingerprint stored by the last [`Self::record`], if any.
+    fn recorded(
+        &self,
+        shell: &ShellName,
+        task: &TaskName,
+    ) -> Result<Option<String>, TaskStateError>;
+
+    /// Stores `fingerprint` as the task's last successful run.
+    fn record(
+        &self,
+        shell: &ShellName,
+        task: &TaskName,
+        fingerprint: &str,
+    ) -> Result<(), TaskStateError>;
+}
@@ -8,11 +8,14 @@
 //! - The configuration AST ([`DevShellDefinition`] and friends) that mirrors the
 //!   structure of `lazynix.yaml`.
 //! - Pure domain services ([`service`]): flake rendering, lint
-//!   classification and reporting, task-command interpolation.
+//!   classification and reporting, task ordering and command interpolation,
+//!   test and service scripts, the direnv `.envrc`, the JSON Schema
+//!   of the config, and the language server's answers.
 //! - Ports ([`interface`]): the traits infrastructure adapters
 //!   implement, together with the focused error types they return.
 //!
-//! This crate performs no I/O and depends only on `serde` / `thiserror`.
+//! This crate performs no I/O and depends only on `serde` / `serde_json`
+//! / `schemars` / `thiserror`.
 
 mod definition;
 mod error;
@@ -22,15 +25,35 @@ pub mod interface;
 pub mod service;
 
 pub use definition::{
-    DevShell, DevShellDefinition, Env, EnvVar, Package, PackageEntry, PinnedPackageEntry, Settings,
-    TaskDef, validate_config,
+    DevShell, DevShellDefinition, Env, EnvVar, EnvVarOp, FailurePolicy, FlakeInput, NixpkgsConfig,
+    Overlay, Package, PackageEntry, PinnedPackageEntry, ReadyCheck, ReadyProbe, ServiceDef,
+    Settings, TaskArg, TaskDef, TestDef, TestSuite, merge_definitions, rebase_paths,
+    validate_config,
+};
+pub use error::{
+    ConfigError, Diagnostic, FlakeError, LspError, NixError, ParseError, ReportError, ServiceError,
+    TaskArgError, TaskStateError, ValidationError, YamlError,
 };
-pub use error::{ConfigError, Diagnostic, FlakeError, NixError, ParseError, ValidationError};
 pub use interface::persistence::{FlakeReader, FlakeWriter};
+pub use service::direnv::render_envrc;
+pub use service::env::{EnvFormat, env_script, render_env};
 pub use service::flake::render_flake;
 pub use service::lint::{
-    PackageValidationError, ValidationResult, classify_nix_eval_error, format_validation_result,
-    format_validation_result_verbose,
+    PackageValidationError, ValidationResult, check_envvar_commands, classify_nix_eval_error,
+    format_validation_result, format_validation_result_verbose,
+};
+pub use service::lsp::{complete, diagnose, hover, pin_actions};
+pub use service::schema::{SchemaTarget, render_schema};
+pub use service::services::{format_service_table, service_script};
+pub use service::task::{
+    TaskArgs, format_task_usage, interpolate_args, interpolate_command, parallel_script,
+    parse_task_args, resolve_task_order, task_prelude,
+};
+pub use service::test::{
+    ReportFormat, TestOutcome, TestReport, TestStatus, format_test_result, format_test_summary,
+    matches_glob, render_report, select_tests, test_script,
+};
+pub use values::{
+    EnvVarName, FlakeUrl, InputName, NixFilePath, NixSystem, OverlayName, PackageName,
+    PackageVersion, RegistryUrl, ServiceName, ShellName, TaskArgName, TaskName, Timeout,
 };
-pub use service::task::interpolate_command;
-pub use values::{EnvVarName, PackageName, PackageVersion, RegistryUrl, TaskName};
@@ -0,0 +1,81 @@
+//! The `.envrc` that `lnix direnv init` writes.
+
+use crate::ShellName;
+use crate::service::shell::quote;
+
+/// Renders an `.envrc` for the directory holding `lazynix.yaml`.
+///
+/// direnv reloads it whenever `lazynix.yaml`, `lazynix-settings.yaml`,
+/// or one of `files` (extended configs, dotenv and alias files,
+/// relative to that directory) changes. It then runs `lnix generate`
+/// for that directory (never one from `LAZYNIX_CONFIG_DIR`) if any of
+/// them is newer than `flake.nix`, and loads `shell` with `use flake`.
+pub fn render_envrc(shell: &ShellName, files: &[String]) -> String {
+    let mut inputs = vec![
+        "lazynix.yaml".to_string(),
+        "lazynix-settings.yaml".to_string(),
+    ];
+    for file in files {
+        if !inputs.contains(file) {
+            inputs.push(file.clone());
+        }
+    }
+    let inputs: Vec<String> = inputs.iter().map(|input| quote(input)).collect();
+    let flake = if shell.as_str() == "default" {
+        "use flake".to_string()
+    } else {
+        format!("use flake {}", quote(&format!(".#{}", shell)))
+    };
+    format!(
+        r#"# Written by `lnix direnv init`: regenerates flake.nix when lazynix.yaml
+# or a file it references is newer, then loads the dev shell.
+lazynix_inputs=({inputs})
+watch_file "${{lazynix_inputs[@]}}"
+if has lnix; then
+  for input in "${{lazynix_inputs[@]}}"; do
+    if [[ ! -e flake.nix || $input -nt flake.nix ]]; then
+      lnix -C . generate
+      break
+    fi
+  done
+else
+  log_error "lnix not found; flake.nix may be out of date"
+fi
+{flake}
+"#,
+        inputs = inputs.join(" "),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn watches_config_inputs_and_loads_the_shell() {
+        // Arrange
+        let files = vec![
+            ".env".to_string(),
+            "my aliases.sh".to_string(),
+            "lazynix.yaml".to_string(),
+        ];
+
+        // Act
+        let default = render_envrc(&"default".parse().unwrap(), &files);
+        let ci = render_envrc(&"ci".parse().unwrap(), &[]);
+
+        // Assert
+        assert!(
+            default.contains(
+                "lazynix_inputs=(lazynix.yaml lazynix-settings.yaml .env 'my aliases.sh')\n"
+            ),
+            "got: {default}"
+        );
+        assert!(
+            default.contains("\n      lnix -C . generate\n"),
+            "got: {default}"
+        );
+        assert!(default.ends_with("\nuse flake\n"));
+        assert!(ci.ends_with("\nuse flake '.#ci'\n"), "got: {ci}");
+    }
+}
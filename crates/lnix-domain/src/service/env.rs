@@ -0,0 +1,157 @@
+//! `lnix env`: the dev shell's environment, in formats other tools read.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use crate::DevShell;
+use crate::error::ParseError;
+use crate::service::shell::{quote, render_prelude};
+
+/// How `lnix env` prints the environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvFormat {
+    /// One JSON object of name to value.
+    Json,
+    /// `NAME="value"` lines, as read by VS Code's `envFile` and
+    /// systemd's `EnvironmentFile`.
+    Dotenv,
+    /// `export` lines for POSIX shells.
+    Sh,
+    /// `set -gx` lines for fish.
+    Fish,
+}
+
+impl FromStr for EnvFormat {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "dotenv" => Ok(Self::Dotenv),
+            "sh" => Ok(Self::Sh),
+            "fish" => Ok(Self::Fish),
+            _ => Err(ParseError::InvalidEnvFormat(s.to_string())),
+        }
+    }
+}
+
+/// The commands that apply `dev_shell`'s own `env:` on top of what Nix
+/// provides, as its `shellHook` does: the dotenv files, then the env
+/// vars.
+pub fn env_script(dev_shell: &DevShell) -> String {
+    render_prelude(
+        None,
+        dev_shell.env.iter().flat_map(|env| &env.dotenv),
+        dev_shell.env.iter().flat_map(|env| &env.envvar),
+    )
+    .join("\n")
+}
+
+/// Renders `vars` as `format`, one variable per line (or per object
+/// member), without a trailing newline.
+pub fn render_env(format: EnvFormat, vars: &BTreeMap<String, String>) -> String {
+    let line: fn(&str, &str) -> String = match format {
+        EnvFormat::Json => {
+            return serde_json::to_string_pretty(vars).expect("a string map always serializes");
+        }
+        EnvFormat::Dotenv => |name, value| format!("{}={}", name, dotenv_string(value)),
+        EnvFormat::Sh => |name, value| format!("export {}={}", name, quote(value)),
+        EnvFormat::Fish => |name, value| format!("set -gx {} {}", name, fish_words(name, value)),
+    };
+    let lines: Vec<String> = vars.iter().map(|(name, value)| line(name, value)).collect();
+    lines.join("\n")
+}
+
+/// Double-quoted, with `\`, `"`, and line breaks escaped, as well as
+/// `$` and `` ` ``, so that readers which expand variables (docker
+/// compose, python-dotenv, `set -a; . file`) keep the value as is.
+fn dotenv_string(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '$' => quoted.push_str("\\$"),
+            '`' => quoted.push_str("\\`"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// `value` as fish words. fish keeps `*PATH` variables as lists, so
+/// those are split on `:`.
+fn fish_words(name: &str, value: &str) -> String {
+    let quote_fish = |word: &str| format!("'{}'", word.replace('\\', "\\\\").replace('\'', "\\'"));
+    if name.ends_with("PATH") && !value.is_empty() {
+        value
+            .split(':')
+            .map(quote_fish)
+            .collect::<Vec<_>>()
+            .join(" ")
+    } else {
+        quote_fish(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> BTreeMap<String, String> {
+        BTreeMap::from([
+            (
+                "GREETING".to_string(),
+                "it's \"$HOME\" `id`\nbye".to_string(),
+            ),
+            ("PATH".to_string(), "/nix/store/x/bin:/usr/bin".to_string()),
+        ])
+    }
+
+    #[test]
+    fn renders_each_format() {
+        // Act
+        let json = render_env(EnvFormat::Json, &vars());
+        let dotenv = render_env(EnvFormat::Dotenv, &vars());
+        let sh = render_env(EnvFormat::Sh, &vars());
+        let fish = render_env(EnvFormat::Fish, &vars());
+
+        // Assert
+        assert_eq!(
+            json,
+            "{\n  \"GREETING\": \"it's \\\"$HOME\\\" `id`\\nbye\",\n  \"PATH\": \"/nix/store/x/bin:/usr/bin\"\n}"
+        );
+        assert_eq!(
+            dotenv,
+            "GREETING=\"it's \\\"\\$HOME\\\" \\`id\\`\\nbye\"\nPATH=\"/nix/store/x/bin:/usr/bin\""
+        );
+        assert_eq!(
+            sh,
+            "export GREETING='it'\\''s \"$HOME\" `id`\nbye'\nexport PATH=/nix/store/x/bin:/usr/bin"
+        );
+        assert_eq!(
+            fish,
+            "set -gx GREETING 'it\\'s \"$HOME\" `id`\nbye'\nset -gx PATH '/nix/store/x/bin' '/usr/bin'"
+        );
+        assert_eq!(render_env(EnvFormat::Json, &BTreeMap::new()), "{}");
+    }
+
+    #[test]
+    fn applies_dotenv_files_before_env_vars() {
+        // Arrange
+        let dev_shell: DevShell = serde_yaml::from_str(
+            "package:\n  stable: []\nenv:\n  dotenv: [.env]\n  envvar:\n    - name: MODE\n      value: dev\n",
+        )
+        .unwrap();
+
+        // Act
+        let script = env_script(&dev_shell);
+
+        // Assert
+        assert_eq!(script, "set -a\n. \"$PWD/.env\"\nset +a\nexport MODE=dev");
+        assert!("yaml".parse::<EnvFormat>().is_err());
+    }
+}
@@ -4,15 +4,40 @@
 //! imports; pinned packages reference their per-package imports. Empty
 //! groups are omitted, and an all-empty list yields a placeholder
 //! comment so the generated flake stays valid.
+//!
+//! Packages restricted to some `platforms` are kept out of the list and
+//! appended as `lib.optionals` guards on `system`, one per distinct set
+//! of platforms.
+
+use crate::{DevShell, NixSystem, PackageEntry};
 
-use crate::{DevShellDefinition, PackageEntry, PinnedPackageEntry};
+use super::{input, pinned};
 
-use super::pinned;
+/// A channel (or custom input) package as a list element. Entries with
+/// `with` sub-packages become a parenthesized `withPackages` call, since
+/// an unparenthesized application would be read as several elements.
+fn render_reference(import_name: &str, entry: &PackageEntry) -> String {
+    let package = input::render_reference(import_name, entry);
+    if entry.with_packages.is_empty() {
+        return package;
+    }
+    let sub_packages: Vec<String> = entry
+        .with_packages
+        .iter()
+        .map(|sub| format!("ps.{}", sub))
+        .collect();
+    format!(
+        "({}.withPackages (ps: [ {} ]))",
+        package,
+        sub_packages.join(" ")
+    )
+}
 
 fn render_channel(import_name: &str, entries: &[PackageEntry]) -> String {
     entries
         .iter()
-        .map(|entry| format!("            {}.{}", import_name, entry.name))
+        .filter(|entry| entry.platforms.is_empty())
+        .map(|entry| format!("            {}", render_reference(import_name, entry)))
         .collect::<Vec<_>>()
         .join("\n")
 }
@@ -24,43 +49,106 @@ fn labeled_group(label: &str, body: &str) -> Option<String> {
     Some(format!("            # {} packages\n{}", label, body))
 }
 
-/// Assembles the full `buildInputs` body from all three package sources.
-pub(super) fn render_build_inputs(
-    config: &DevShellDefinition,
-    resolved: &[&PinnedPackageEntry],
-) -> String {
-    let package = &config.dev_shell.package;
+/// Assembles the full `buildInputs` body of one shell from all three
+/// package sources.
+pub(super) fn render_build_inputs(dev_shell: &DevShell) -> String {
+    let package = &dev_shell.package;
+    let resolved: Vec<_> = pinned::collect_resolved(dev_shell)
+        .into_iter()
+        .filter(|entry| entry.platforms.is_empty())
+        .collect();
     let groups = [
         labeled_group("Stable", &render_channel("stablePackages", &package.stable)),
         labeled_group(
             "Unstable",
             &render_channel("unstablePackages", &package.unstable),
         ),
-        labeled_group("Pinned", &pinned::render_packages(resolved)),
+        labeled_group("Pinned", &pinned::render_packages(&resolved)),
     ];
 
     let parts: Vec<String> = groups.into_iter().flatten().collect();
     if parts.is_empty() {
-        return String::from("            # No packages specified");
+        if platform_specific_entries(dev_shell).is_empty() {
+            return String::from("            # No packages specified");
+        }
+        return String::from("            # Platform-specific packages only");
     }
     parts.join("\n")
 }
 
+/// `(platforms, buildInputs entry)` for every package restricted to
+/// some platforms, in declaration order.
+fn platform_specific_entries(dev_shell: &DevShell) -> Vec<(&[NixSystem], String)> {
+    let package = &dev_shell.package;
+    let channels = [
+        ("stablePackages", &package.stable),
+        ("unstablePackages", &package.unstable),
+    ];
+    let channel_entries = channels.into_iter().flat_map(|(import_name, entries)| {
+        entries
+            .iter()
+            .filter(|entry| !entry.platforms.is_empty())
+            .map(move |entry| {
+                (
+                    entry.platforms.as_slice(),
+                    render_reference(import_name, entry),
+                )
+            })
+    });
+    let pinned_entries = pinned::collect_resolved(dev_shell)
+        .into_iter()
+        .filter(|entry| !entry.platforms.is_empty())
+        .map(|entry| (entry.platforms.as_slice(), pinned::render_reference(entry)));
+    channel_entries.chain(pinned_entries).collect()
+}
+
+/// The `++ lib.optionals (...) [ ... ]` suffix appended to the
+/// `buildInputs` list, or an empty string when every package is
+/// installed on all systems.
+pub(super) fn render_platform_guards(dev_shell: &DevShell) -> String {
+    let mut groups: Vec<(&[NixSystem], Vec<String>)> = Vec::new();
+    for (platforms, reference) in platform_specific_entries(dev_shell) {
+        match groups.iter_mut().find(|(seen, _)| *seen == platforms) {
+            Some((_, references)) => references.push(reference),
+            None => groups.push((platforms, vec![reference])),
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(platforms, references)| {
+            let systems = platforms
+                .iter()
+                .map(|system| format!("\"{}\"", system))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let body = references
+                .iter()
+                .map(|reference| format!("            {}", reference))
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!(
+                "\n          ++ stablePackages.lib.optionals (builtins.elem system [ {} ]) [\n{}\n          ]",
+                systems, body
+            )
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn config_from_yaml(yaml: &str) -> DevShellDefinition {
+    fn shell_from_yaml(yaml: &str) -> DevShell {
         serde_yaml::from_str(yaml).unwrap()
     }
 
     #[test]
     fn emits_placeholder_when_no_packages() {
         // Arrange
-        let config = config_from_yaml("devShell:\n  package: {}\n");
+        let dev_shell = shell_from_yaml("package: {}\n");
 
         // Act
-        let build_inputs = render_build_inputs(&config, &[]);
+        let build_inputs = render_build_inputs(&dev_shell);
 
         // Assert
         assert_eq!(build_inputs, "            # No packages specified");
@@ -69,20 +157,19 @@ mod tests {
     #[test]
     fn groups_stable_and_unstable_packages() {
         // Arrange
-        let config = config_from_yaml(
+        let dev_shell = shell_from_yaml(
             r#"
-devShell:
-  package:
-    stable:
-      - name: python312
-      - name: gcc
-    unstable:
-      - name: rust-analyzer
+package:
+  stable:
+    - name: python312
+    - name: gcc
+  unstable:
+    - name: rust-analyzer
 "#,
         );
 
         // Act
-        let build_inputs = render_build_inputs(&config, &[]);
+        let build_inputs = render_build_inputs(&dev_shell);
 
         // Assert
         assert!(build_inputs.contains("# Stable packages"));
@@ -91,4 +178,127 @@ devShell:
         assert!(build_inputs.contains("# Unstable packages"));
         assert!(build_inputs.contains("unstablePackages.rust-analyzer"));
     }
+
+    #[test]
+    fn keeps_platform_specific_packages_out_of_the_plain_list() {
+        // Arrange
+        let dev_shell = shell_from_yaml(
+            r#"
+package:
+  stable:
+    - name: git
+    - name: inotify-tools
+      platforms: [x86_64-linux, aarch64-linux]
+"#,
+        );
+
+        // Act
+        let build_inputs = render_build_inputs(&dev_shell);
+
+        // Assert
+        assert!(build_inputs.contains("stablePackages.git"));
+        assert!(!build_inputs.contains("inotify-tools"));
+    }
+
+    #[test]
+    fn groups_guards_by_platform_set() {
+        // Arrange
+        let dev_shell = shell_from_yaml(
+            r#"
+package:
+  stable:
+    - name: inotify-tools
+      platforms: [x86_64-linux, aarch64-linux]
+    - name: darwin.apple_sdk.frameworks.Security
+      platforms: [aarch64-darwin]
+  unstable:
+    - name: strace
+      platforms: [x86_64-linux, aarch64-linux]
+"#,
+        );
+
+        // Act
+        let guards = render_platform_guards(&dev_shell);
+
+        // Assert
+        assert_eq!(
+            guards,
+            r#"
+          ++ stablePackages.lib.optionals (builtins.elem system [ "x86_64-linux" "aarch64-linux" ]) [
+            stablePackages.inotify-tools
+            unstablePackages.strace
+          ]
+          ++ stablePackages.lib.optionals (builtins.elem system [ "aarch64-darwin" ]) [
+            stablePackages.darwin.apple_sdk.frameworks.Security
+          ]"#
+        );
+    }
+
+    #[test]
+    fn guards_resolved_pinned_packages() {
+        // Arrange
+        let dev_shell = shell_from_yaml(
+            r#"
+package:
+  pinned:
+    - name: go
+      version: "1.21.13"
+      platforms: [x86_64-linux]
+      resolvedCommit: e607cb5
+      resolvedAttr: go_1_21
+"#,
+        );
+
+        // Act
+        let build_inputs = render_build_inputs(&dev_shell);
+        let guards = render_platform_guards(&dev_shell);
+
+        // Assert
+        assert_eq!(
+            build_inputs,
+            "            # Platform-specific packages only"
+        );
+        assert!(guards.contains("pinnedPkgs-go-1-21-13.go_1_21"));
+    }
+
+    #[test]
+    fn no_guards_without_platform_specific_packages() {
+        // Arrange
+        let dev_shell = shell_from_yaml("package:\n  stable:\n    - name: git\n");
+
+        // Act
+        let guards = render_platform_guards(&dev_shell);
+
+        // Assert
+        assert!(guards.is_empty());
+    }
+
+    #[test]
+    fn bundles_with_sub_packages_through_with_packages() {
+        // Arrange
+        let dev_shell = shell_from_yaml(
+            r#"
+package:
+  stable:
+    - name: python312
+      with: [requests, numpy]
+  unstable:
+    - name: lua
+      with: [luasocket]
+"#,
+        );
+
+        // Act
+        let build_inputs = render_build_inputs(&dev_shell);
+
+        // Assert
+        assert!(build_inputs.contains(
+            "            (stablePackages.python312.withPackages (ps: [ ps.requests ps.numpy ]))\n"
+        ));
+        assert!(
+            build_inputs.ends_with(
+                "            (unstablePackages.lua.withPackages (ps: [ ps.luasocket ]))"
+            )
+        );
+    }
 }
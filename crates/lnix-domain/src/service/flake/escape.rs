@@ -0,0 +1,41 @@
+//! Escaping for shell code embedded in the generated `shellHook`.
+//!
+//! Env var lines are already shell-quoted (see [`crate::service::shell`]);
+//! they additionally need Nix indented-string (`''...''`) escaping,
+//! because the whole hook is such a string in `flake.nix`.
+
+/// Escapes `text` for verbatim inclusion in a Nix indented string.
+///
+/// `${` would start an interpolation and `''` would end the string, so
+/// a `$` before `{` is written `''$` and a `'` that begins either
+/// sequence is written `''\'`. Other characters pass through.
+pub(super) fn escape_indented_string(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut escaped = String::with_capacity(text.len());
+    for (i, &c) in chars.iter().enumerate() {
+        let next = chars.get(i + 1).copied();
+        let after_next = chars.get(i + 2).copied();
+        match c {
+            '$' if next == Some('{') => escaped.push_str("''$"),
+            '\'' if next == Some('\'') || (next == Some('$') && after_next == Some('{')) => {
+                escaped.push_str(r"''\'")
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_interpolation_and_string_terminators() {
+        // Arrange / Act / Assert
+        assert_eq!(escape_indented_string("${HOME}"), "''${HOME}");
+        assert_eq!(escape_indented_string("a''b"), r"a''\''b");
+        assert_eq!(escape_indented_string("'${x}"), r"''\'''${x}");
+        assert_eq!(escape_indented_string("$HOME 'x'"), "$HOME 'x'");
+    }
+}
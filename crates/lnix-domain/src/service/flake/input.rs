@@ -0,0 +1,106 @@
+//! Renders the flake fragments for custom `inputs`.
+//!
+//! Each input becomes a `<name>.url` line (plus one line per `follows`)
+//! and an `outputs` parameter. Packages taken `from` an input reference
+//! its `packages.${system}` set.
+
+use crate::{FlakeInput, PackageEntry};
+
+/// Flake `inputs` lines, e.g. `fenix.url = "github:nix-community/fenix";`
+/// followed by `fenix.inputs.nixpkgs.follows = "nixpkgs";`.
+pub(super) fn render_inputs(inputs: &[FlakeInput]) -> String {
+    inputs
+        .iter()
+        .flat_map(|input| {
+            let url = format!("    {}.url = \"{}\";", input.name, input.url);
+            let follows = input.follows.iter().map(|(nested, target)| {
+                format!(
+                    "    {}.inputs.{}.follows = \"{}\";",
+                    input.name, nested, target
+                )
+            });
+            std::iter::once(url).chain(follows)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Trailing output parameters, e.g. `, fenix, rust-overlay`.
+pub(super) fn render_output_params(inputs: &[FlakeInput]) -> String {
+    inputs
+        .iter()
+        .map(|input| format!(", {}", input.name))
+        .collect()
+}
+
+/// Reference to a package in the `buildInputs` list: `<import>.<name>`
+/// for channel packages, `<input>.packages.${system}.<name>` for
+/// packages taken `from` a custom input.
+pub(super) fn render_reference(import_name: &str, entry: &PackageEntry) -> String {
+    match &entry.from {
+        Some(input) => format!("{}.packages.${{system}}.{}", input, entry.name),
+        None => format!("{}.{}", import_name, entry.name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs_from_yaml(yaml: &str) -> Vec<FlakeInput> {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn renders_url_and_follows_lines() {
+        // Arrange
+        let inputs = inputs_from_yaml(
+            r#"
+- name: fenix
+  url: github:nix-community/fenix
+  follows:
+    nixpkgs: nixpkgs
+- name: company
+  url: git+https://example.com/flake.git
+"#,
+        );
+
+        // Act
+        let rendered = render_inputs(&inputs);
+
+        // Assert
+        assert_eq!(
+            rendered,
+            r#"    fenix.url = "github:nix-community/fenix";
+    fenix.inputs.nixpkgs.follows = "nixpkgs";
+    company.url = "git+https://example.com/flake.git";"#
+        );
+    }
+
+    #[test]
+    fn renders_output_params_in_declaration_order() {
+        // Arrange
+        let inputs = inputs_from_yaml(
+            "- name: fenix\n  url: github:nix-community/fenix\n- name: company\n  url: github:acme/flake\n",
+        );
+
+        // Act
+        let params = render_output_params(&inputs);
+
+        // Assert
+        assert_eq!(params, ", fenix, company");
+    }
+
+    #[test]
+    fn references_packages_of_the_from_input() {
+        // Arrange
+        let entry: PackageEntry =
+            serde_yaml::from_str("name: stable.toolchain\nfrom: fenix\n").unwrap();
+
+        // Act
+        let reference = render_reference("stablePackages", &entry);
+
+        // Assert
+        assert_eq!(reference, "fenix.packages.${system}.stable.toolchain");
+    }
+}
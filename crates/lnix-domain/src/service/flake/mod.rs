@@ -2,28 +2,39 @@
 //!
 //! This module orchestrates the section renderers (inputs, `let`
 //! bindings, `buildInputs`, shell hook) and stitches them into the
-//! final flake template. The per-section logic lives in the submodules.
+//! final flake template. The inputs and `let` bindings are shared by
+//! every dev shell; each shell then gets its own `devShells.<name>`
+//! entry. The per-section logic lives in the submodules.
 
 mod build_inputs;
-mod path;
+mod escape;
+mod input;
+mod nixpkgs_import;
 pub mod pinned;
 mod shell_hook;
-mod test_runner;
 
-use crate::DevShellDefinition;
+use crate::{DevShell, DevShellDefinition, ShellName};
 
-use build_inputs::render_build_inputs;
+use build_inputs::{render_build_inputs, render_platform_guards};
 use shell_hook::compose_shell_hook;
 
 /// Default stable nixpkgs revision, used unless overridden by settings.
 const DEFAULT_STABLE_URL: &str = "github:NixOS/nixpkgs/nixos-25.11";
 
-fn render_inputs_section(stable_url: &str, resolved: &[&crate::PinnedPackageEntry]) -> String {
+fn render_inputs_section(
+    stable_url: &str,
+    custom: &[crate::FlakeInput],
+    resolved: &[&crate::PinnedPackageEntry],
+) -> String {
     let mut lines = vec![
         format!("    nixpkgs.url = \"{}\";", stable_url),
         "    nixpkgs-unstable.url = \"github:NixOS/nixpkgs/nixos-unstable\";".to_string(),
         "    flake-utils.url = \"github:numtide/flake-utils\";".to_string(),
     ];
+    let custom_inputs = input::render_inputs(custom);
+    if !custom_inputs.is_empty() {
+        lines.push(custom_inputs);
+    }
     let pinned_inputs = pinned::render_inputs(resolved);
     if !pinned_inputs.is_empty() {
         lines.push(pinned_inputs);
@@ -31,38 +42,73 @@ fn render_inputs_section(stable_url: &str, resolved: &[&crate::PinnedPackageEntr
     lines.join("\n")
 }
 
-fn render_let_section(allow_unfree: bool, resolved: &[&crate::PinnedPackageEntry]) -> String {
+fn render_let_section(import_args: &str, resolved: &[&crate::PinnedPackageEntry]) -> String {
     let mut bindings = vec![
         format!(
-            "        stablePackages = import nixpkgs {{\n          inherit system;\n          config.allowUnfree = {};\n        }};",
-            allow_unfree
+            "        stablePackages = import nixpkgs {{\n{}\n        }};",
+            import_args
         ),
         format!(
-            "        unstablePackages = import nixpkgs-unstable {{\n          inherit system;\n          config.allowUnfree = {};\n        }};",
-            allow_unfree
+            "        unstablePackages = import nixpkgs-unstable {{\n{}\n        }};",
+            import_args
         ),
     ];
-    let pinned_bindings = pinned::render_let_bindings(resolved, allow_unfree);
+    let pinned_bindings = pinned::render_let_bindings(resolved, import_args);
     if !pinned_bindings.is_empty() {
         bindings.push(pinned_bindings);
     }
     bindings.join("\n")
 }
 
+fn render_dev_shell(name: &ShellName, dev_shell: &DevShell) -> String {
+    format!(
+        r#"        devShells.{} = stablePackages.mkShell {{
+          buildInputs = [
+{}
+          ]{};
+
+          shellHook = ''
+{}
+          '';
+        }};"#,
+        name,
+        render_build_inputs(dev_shell),
+        render_platform_guards(dev_shell),
+        compose_shell_hook(dev_shell)
+    )
+}
+
+fn render_dev_shells_section(config: &DevShellDefinition) -> String {
+    config
+        .dev_shells
+        .iter()
+        .map(|(name, dev_shell)| render_dev_shell(name, dev_shell))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
 /// Renders the `flake.nix` content for `config`.
 ///
 /// `override_stable_package` replaces the default stable nixpkgs input
 /// when provided (e.g. to pin a different channel or a fork).
 pub fn render_flake(config: &DevShellDefinition, override_stable_package: Option<&str>) -> String {
-    let allow_unfree = config.dev_shell.allow_unfree;
+    // The nixpkgs imports are shared, so unfree packages are allowed
+    // only when every shell allows them (`validate_config` rejects
+    // shells that disagree).
+    let allow_unfree = config.dev_shells.values().all(|shell| shell.allow_unfree);
     let stable_url = override_stable_package.unwrap_or(DEFAULT_STABLE_URL);
-    let resolved_pinned = pinned::collect_resolved(config);
+    let resolved_pinned = pinned::collect_resolved_inputs(config);
 
-    let inputs_section = render_inputs_section(stable_url, &resolved_pinned);
-    let pinned_output_params = pinned::render_output_params(&resolved_pinned);
-    let let_section = render_let_section(allow_unfree, &resolved_pinned);
-    let build_inputs = render_build_inputs(config, &resolved_pinned);
-    let shell_hook = compose_shell_hook(config);
+    let inputs_section = render_inputs_section(stable_url, &config.inputs, &resolved_pinned);
+    let output_params = format!(
+        "{}{}",
+        input::render_output_params(&config.inputs),
+        pinned::render_output_params(&resolved_pinned)
+    );
+    let import_args =
+        nixpkgs_import::render_import_args(allow_unfree, &config.nixpkgs_config, &config.overlays);
+    let let_section = render_let_section(&import_args, &resolved_pinned);
+    let dev_shells_section = render_dev_shells_section(config);
 
     format!(
         r#"# Generated by LazyNix - DO NOT EDIT MANUALLY
@@ -82,20 +128,12 @@ pub fn render_flake(config: &DevShellDefinition, override_stable_package: Option
 {}
       in
       {{
-        devShells.default = stablePackages.mkShell {{
-          buildInputs = [
 {}
-          ];
-
-          shellHook = ''
-{}
-          '';
-        }};
       }}
     );
 }}
 "#,
-        inputs_section, pinned_output_params, let_section, build_inputs, shell_hook
+        inputs_section, output_params, let_section, dev_shells_section
     )
 }
 
@@ -174,4 +212,312 @@ devShell:
         assert!(!flake.contains("nixpkgs--go--1-21-13"));
         assert!(!flake.contains("pinnedPkgs-go"));
     }
+
+    #[test]
+    fn renders_one_dev_shell_entry_per_named_shell() {
+        let yaml = r#"
+devShells:
+  default:
+    package:
+      stable:
+        - name: bash
+  ci:
+    package:
+      stable:
+        - name: jq
+    shellHook:
+      - echo ci
+"#;
+
+        let flake = render_from_yaml(yaml, None);
+
+        assert!(flake.contains("devShells.default = stablePackages.mkShell {"));
+        assert!(flake.contains("devShells.ci = stablePackages.mkShell {"));
+        let ci_section =
+            &flake[flake.find("devShells.ci").unwrap()..flake.find("devShells.default").unwrap()];
+        assert!(ci_section.contains("stablePackages.jq"));
+        assert!(ci_section.contains("echo ci"));
+        assert!(!ci_section.contains("stablePackages.bash"));
+    }
+
+    #[test]
+    fn legacy_dev_shell_renders_as_default() {
+        let flake = render_from_yaml(BASIC, None);
+
+        assert!(flake.contains("devShells.default = stablePackages.mkShell {"));
+        assert_eq!(flake.matches("mkShell").count(), 1);
+    }
+
+    #[test]
+    fn appends_platform_guards_to_build_inputs() {
+        let flake = render_from_yaml(
+            "devShell:\n  package:\n    stable:\n      - name: bash\n      - name: inotify-tools\n        platforms: [x86_64-linux]\n",
+            None,
+        );
+
+        assert!(flake.contains(
+            "          ]\n          ++ stablePackages.lib.optionals (builtins.elem system [ \"x86_64-linux\" ]) [\n            stablePackages.inotify-tools\n          ];"
+        ));
+    }
+
+    #[test]
+    fn renders_custom_inputs_and_their_packages() {
+        let yaml = r#"
+inputs:
+  - name: fenix
+    url: github:nix-community/fenix
+    follows:
+      nixpkgs: nixpkgs
+devShell:
+  package:
+    stable:
+      - name: bash
+      - name: stable.toolchain
+        from: fenix
+    pinned:
+      - name: go
+        version: "1.21.13"
+        resolvedCommit: "e607cb5"
+        resolvedAttr: "go_1_21"
+"#;
+
+        let flake = render_from_yaml(yaml, None);
+
+        assert!(flake.contains(
+            "    flake-utils.url = \"github:numtide/flake-utils\";\n    fenix.url = \"github:nix-community/fenix\";\n    fenix.inputs.nixpkgs.follows = \"nixpkgs\";\n"
+        ));
+        assert!(flake.contains(
+            "outputs = { self, nixpkgs, nixpkgs-unstable, flake-utils, fenix, nixpkgs--go--1-21-13 }:"
+        ));
+        assert!(flake.contains("            fenix.packages.${system}.stable.toolchain\n"));
+    }
+
+    #[test]
+    fn applies_overlays_to_every_nixpkgs_import() {
+        let yaml = r#"
+inputs:
+  - name: rust-overlay
+    url: github:oxalica/rust-overlay
+overlays:
+  - from: rust-overlay
+devShell:
+  package:
+    stable:
+      - name: bash
+    pinned:
+      - name: go
+        version: "1.21.13"
+        resolvedCommit: "e607cb5"
+        resolvedAttr: "go_1_21"
+"#;
+
+        let flake = render_from_yaml(yaml, None);
+
+        assert_eq!(
+            flake
+                .matches("          overlays = [ rust-overlay.overlays.default ];\n        };")
+                .count(),
+            3
+        );
+    }
+
+    #[test]
+    fn renders_nixpkgs_config_into_every_nixpkgs_import() {
+        let yaml = r#"
+nixpkgsConfig:
+  permittedInsecurePackages:
+    - openssl-1.1.1w
+devShell:
+  package:
+    stable:
+      - name: bash
+    pinned:
+      - name: go
+        version: "1.21.13"
+        resolvedCommit: "e607cb5"
+        resolvedAttr: "go_1_21"
+"#;
+
+        let flake = render_from_yaml(yaml, None);
+
+        assert_eq!(
+            flake
+                .matches("          config.permittedInsecurePackages = [ \"openssl-1.1.1w\" ];\n")
+                .count(),
+            3
+        );
+    }
+
+    /// Decodes the first `shellHook = ''...''` of `flake` following the
+    /// Nix lexer's indented-string rules. Panics on an interpolation,
+    /// which a literal value must never produce.
+    fn decode_shell_hook(flake: &str) -> String {
+        let start = flake.find("shellHook = ''").unwrap() + "shellHook = ''".len();
+        let chars: Vec<char> = flake[start..].chars().collect();
+        let mut decoded = String::new();
+        let mut i = 0;
+        loop {
+            let rest: String = chars[i..].iter().take(3).collect();
+            if rest == "'''" {
+                decoded.push_str("''");
+                i += 3;
+            } else if rest == "''$" {
+                decoded.push('$');
+                i += 3;
+            } else if rest == "''\\" {
+                match chars[i + 3] {
+                    'n' => decoded.push('\n'),
+                    't' => decoded.push('\t'),
+                    'r' => decoded.push('\r'),
+                    c => decoded.push(c),
+                }
+                i += 4;
+            } else if rest.starts_with("''") {
+                return decoded;
+            } else if rest.starts_with("${") {
+                panic!("unexpected interpolation in shellHook: {}", decoded);
+            } else {
+                decoded.push(chars[i]);
+                i += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn envvar_values_round_trip_through_nix_and_shell() {
+        let values = [
+            "plain",
+            "hello world",
+            "it's \"quoted\"",
+            "$HOME and ${HOME}",
+            "two '' quotes and '${x}'",
+            "back\\slash `tick` ;|&<>*?",
+        ];
+        let envvars: String = values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                format!(
+                    "      - name: V{}\n        value: '{}'\n",
+                    i,
+                    value.replace('\'', "''")
+                )
+            })
+            .collect();
+        let yaml = format!(
+            "devShell:\n  package:\n    stable: []\n  shellHook:\n    - ':'\n  env:\n    envvar:\n{}      - name: EXPANDED\n        value: $ROOT/bin:${{ROOT}}\n        expand: true\n",
+            envvars
+        );
+
+        let flake = render_from_yaml(&yaml, None);
+        let hook = decode_shell_hook(&flake);
+        let printed: String = (0..values.len()).map(|i| format!(" \"$V{}\"", i)).collect();
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!("{}\nprintf '%s\\n'{} \"$EXPANDED\"", hook, printed))
+            .env("ROOT", "/r")
+            .output()
+            .unwrap();
+
+        assert!(output.status.success(), "{:?}", output);
+        let lines: Vec<String> = String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect();
+        let mut expected: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        expected.push("/r/bin:/r".to_string());
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn envvar_ops_combine_with_inherited_values_in_the_shell() {
+        let yaml = r#"
+devShell:
+  package:
+    stable: []
+  shellHook:
+    - ':'
+  env:
+    envvar:
+      - name: FRONT
+        value: ./bin dir
+        op: prepend
+      - name: BACK
+        value: ./src
+        op: append
+        separator: ";"
+      - name: FRESH
+        value: only
+        op: append
+      - name: GONE
+        op: unset
+"#;
+
+        let flake = render_from_yaml(yaml, None);
+        let hook = decode_shell_hook(&flake);
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!(
+                "{}\nprintf '%s\\n' \"$FRONT\" \"$BACK\" \"$FRESH\" \"${{GONE-unset}}\"",
+                hook
+            ))
+            .env("FRONT", "/usr/bin")
+            .env("BACK", "/lib")
+            .env_remove("FRESH")
+            .env("GONE", "host")
+            .output()
+            .unwrap();
+
+        assert!(output.status.success(), "{:?}", output);
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "./bin dir:/usr/bin\n/lib;./src\nonly\nunset\n"
+        );
+    }
+
+    #[test]
+    fn command_envvars_run_at_shell_entry_and_report_failures() {
+        let yaml = r#"
+devShell:
+  package:
+    stable: []
+  shellHook:
+    - ':'
+  env:
+    envvar:
+      - name: GREETING
+        command: printf '%s' "hi ${NAME}"
+      - name: SEARCH
+        command: echo extra
+        op: append
+      - name: BROKEN
+        command: exit 3
+"#;
+
+        let flake = render_from_yaml(yaml, None);
+        let hook = decode_shell_hook(&flake);
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!(
+                "{}\nprintf '%s\\n' \"$GREETING\" \"$SEARCH\" \"${{BROKEN-unset}}\" \"${{__lazynix_value-clean}}\"",
+                hook
+            ))
+            .env("NAME", "nix")
+            .env("SEARCH", "base")
+            .env_remove("BROKEN")
+            .output()
+            .unwrap();
+
+        assert!(output.status.success(), "{:?}", output);
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "hi nix\nbase:extra\nunset\nclean\n"
+        );
+        assert!(
+            String::from_utf8(output.stderr)
+                .unwrap()
+                .contains("lazynix: failed to compute BROKEN from: exit 3")
+        );
+    }
 }
@@ -0,0 +1,129 @@
+//! Renders the argument set passed to every nixpkgs import.
+//!
+//! The stable, unstable, and pinned imports must agree on config and
+//! overlays, or a package would behave differently depending on which
+//! revision it came from, so all of them take their arguments from here.
+
+use crate::{NixpkgsConfig, Overlay, PackageName};
+
+fn render_overlay(overlay: &Overlay) -> String {
+    match overlay {
+        Overlay::Input { from, name } => format!("{}.overlays.{}", from, name),
+        Overlay::File(path) => format!("(import {})", path.to_nix_literal()),
+    }
+}
+
+fn render_string_list(names: &[PackageName]) -> String {
+    let quoted: Vec<String> = names.iter().map(|name| format!("\"{}\"", name)).collect();
+    format!("[ {} ]", quoted.join(" "))
+}
+
+/// `config.*` lines for the options set in `nixpkgs_config`, after the
+/// always-present `allowUnfree`.
+fn render_config(allow_unfree: bool, nixpkgs_config: &NixpkgsConfig) -> Vec<String> {
+    let mut lines = vec![format!("          config.allowUnfree = {};", allow_unfree)];
+    if nixpkgs_config.allow_broken {
+        lines.push("          config.allowBroken = true;".to_string());
+    }
+    if !nixpkgs_config.permitted_insecure_packages.is_empty() {
+        lines.push(format!(
+            "          config.permittedInsecurePackages = {};",
+            render_string_list(&nixpkgs_config.permitted_insecure_packages)
+        ));
+    }
+    if !nixpkgs_config.allow_unfree_packages.is_empty() {
+        // `nixpkgs.lib` is the flake's lib output, which does not depend
+        // on the package set being configured here.
+        lines.push(format!(
+            "          config.allowUnfreePredicate = pkg: builtins.elem (nixpkgs.lib.getName pkg) {};",
+            render_string_list(&nixpkgs_config.allow_unfree_packages)
+        ));
+    }
+    if nixpkgs_config.cuda_support {
+        lines.push("          config.cudaSupport = true;".to_string());
+    }
+    if nixpkgs_config.rocm_support {
+        lines.push("          config.rocmSupport = true;".to_string());
+    }
+    lines
+}
+
+/// The body of `import <nixpkgs> { ... }`, one attribute per line,
+/// indented for the flake's `let` block.
+pub(super) fn render_import_args(
+    allow_unfree: bool,
+    nixpkgs_config: &NixpkgsConfig,
+    overlays: &[Overlay],
+) -> String {
+    let mut lines = vec!["          inherit system;".to_string()];
+    lines.extend(render_config(allow_unfree, nixpkgs_config));
+    if !overlays.is_empty() {
+        let rendered: Vec<String> = overlays.iter().map(render_overlay).collect();
+        lines.push(format!("          overlays = [ {} ];", rendered.join(" ")));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn omits_overlays_when_none_are_declared() {
+        // Arrange / Act
+        let args = render_import_args(true, &NixpkgsConfig::default(), &[]);
+
+        // Assert
+        assert_eq!(
+            args,
+            "          inherit system;\n          config.allowUnfree = true;"
+        );
+    }
+
+    #[test]
+    fn renders_input_and_file_overlays_in_order() {
+        // Arrange
+        let overlays: Vec<Overlay> = serde_yaml::from_str(
+            "- from: rust-overlay\n- path: nix/overlay.nix\n- from: company\n  name: python-fixes\n",
+        )
+        .unwrap();
+
+        // Act
+        let args = render_import_args(false, &NixpkgsConfig::default(), &overlays);
+
+        // Assert
+        assert!(args.ends_with(
+            "          overlays = [ rust-overlay.overlays.default (import ./nix/overlay.nix) company.overlays.python-fixes ];"
+        ));
+    }
+
+    #[test]
+    fn renders_every_nixpkgs_config_option() {
+        // Arrange
+        let nixpkgs_config: NixpkgsConfig = serde_yaml::from_str(
+            r#"
+allowBroken: true
+permittedInsecurePackages: [openssl-1.1.1w, nodejs-16.20.2]
+allowUnfreePackages: [vscode]
+cudaSupport: true
+rocmSupport: true
+"#,
+        )
+        .unwrap();
+
+        // Act
+        let args = render_import_args(false, &nixpkgs_config, &[]);
+
+        // Assert
+        assert_eq!(
+            args,
+            r#"          inherit system;
+          config.allowUnfree = false;
+          config.allowBroken = true;
+          config.permittedInsecurePackages = [ "openssl-1.1.1w" "nodejs-16.20.2" ];
+          config.allowUnfreePredicate = pkg: builtins.elem (nixpkgs.lib.getName pkg) [ "vscode" ];
+          config.cudaSupport = true;
+          config.rocmSupport = true;"#
+        );
+    }
+}
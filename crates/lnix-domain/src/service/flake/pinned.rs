@@ -9,7 +9,9 @@
 //! (see `lnix_infra::persistence::flake_reader`); keeping them in one
 //! place prevents silent breakage when the writer format changes.
 
-use crate::{DevShellDefinition, PinnedPackageEntry};
+use std::collections::HashSet;
+
+use crate::{DevShell, DevShellDefinition, PinnedPackageEntry};
 
 /// Prefix of a flake input name for a pinned package
 /// (`nixpkgs--<name>--<dashed-version>`).
@@ -49,11 +51,11 @@ fn binding_name(entry: &PinnedPackageEntry) -> String {
     )
 }
 
-/// Collects the pinned entries that have been resolved to a concrete
-/// nixpkgs commit and attribute. Unresolved entries are skipped.
-pub(super) fn collect_resolved(config: &DevShellDefinition) -> Vec<&PinnedPackageEntry> {
-    config
-        .dev_shell
+/// Collects the pinned entries of one shell that have been resolved to
+/// a concrete nixpkgs commit and attribute. Unresolved entries are
+/// skipped.
+pub(super) fn collect_resolved(dev_shell: &DevShell) -> Vec<&PinnedPackageEntry> {
+    dev_shell
         .package
         .pinned
         .iter()
@@ -61,14 +63,30 @@ pub(super) fn collect_resolved(config: &DevShellDefinition) -> Vec<&PinnedPackag
         .collect()
 }
 
-/// `buildInputs` entries, e.g. `pinnedPkgs-go-1-21-13.go_1_21`.
+/// Collects the resolved pinned entries of every shell, keeping one
+/// entry per input name so that a version pinned in several shells
+/// becomes a single flake input.
+pub(super) fn collect_resolved_inputs(config: &DevShellDefinition) -> Vec<&PinnedPackageEntry> {
+    let mut seen = HashSet::new();
+    config
+        .dev_shells
+        .values()
+        .flat_map(collect_resolved)
+        .filter(|entry| seen.insert(input_name(entry)))
+        .collect()
+}
+
+/// Reference to a resolved package, e.g. `pinnedPkgs-go-1-21-13.go_1_21`.
+pub(super) fn render_reference(entry: &PinnedPackageEntry) -> String {
+    let attr = entry.resolved_attr.as_ref().unwrap();
+    format!("{}.{}", binding_name(entry), attr)
+}
+
+/// `buildInputs` entries, one [`render_reference`] per line.
 pub(super) fn render_packages(resolved: &[&PinnedPackageEntry]) -> String {
     resolved
         .iter()
-        .map(|entry| {
-            let attr = entry.resolved_attr.as_ref().unwrap();
-            format!("            {}.{}", binding_name(entry), attr)
-        })
+        .map(|entry| format!("            {}", render_reference(entry)))
         .collect::<Vec<_>>()
         .join("\n")
 }
@@ -102,16 +120,17 @@ pub(super) fn render_output_params(resolved: &[&PinnedPackageEntry]) -> String {
     format!(", {}", params)
 }
 
-/// `let` bindings importing each pinned revision with the unfree flag.
-pub(super) fn render_let_bindings(resolved: &[&PinnedPackageEntry], allow_unfree: bool) -> String {
+/// `let` bindings importing each pinned revision with the shared
+/// nixpkgs import arguments.
+pub(super) fn render_let_bindings(resolved: &[&PinnedPackageEntry], import_args: &str) -> String {
     resolved
         .iter()
         .map(|entry| {
             format!(
-                "        {} = import {} {{\n          inherit system;\n          config.allowUnfree = {};\n        }};",
+                "        {} = import {} {{\n{}\n        }};",
                 binding_name(entry),
                 input_name(entry),
-                allow_unfree
+                import_args
             )
         })
         .collect::<Vec<_>>()
@@ -163,4 +182,32 @@ resolvedAttr: "go_1_21"
     fn output_params_are_empty_without_resolved_entries() {
         assert_eq!(render_output_params(&[]), "");
     }
+
+    #[test]
+    fn shares_one_input_for_a_version_pinned_in_several_shells() {
+        let config: DevShellDefinition = serde_yaml::from_str(
+            r#"
+devShells:
+  default:
+    package:
+      pinned:
+        - name: go
+          version: "1.21.13"
+          resolvedCommit: "e607cb5"
+          resolvedAttr: "go_1_21"
+  ci:
+    package:
+      pinned:
+        - name: go
+          version: "1.21.13"
+          resolvedCommit: "e607cb5"
+          resolvedAttr: "go_1_21"
+"#,
+        )
+        .unwrap();
+
+        let resolved = collect_resolved_inputs(&config);
+
+        assert_eq!(resolved.len(), 1);
+    }
 }
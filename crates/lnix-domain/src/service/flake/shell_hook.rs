@@ -1,13 +1,13 @@
 //! Composes the dev shell's `shellHook` from its ordered fragments.
 //!
 //! Order is significant: dotenv files load first, then explicit env
-//! vars, then shell aliases, then the user's own hook lines, and
-//! finally the test-runner block (when in test mode).
+//! vars, then shell aliases, and finally the user's own hook lines.
+//! Tests are not part of the hook: `lnix test` runs them itself.
 
-use crate::{DevShellDefinition, EnvVar};
+use crate::service::shell::{render_envvar, resolve_path};
+use crate::{DevShell, EnvVar};
 
-use super::path::resolve_path;
-use super::test_runner::render_test_execution;
+use super::escape::escape_indented_string;
 
 fn render_dotenv_loading(dotenv_files: &[String]) -> String {
     dotenv_files
@@ -26,10 +26,17 @@ fn render_dotenv_loading(dotenv_files: &[String]) -> String {
         .join("\n\n")
 }
 
+/// One line per variable. Values are exported verbatim unless the
+/// entry opts into `expand`.
 fn render_envvar_export(envvars: &[EnvVar]) -> String {
     envvars
         .iter()
-        .map(|envvar| format!("            export {}={}", envvar.name, envvar.value))
+        .flat_map(|envvar| {
+            escape_indented_string(&render_envvar(envvar))
+                .lines()
+                .map(|line| format!("            {}", line))
+                .collect::<Vec<_>>()
+        })
         .collect::<Vec<_>>()
         .join("\n")
 }
@@ -62,22 +69,8 @@ fn render_user_hook(shell_hook: &[String]) -> String {
         .join("\n")
 }
 
-fn render_test_block(tests: &[String]) -> String {
-    let test_execution = render_test_execution(tests);
-    if test_execution.is_empty() {
-        return String::new();
-    }
-    format!(
-        r#"if [ "$LAZYNIX_TEST_MODE" = "1" ]; then
-{}
-fi"#,
-        test_execution
-    )
-}
-
-/// Assembles the full `shellHook` body in fragment order.
-pub(super) fn compose_shell_hook(config: &DevShellDefinition) -> String {
-    let dev_shell = &config.dev_shell;
+/// Assembles the full `shellHook` body of one shell in fragment order.
+pub(super) fn compose_shell_hook(dev_shell: &DevShell) -> String {
     let env = dev_shell.env.as_ref();
 
     let fragments = [
@@ -87,7 +80,6 @@ pub(super) fn compose_shell_hook(config: &DevShellDefinition) -> String {
             .unwrap_or_default(),
         render_shell_alias_loading(&dev_shell.shell_alias),
         render_user_hook(&dev_shell.shell_hook),
-        render_test_block(&dev_shell.test),
     ];
 
     fragments
@@ -100,18 +92,20 @@ pub(super) fn compose_shell_hook(config: &DevShellDefinition) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{DevShellDefinition, ShellName};
 
-    fn config_from_yaml(yaml: &str) -> DevShellDefinition {
-        serde_yaml::from_str(yaml).unwrap()
+    fn shell_from_yaml(yaml: &str) -> DevShell {
+        let config: DevShellDefinition = serde_yaml::from_str(yaml).unwrap();
+        config.shell(&ShellName::default()).unwrap().clone()
     }
 
     #[test]
     fn defaults_to_welcome_message_when_empty() {
         // Arrange
-        let config = config_from_yaml("devShell:\n  package:\n    stable:\n      - name: bash\n");
+        let dev_shell = shell_from_yaml("devShell:\n  package:\n    stable:\n      - name: bash\n");
 
         // Act
-        let hook = compose_shell_hook(&config);
+        let hook = compose_shell_hook(&dev_shell);
 
         // Assert
         assert_eq!(hook, "            echo \"Welcome to LazyNix DevShell!\"");
@@ -120,7 +114,7 @@ mod tests {
     #[test]
     fn orders_dotenv_then_envvar_then_alias_then_user_hook() {
         // Arrange
-        let config = config_from_yaml(
+        let dev_shell = shell_from_yaml(
             r#"
 devShell:
   package:
@@ -140,7 +134,7 @@ devShell:
         );
 
         // Act
-        let hook = compose_shell_hook(&config);
+        let hook = compose_shell_hook(&dev_shell);
 
         // Assert
         let dotenv_pos = hook.find("Load dotenv").unwrap();
@@ -153,9 +147,9 @@ devShell:
     }
 
     #[test]
-    fn gates_test_block_behind_test_mode() {
+    fn leaves_tests_out_of_the_hook() {
         // Arrange
-        let config = config_from_yaml(
+        let dev_shell = shell_from_yaml(
             r#"
 devShell:
   package:
@@ -167,10 +161,76 @@ devShell:
         );
 
         // Act
-        let hook = compose_shell_hook(&config);
+        let hook = compose_shell_hook(&dev_shell);
+
+        // Assert
+        assert!(!hook.contains("LAZYNIX_TEST_MODE"));
+        assert!(!hook.contains("pytest"));
+    }
+
+    #[test]
+    fn quotes_envvar_values_literally_unless_expand_is_set() {
+        // Arrange
+        let dev_shell = shell_from_yaml(
+            r#"
+devShell:
+  package:
+    stable: []
+  env:
+    envvar:
+      - name: PLAIN
+        value: ./src
+      - name: GREETING
+        value: hello ${USER}
+      - name: TOOLS
+        value: $PWD/bin
+        expand: true
+"#,
+        );
+
+        // Act
+        let hook = compose_shell_hook(&dev_shell);
 
         // Assert
-        assert!(hook.contains("if [ \"$LAZYNIX_TEST_MODE\" = \"1\" ]"));
-        assert!(hook.contains("pytest"));
+        assert!(hook.contains("            export PLAIN=./src\n"));
+        assert!(hook.contains("            export GREETING='hello ''${USER}'\n"));
+        assert!(hook.contains("            export TOOLS=\"$PWD/bin\"\n"));
+    }
+
+    #[test]
+    fn renders_prepend_append_and_unset() {
+        // Arrange
+        let dev_shell = shell_from_yaml(
+            r#"
+devShell:
+  package:
+    stable: []
+  env:
+    envvar:
+      - name: PATH
+        value: ./node_modules/.bin
+        op: prepend
+      - name: PYTHONPATH
+        value: ./src
+        op: append
+        separator: ";"
+      - name: PYTHONHOME
+        op: unset
+"#,
+        );
+
+        // Act
+        let hook = compose_shell_hook(&dev_shell);
+
+        // Assert
+        assert!(
+            hook.contains("            export PATH=./node_modules/.bin''${PATH:+:\"$PATH\"}\n")
+        );
+        assert!(
+            hook.contains(
+                "            export PYTHONPATH=''${PYTHONPATH:+\"$PYTHONPATH\"';'}./src\n"
+            )
+        );
+        assert!(hook.contains("            unset PYTHONHOME\n"));
     }
 }
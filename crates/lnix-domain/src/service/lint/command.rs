@@ -0,0 +1,202 @@
+//! Checks that env var `command`s run binaries the dev shell provides.
+//!
+//! Package attribute names and binary names do not map one to one
+//! (`gnupg` ships `gpg`), so the check is a heuristic that only yields
+//! warnings: a binary counts as provided when it is the last segment of
+//! some package's attribute path, with or without a version suffix
+//! (`go_1_22`, `python312`), or when [`ALIASES`] names that package.
+
+use crate::error::Diagnostic;
+use crate::{DevShell, DevShellDefinition};
+
+/// Shell builtins and tools every `mkShell` environment already has.
+const ALWAYS_AVAILABLE: &[&str] = &[
+    "awk", "basename", "bash", "cat", "cd", "command", "cut", "date", "diff", "dirname", "echo",
+    "env", "eval", "exec", "false", "find", "grep", "head", "hostname", "id", "ls", "make",
+    "mktemp", "patch", "printf", "pwd", "read", "readlink", "realpath", "sed", "sh", "sort",
+    "tail", "tar", "test", "tr", "true", "uname", "uniq", "wc", "whoami", "xargs",
+];
+
+/// Binaries shipped by a package of another name, keyed by binary.
+/// Package names are compared without their version suffix.
+const ALIASES: &[(&str, &[&str])] = &[
+    ("aws", &["awscli", "awscli2"]),
+    ("gpg", &["gnupg"]),
+    ("node", &["nodejs"]),
+    ("npm", &["nodejs"]),
+    ("npx", &["nodejs"]),
+    ("psql", &["postgresql"]),
+    ("python3", &["python"]),
+    ("rg", &["ripgrep"]),
+    ("sqlite3", &["sqlite"]),
+];
+
+/// The binary a command starts with, skipping leading `NAME=value`
+/// assignments. `None` for paths (`./scripts/x`), which are project
+/// files rather than packages.
+fn command_binary(command: &str) -> Option<&str> {
+    let binary = command
+        .split_whitespace()
+        .find(|word| !word.contains('='))?;
+    (!binary.contains('/')).then_some(binary)
+}
+
+fn provides(dev_shell: &DevShell, binary: &str) -> bool {
+    let package = &dev_shell.package;
+    let mut names = package
+        .stable
+        .iter()
+        .chain(&package.unstable)
+        .map(|entry| &entry.name)
+        .chain(package.pinned.iter().map(|entry| &entry.name));
+    let aliases = ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == binary)
+        .map_or(&[][..], |(_, packages)| *packages);
+    names.any(|name| {
+        let last = name.as_str().rsplit('.').next().unwrap_or_default();
+        let unversioned = without_version(last);
+        last == binary || unversioned == binary || aliases.contains(&unversioned)
+    })
+}
+
+/// `name` without a trailing version such as `312` or `_1_22`.
+fn without_version(name: &str) -> &str {
+    let base = name.trim_end_matches(|c: char| c.is_ascii_digit() || c == '_');
+    if base.is_empty() { name } else { base }
+}
+
+/// Returns a [`Diagnostic::CommandNotInPackages`] for every env var
+/// whose `command` starts with a binary no package of its dev shell
+/// appears to provide.
+pub fn check_envvar_commands(config: &DevShellDefinition) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for (shell_name, dev_shell) in &config.dev_shells {
+        let Some(env) = &dev_shell.env else {
+            continue;
+        };
+        for envvar in &env.envvar {
+            let Some(binary) = envvar.command.as_deref().and_then(command_binary) else {
+                continue;
+            };
+            if ALWAYS_AVAILABLE.contains(&binary) || provides(dev_shell, binary) {
+                continue;
+            }
+            diagnostics.push(Diagnostic::CommandNotInPackages {
+                shell: shell_name.clone(),
+                envvar: envvar.name.clone(),
+                binary: binary.to_string(),
+            });
+        }
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_from_yaml(yaml: &str) -> DevShellDefinition {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn warns_when_no_package_provides_the_binary() {
+        // Arrange
+        let config = config_from_yaml(
+            r#"
+devShell:
+  package:
+    stable:
+      - name: jq
+  env:
+    envvar:
+      - name: TOKEN
+        command: op read op://vault/token
+"#,
+        );
+
+        // Act
+        let diagnostics = check_envvar_commands(&config);
+
+        // Assert
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::CommandNotInPackages {
+                shell: "default".parse().unwrap(),
+                envvar: "TOKEN".parse().unwrap(),
+                binary: "op".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn accepts_packaged_builtin_and_local_binaries() {
+        // Arrange
+        let config = config_from_yaml(
+            r#"
+devShell:
+  package:
+    stable:
+      - name: git
+      - name: python312
+  env:
+    envvar:
+      - name: GIT_SHA
+        command: git rev-parse HEAD
+      - name: PY_VERSION
+        command: LC_ALL=C python3 --version
+      - name: TOOL_DIR
+        command: pwd
+      - name: BUILD_ID
+        command: ./scripts/build-id
+"#,
+        );
+
+        // Act
+        let diagnostics = check_envvar_commands(&config);
+
+        // Assert
+        assert!(diagnostics.is_empty(), "got: {diagnostics:?}");
+    }
+
+    #[test]
+    fn does_not_count_a_package_whose_name_only_shares_a_prefix() {
+        // Arrange
+        let config = config_from_yaml(
+            r#"
+devShell:
+  package:
+    stable:
+      - name: gopass
+      - name: openssl
+      - name: gitleaks
+      - name: nodejs_20
+      - name: go_1_22
+  env:
+    envvar:
+      - name: GO_VERSION
+        command: go version
+      - name: TOKEN
+        command: op read op://vault/token
+      - name: SHA
+        command: git rev-parse HEAD
+      - name: NODE_VERSION
+        command: node --version
+"#,
+        );
+
+        // Act
+        let diagnostics = check_envvar_commands(&config);
+
+        // Assert
+        let binaries: Vec<&str> = diagnostics
+            .iter()
+            .map(|diagnostic| match diagnostic {
+                Diagnostic::CommandNotInPackages { binary, .. } => binary.as_str(),
+                other => panic!("unexpected {other:?}"),
+            })
+            .collect();
+        assert_eq!(binaries, vec!["op", "git"]);
+    }
+}
@@ -0,0 +1,251 @@
+//! The "pin" quick fix: moves a `stable` package to `pinned`, at the
+//! version the package index has for it.
+
+use crate::interface::gateway::PackageInfo;
+use crate::interface::lsp::{CodeAction, TextEdit, TextRange};
+use crate::service::lsp::outline::{LineIndex, Node, Outline, Span, path_matches};
+
+/// Pin actions for the packages `range` touches.
+///
+/// Only entries that are just a `name` are offered, so that nothing
+/// set on the entry is lost in the move. `unstable` entries are not:
+/// the index knows the versions of the stable channel only.
+pub fn pin_actions(text: &str, range: TextRange, packages: &[PackageInfo]) -> Vec<CodeAction> {
+    let outline = Outline::parse(text);
+    let index = LineIndex::new(text);
+    outline
+        .nodes()
+        .iter()
+        .filter(|node| {
+            node.in_shell()
+                .is_some_and(|(_, rest)| path_matches(rest, &["package", "stable", "[]"]))
+        })
+        .filter(|item| lines_range(&index, item).touches(&range))
+        .filter_map(|item| pin_action(text, &outline, &index, item, packages))
+        .collect()
+}
+
+fn pin_action(
+    text: &str,
+    outline: &Outline,
+    index: &LineIndex,
+    item: &Node,
+    packages: &[PackageInfo],
+) -> Option<CodeAction> {
+    let mut fields = outline.children(item);
+    let name = fields
+        .next()
+        .filter(|field| field.key_name() == Some("name"))?;
+    if fields.next().is_some() {
+        return None;
+    }
+    let attr = &name.value.as_ref()?.text;
+    let package = packages
+        .binary_search_by(|package| package.attr.as_str().cmp(attr))
+        .ok()
+        .map(|i| &packages[i])?;
+    let channel = outline.get(&item.path[..item.path.len() - 1])?;
+    let package_node = outline.get(&channel.path[..channel.path.len() - 1])?;
+
+    // The last entry takes its `stable:` key with it.
+    let removed = if outline.children(channel).count() == 1 {
+        channel
+    } else {
+        item
+    };
+    let delete = lines_span(index, removed.line, removed.last_line);
+
+    let entry = |indent: &str| {
+        format!(
+            "{indent}- name: {}\n{indent}  version: \"{}\"\n",
+            package.attr, package.version
+        )
+    };
+    let pinned = outline
+        .children(package_node)
+        .find(|child| child.key_name() == Some("pinned"));
+    let block_item = pinned.and_then(|pinned| {
+        outline
+            .children(pinned)
+            .find(|child| child.line > pinned.line)
+    });
+    let (target, mut new_text) = match (pinned, block_item) {
+        (Some(pinned), Some(first)) => {
+            let at = index.line_start(pinned.last_line + 1);
+            (
+                Span { start: at, end: at },
+                entry(&" ".repeat(first.column)),
+            )
+        }
+        (pinned, _) => {
+            let key_indent = " ".repeat(channel.column);
+            let item_indent = key_indent.clone() + &" ".repeat(item.column - channel.column);
+            let target = match pinned {
+                // `pinned: []` gives way to the block.
+                Some(pinned) => lines_span(index, pinned.line, pinned.last_line),
+                None => {
+                    let at = index.line_start(package_node.last_line + 1);
+                    Span { start: at, end: at }
+                }
+            };
+            (
+                target,
+                format!("{key_indent}pinned:\n{}", entry(&item_indent)),
+            )
+        }
+    };
+
+    let edits = if target.start == target.end
+        && (target.start == delete.start || target.start == delete.end)
+    {
+        vec![(delete, new_text)]
+    } else {
+        if target.start == text.len() && !text.is_empty() && !text.ends_with('\n') {
+            new_text.insert(0, '\n');
+        }
+        let mut edits = vec![(delete, String::new()), (target, new_text)];
+        edits.sort_by_key(|(span, _)| span.start);
+        edits
+    };
+    Some(CodeAction {
+        title: format!("Pin {} to {}", package.attr, package.version),
+        edits: edits
+            .into_iter()
+            .map(|(span, new_text)| TextEdit {
+                range: index.range(span),
+                new_text,
+            })
+            .collect(),
+    })
+}
+
+/// The bytes of lines `first..=last`, line breaks included.
+fn lines_span(index: &LineIndex, first: usize, last: usize) -> Span {
+    Span {
+        start: index.line_start(first),
+        end: index.line_start(last + 1),
+    }
+}
+
+/// The lines a node spans, as a range ending before the last line break.
+fn lines_range(index: &LineIndex, node: &Node) -> TextRange {
+    index.range(Span {
+        start: index.line_start(node.line),
+        end: index.line_start(node.last_line) + index.line_text(node.last_line).len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interface::lsp::TextPosition;
+
+    fn packages() -> Vec<PackageInfo> {
+        [("go", "1.22.1"), ("jq", "1.7.1"), ("ripgrep", "14.1.0")]
+            .into_iter()
+            .map(|(attr, version)| PackageInfo {
+                attr: attr.to_string(),
+                version: version.to_string(),
+                description: String::new(),
+            })
+            .collect()
+    }
+
+    fn on_line(line: u32) -> TextRange {
+        TextRange::new(TextPosition::new(line, 0), TextPosition::new(line, 0))
+    }
+
+    fn apply(text: &str, action: &CodeAction) -> String {
+        let index = LineIndex::new(text);
+        let mut result = text.to_string();
+        for edit in action.edits.iter().rev() {
+            let start = index.offset(edit.range.start);
+            let end = index.offset(edit.range.end);
+            result.replace_range(start..end, &edit.new_text);
+        }
+        result
+    }
+
+    #[test]
+    fn pins_a_package_into_a_new_pinned_list() {
+        // Arrange
+        let text = "\
+devShell:
+  package:
+    stable:
+      - name: jq
+      - name: ripgrep
+      - name: go
+        from: nixpkgs-go
+  shellHook: echo hi
+";
+
+        // Act
+        let actions = pin_actions(text, on_line(3), &packages());
+        let with_fields = pin_actions(text, on_line(5), &packages());
+
+        // Assert
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].title, "Pin jq to 1.7.1");
+        assert_eq!(
+            apply(text, &actions[0]),
+            "\
+devShell:
+  package:
+    stable:
+      - name: ripgrep
+      - name: go
+        from: nixpkgs-go
+    pinned:
+      - name: jq
+        version: \"1.7.1\"
+  shellHook: echo hi
+"
+        );
+        assert!(with_fields.is_empty());
+    }
+
+    #[test]
+    fn pins_the_last_entry_into_an_existing_pinned_list() {
+        // Arrange
+        let text = "\
+devShell:
+  package:
+    stable:
+      - name: go
+    pinned:
+      - name: nodejs
+        version: \"20.11.0\"
+";
+
+        // Act
+        let actions = pin_actions(text, on_line(3), &packages());
+
+        // Assert
+        assert_eq!(actions.len(), 1);
+        assert_eq!(
+            apply(text, &actions[0]),
+            "\
+devShell:
+  package:
+    pinned:
+      - name: nodejs
+        version: \"20.11.0\"
+      - name: go
+        version: \"1.22.1\"
+"
+        );
+    }
+
+    #[test]
+    fn does_not_offer_to_pin_unstable_packages() {
+        // Arrange
+        let text = "devShell:\n  package:\n    unstable:\n      - name: go\n";
+
+        // Act
+        let actions = pin_actions(text, on_line(3), &packages());
+
+        // Assert
+        assert!(actions.is_empty());
+    }
+}
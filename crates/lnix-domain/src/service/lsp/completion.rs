@@ -0,0 +1,222 @@
+//! Completion of package names (from the package index) and of the
+//! task names a task `deps` on.
+
+use crate::interface::gateway::PackageInfo;
+use crate::interface::lsp::{
+    CompletionItem, CompletionKind, CompletionList, TextPosition, TextRange,
+};
+use crate::service::lsp::outline::{LineIndex, Node, Outline, Segment, Span, path_matches};
+
+/// How many packages one completion offers at most.
+const MAX_PACKAGE_ITEMS: usize = 200;
+
+/// The completions at `position` of `text`.
+pub fn complete(text: &str, position: TextPosition, packages: &[PackageInfo]) -> CompletionList {
+    let outline = Outline::parse(text);
+    let index = LineIndex::new(text);
+    let cursor = index.offset(position);
+    let line = position.line as usize;
+
+    for node in outline.on_line(line) {
+        let Some((shell, rest)) = node.in_shell() else {
+            continue;
+        };
+        if is_package_name(rest) {
+            let Some(span) = value_span(text, node, cursor) else {
+                continue;
+            };
+            let range = index.range(span);
+            let prefix = text[span.start..cursor].trim_start_matches(['"', '\'']);
+            let mut matching = packages
+                .iter()
+                .filter(|package| package.attr.starts_with(prefix));
+            let items: Vec<CompletionItem> = matching
+                .by_ref()
+                .take(MAX_PACKAGE_ITEMS)
+                .map(|package| CompletionItem {
+                    label: package.attr.clone(),
+                    kind: CompletionKind::Package,
+                    detail: Some(package.version.clone()),
+                    documentation: Some(package.description.clone())
+                        .filter(|description| !description.is_empty()),
+                    range,
+                })
+                .collect();
+            return CompletionList {
+                is_incomplete: matching.next().is_some(),
+                items,
+            };
+        }
+        if let [
+            Segment::Key(task_key),
+            Segment::Key(task),
+            Segment::Key(deps),
+            ..,
+        ] = rest
+            && task_key == "task"
+            && deps == "deps"
+        {
+            let span = if rest.len() == 3 {
+                flow_item_span(text, &index, node, cursor)
+            } else if path_matches(rest, &["task", "*", "deps", "[]"]) {
+                value_span(text, node, cursor)
+            } else {
+                None
+            };
+            let Some(span) = span else {
+                continue;
+            };
+            return CompletionList {
+                is_incomplete: false,
+                items: task_items(&outline, shell, task, index.range(span)),
+            };
+        }
+    }
+    CompletionList::default()
+}
+
+/// Whether a path below a dev shell is a package's `name`.
+pub(crate) fn is_package_name(rest: &[Segment]) -> bool {
+    ["stable", "unstable", "pinned"]
+        .iter()
+        .any(|channel| path_matches(rest, &["package", channel, "[]", "name"]))
+}
+
+/// The span of `node`'s scalar value (or the empty span at `cursor`
+/// when it has none yet), if `cursor` is in it.
+fn value_span(text: &str, node: &Node, cursor: usize) -> Option<Span> {
+    match &node.value {
+        Some(value) if (value.span.start..=value.span.end).contains(&cursor) => Some(value.span),
+        Some(_) => None,
+        None => {
+            let after_marker = match &node.key {
+                Some(key) => text[key.span.end..cursor].trim_start().starts_with(':'),
+                None => cursor > node.column,
+            };
+            after_marker.then_some(Span {
+                start: cursor,
+                end: cursor,
+            })
+        }
+    }
+}
+
+/// The span of the flow-sequence item the cursor is in, for `deps: [a, b]`.
+fn flow_item_span(text: &str, index: &LineIndex, node: &Node, cursor: usize) -> Option<Span> {
+    let key = node.key.as_ref()?;
+    let line_end = index.line_start(node.line) + index.line_text(node.line).len();
+    if !(key.span.end..=line_end).contains(&cursor) {
+        return None;
+    }
+    let before = &text[key.span.end..cursor];
+    let open = before.find('[')?;
+    if before[open..].contains(']') {
+        return None;
+    }
+    let item_start = key.span.end + before.rfind([',', '['])? + 1;
+    let after = &text[cursor..line_end];
+    let token = &after[..after.find([',', ']']).unwrap_or(after.len())];
+    Some(Span {
+        start: cursor - text[item_start..cursor].trim_start().len(),
+        end: cursor + token.trim_end().len(),
+    })
+}
+
+/// Every other task of `shell`, described by its `description`.
+fn task_items(
+    outline: &Outline,
+    shell: &str,
+    current: &str,
+    range: TextRange,
+) -> Vec<CompletionItem> {
+    outline
+        .in_shells(&["task", "*"])
+        .filter(|(name, _)| *name == shell)
+        .filter_map(|(_, task)| {
+            let name = task.key_name()?;
+            (name != current).then(|| CompletionItem {
+                label: name.to_string(),
+                kind: CompletionKind::Task,
+                detail: None,
+                documentation: outline
+                    .children(task)
+                    .find(|child| child.key_name() == Some("description"))
+                    .and_then(|child| child.value.as_ref())
+                    .map(|value| value.text.clone()),
+                range,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(attr: &str) -> PackageInfo {
+        PackageInfo {
+            attr: attr.to_string(),
+            version: "1.0".to_string(),
+            description: format!("The {attr} package"),
+        }
+    }
+
+    fn labels(list: &CompletionList) -> Vec<&str> {
+        list.items.iter().map(|item| item.label.as_str()).collect()
+    }
+
+    #[test]
+    fn completes_package_names_by_prefix() {
+        // Arrange
+        let text = "devShell:\n  package:\n    stable:\n      - name: pyth\n      - name: \n";
+        let packages = [package("go"), package("python312"), package("python313")];
+
+        // Act
+        let typed = complete(text, TextPosition::new(3, 18), &packages);
+        let empty = complete(text, TextPosition::new(4, 14), &packages);
+
+        // Assert
+        assert_eq!(labels(&typed), vec!["python312", "python313"]);
+        assert_eq!(typed.items[0].detail.as_deref(), Some("1.0"));
+        assert_eq!(typed.items[0].range.start, TextPosition::new(3, 14));
+        assert_eq!(typed.items[0].range.end, TextPosition::new(3, 18));
+        assert_eq!(labels(&empty).len(), 3);
+    }
+
+    #[test]
+    fn completes_sibling_task_names_in_deps() {
+        // Arrange
+        let text = "\
+devShell:
+  package:
+    stable:
+      - name: bash
+  task:
+    lint:
+      description: Run the linters
+      commands: [ruff]
+    build:
+      commands: [make]
+      deps:
+        - l
+    release:
+      commands: [gh]
+      deps: [lint, b]
+";
+
+        // Act
+        let block = complete(text, TextPosition::new(11, 11), &[]);
+        let flow = complete(text, TextPosition::new(14, 20), &[]);
+        let elsewhere = complete(text, TextPosition::new(9, 18), &[]);
+
+        // Assert
+        assert_eq!(labels(&block), vec!["lint", "release"]);
+        assert_eq!(
+            block.items[0].documentation.as_deref(),
+            Some("Run the linters")
+        );
+        assert_eq!(labels(&flow), vec!["lint", "build"]);
+        assert_eq!(flow.items[0].range.start, TextPosition::new(14, 19));
+        assert!(elsewhere.items.is_empty());
+    }
+}
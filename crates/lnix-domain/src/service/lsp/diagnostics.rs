@@ -0,0 +1,322 @@
+//! Diagnostics for an open `lazynix.yaml`: why it does not parse, or
+//! what `validate_config` finds in it, placed on the text at fault.
+
+use crate::definition::{DevShellDefinition, validate_config};
+use crate::error::{Diagnostic, ValidationError, YamlError};
+use crate::interface::lsp::{LspDiagnostic, Severity, TextRange};
+use crate::service::lsp::outline::{LineIndex, Node, Outline, Segment, Span, path_matches};
+
+/// Diagnoses a document from its text and what parsing it gave.
+///
+/// A document that `extends` others is only checked for its own
+/// syntax and values: the cross-field rules need the merged config.
+pub fn diagnose(text: &str, parsed: &Result<DevShellDefinition, YamlError>) -> Vec<LspDiagnostic> {
+    let outline = Outline::parse(text);
+    let index = LineIndex::new(text);
+    let config = match parsed {
+        Ok(config) => config,
+        Err(error) => {
+            return vec![LspDiagnostic {
+                range: yaml_error_range(&outline, &index, error),
+                severity: Severity::Error,
+                message: error.message.clone(),
+            }];
+        }
+    };
+    if !config.extends.is_empty() {
+        return Vec::new();
+    }
+    match validate_config(config) {
+        Err(error) => vec![LspDiagnostic {
+            range: validation_node(&outline, &error)
+                .map_or_else(|| first_line(&index), |node| key_range(&index, node)),
+            severity: Severity::Error,
+            message: error.to_string(),
+        }],
+        Ok(diagnostics) => diagnostics
+            .iter()
+            .map(|diagnostic| LspDiagnostic {
+                range: diagnostic_node(&outline, diagnostic)
+                    .map_or_else(|| first_line(&index), |node| key_range(&index, node)),
+                severity: Severity::Warning,
+                message: diagnostic.to_string(),
+            })
+            .collect(),
+    }
+}
+
+/// Where a parse error points. Value objects quote the value they
+/// reject, so that value (or key) is highlighted when it can be found
+/// from the parser's location onwards; otherwise the rest of the line.
+fn yaml_error_range(outline: &Outline, index: &LineIndex, error: &YamlError) -> TextRange {
+    let Some((line, column)) = error.location else {
+        return first_line(index);
+    };
+    let line = line.saturating_sub(1);
+    if let Some(quoted) = quoted_value(&error.message) {
+        let found = outline
+            .nodes()
+            .iter()
+            .filter(|node| node.line >= line)
+            .flat_map(|node| node.key.iter().chain(&node.value))
+            .find(|scalar| scalar.text == quoted);
+        if let Some(scalar) = found {
+            return index.range(scalar.span);
+        }
+    }
+    let line_text = index.line_text(line);
+    let start = line_text
+        .char_indices()
+        .nth(column.saturating_sub(1))
+        .map_or(line_text.len(), |(i, _)| i);
+    let end = line_text.trim_end().len().max(start);
+    let line_start = index.line_start(line);
+    index.range(Span {
+        start: line_start + start,
+        end: line_start + end,
+    })
+}
+
+/// The first `'...'` in `message`.
+fn quoted_value(message: &str) -> Option<&str> {
+    let (_, rest) = message.split_once('\'')?;
+    let (value, _) = rest.split_once('\'')?;
+    Some(value)
+}
+
+fn first_line(index: &LineIndex) -> TextRange {
+    index.range(Span {
+        start: 0,
+        end: index.line_text(0).len(),
+    })
+}
+
+/// A mapping entry's key, or else the node's value, or else its line.
+fn key_range(index: &LineIndex, node: &Node) -> TextRange {
+    if let Some(scalar) = node.key.as_ref().or(node.value.as_ref()) {
+        return index.range(scalar.span);
+    }
+    let start = index.line_start(node.line) + node.column;
+    index.range(Span {
+        start,
+        end: index.line_start(node.line) + index.line_text(node.line).len(),
+    })
+}
+
+/// The `n`th node (zero-based) whose scalar value is `value`.
+fn nth_with_value<'a>(
+    nodes: impl IntoIterator<Item = &'a Node>,
+    value: &str,
+    n: usize,
+) -> Option<&'a Node> {
+    let mut matching: Vec<&Node> = nodes
+        .into_iter()
+        .filter(|node| node.value.as_ref().is_some_and(|v| v.text == value))
+        .collect();
+    let last = matching.len().checked_sub(1)?;
+    Some(matching.swap_remove(n.min(last)))
+}
+
+fn top_level<'a>(outline: &'a Outline, pattern: &[&str]) -> Vec<&'a Node> {
+    outline
+        .nodes()
+        .iter()
+        .filter(|node| path_matches(&node.path, pattern))
+        .collect()
+}
+
+fn in_shells<'a>(outline: &'a Outline, pattern: &[&str]) -> Vec<&'a Node> {
+    outline
+        .nodes()
+        .iter()
+        .filter(|node| {
+            node.in_shell()
+                .is_some_and(|(_, rest)| path_matches(rest, pattern))
+        })
+        .collect()
+}
+
+/// The node a validation error is about.
+fn validation_node<'a>(outline: &'a Outline, error: &ValidationError) -> Option<&'a Node> {
+    match error {
+        ValidationError::EmptyTaskCommands(task) => {
+            let task = in_shells(outline, &["task", task]).first().copied()?;
+            let commands = outline
+                .children(task)
+                .find(|child| child.key_name() == Some("commands"));
+            Some(commands.unwrap_or(task))
+        }
+        ValidationError::DuplicateDefaultShell => outline.get(&[
+            Segment::Key("devShells".to_string()),
+            Segment::Key("default".to_string()),
+        ]),
+        ValidationError::ConflictingAllowUnfree => {
+            let mut nodes = in_shells(outline, &["allowUnfree"]).into_iter();
+            let first = nodes.next()?;
+            let first_value = first.value.as_ref().map(|v| v.text.as_str());
+            Some(
+                nodes
+                    .find(|node| node.value.as_ref().map(|v| v.text.as_str()) != first_value)
+                    .unwrap_or(first),
+            )
+        }
+        ValidationError::ReservedInputName(name) => {
+            nth_with_value(top_level(outline, &["inputs", "[]", "name"]), name, 0)
+        }
+        ValidationError::DuplicateInput(name) => {
+            nth_with_value(top_level(outline, &["inputs", "[]", "name"]), name, 1)
+        }
+        ValidationError::UnknownPackageInput { input, .. } => nth_with_value(
+            in_shells(outline, &["package", "*", "[]", "from"]),
+            input,
+            0,
+        ),
+        ValidationError::UnknownFollowsTarget { target, .. } => nth_with_value(
+            top_level(outline, &["inputs", "[]", "follows", "*"]),
+            target,
+            0,
+        ),
+        ValidationError::UnknownOverlayInput(input) => {
+            nth_with_value(top_level(outline, &["overlays", "[]", "from"]), input, 0)
+        }
+        ValidationError::UnknownTaskDependency { task, dependency } => nth_with_value(
+            in_shells(outline, &["task", task, "deps", "[]"]),
+            dependency,
+            0,
+        ),
+        ValidationError::TaskCycle(chain) => {
+            let task = chain.split(" -> ").next()?;
+            in_shells(outline, &["task", task]).first().copied()
+        }
+        ValidationError::DuplicateTaskArg { task, arg } => nth_with_value(
+            in_shells(outline, &["task", task, "args", "[]", "name"]),
+            arg,
+            1,
+        ),
+        ValidationError::DuplicateTestName(name) => {
+            let tests = outline.nodes().iter().filter(|node| {
+                node.in_shell().is_some_and(|(_, rest)| {
+                    path_matches(rest, &["test", "[]"])
+                        || path_matches(rest, &["test", "[]", "name"])
+                        || path_matches(rest, &["test", "tests", "[]"])
+                        || path_matches(rest, &["test", "tests", "[]", "name"])
+                })
+            });
+            nth_with_value(tests, name, 1)
+        }
+        ValidationError::DuplicateServiceName(name) => {
+            nth_with_value(in_shells(outline, &["services", "[]", "name"]), name, 1)
+        }
+        _ => None,
+    }
+}
+
+/// The node a non-fatal finding is about.
+fn diagnostic_node<'a>(outline: &'a Outline, diagnostic: &Diagnostic) -> Option<&'a Node> {
+    match diagnostic {
+        Diagnostic::NoPackages(shell) => {
+            let shell_node = outline.nodes().iter().find(|node| {
+                node.in_shell()
+                    .is_some_and(|(name, rest)| name == shell.as_str() && rest.is_empty())
+            })?;
+            Some(
+                outline
+                    .children(shell_node)
+                    .find(|child| child.key_name() == Some("package"))
+                    .unwrap_or(shell_node),
+            )
+        }
+        Diagnostic::UnfreePackagesIgnored => outline.get(&[
+            Segment::Key("nixpkgsConfig".to_string()),
+            Segment::Key("allowUnfreePackages".to_string()),
+        ]),
+        Diagnostic::CommandNotInPackages { shell, envvar, .. } => outline
+            .in_shells(&["env", "envvar", "[]", "name"])
+            .filter(|(name, _)| *name == shell.as_str())
+            .map(|(_, node)| node)
+            .find(|node| {
+                node.value
+                    .as_ref()
+                    .is_some_and(|v| v.text == envvar.as_str())
+            }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interface::lsp::TextPosition;
+
+    fn parse(text: &str) -> Result<DevShellDefinition, YamlError> {
+        serde_yaml::from_str(text).map_err(|e| YamlError {
+            message: e.to_string(),
+            location: e.location().map(|l| (l.line(), l.column())),
+        })
+    }
+
+    fn range(line: u32, start: u32, end: u32) -> TextRange {
+        TextRange::new(TextPosition::new(line, start), TextPosition::new(line, end))
+    }
+
+    #[test]
+    fn places_value_object_errors_on_the_rejected_value() {
+        // Arrange
+        let text =
+            "devShell:\n  package:\n    stable:\n      - name: bash\n      - name: \"bad name\"\n";
+
+        // Act
+        let diagnostics = diagnose(text, &parse(text));
+
+        // Assert
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].range, range(4, 15, 23));
+        assert!(diagnostics[0].message.contains("Invalid package name"));
+    }
+
+    #[test]
+    fn places_validation_errors_and_warnings() {
+        // Arrange
+        let text = "\
+devShell:
+  package:
+    stable:
+      - name: bash
+  task:
+    build:
+      commands: [make]
+      deps: [lint]
+";
+        let empty = "devShells:\n  docs:\n    package: {}\n";
+
+        // Act
+        let error = diagnose(text, &parse(text));
+        let warning = diagnose(empty, &parse(empty));
+
+        // Assert
+        assert_eq!(error.len(), 1);
+        assert_eq!(error[0].range, range(7, 13, 17));
+        assert!(error[0].message.contains("'lint'"));
+        assert_eq!(warning.len(), 1);
+        assert_eq!(warning[0].severity, Severity::Warning);
+        assert_eq!(warning[0].range, range(2, 4, 11));
+    }
+
+    #[test]
+    fn falls_back_to_the_rest_of_the_line() {
+        // Arrange
+        let text = "devShell:\n  package:\n    stable: [bash]\n";
+
+        // Act
+        let diagnostics = diagnose(text, &parse(text));
+
+        // Assert
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].range.start.line, 2);
+        assert!(
+            diagnostics[0]
+                .message
+                .contains("expected struct PackageEntry")
+        );
+    }
+}
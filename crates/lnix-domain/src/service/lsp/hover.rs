@@ -0,0 +1,66 @@
+//! Hover over a package name: what the package index knows about it.
+
+use crate::interface::gateway::PackageInfo;
+use crate::interface::lsp::{HoverInfo, TextPosition};
+use crate::service::lsp::completion::is_package_name;
+use crate::service::lsp::outline::{LineIndex, Outline};
+
+/// The hover at `position` of `text`, if it is over the `name` of a
+/// package the index knows.
+pub fn hover(text: &str, position: TextPosition, packages: &[PackageInfo]) -> Option<HoverInfo> {
+    let outline = Outline::parse(text);
+    let index = LineIndex::new(text);
+    let cursor = index.offset(position);
+    let value = outline
+        .on_line(position.line as usize)
+        .filter(|node| {
+            node.in_shell()
+                .is_some_and(|(_, rest)| is_package_name(rest))
+        })
+        .find_map(|node| node.value.as_ref())
+        .filter(|value| (value.span.start..=value.span.end).contains(&cursor))?;
+    let package = packages
+        .binary_search_by(|package| package.attr.as_str().cmp(&value.text))
+        .ok()
+        .map(|i| &packages[i])?;
+    let mut markdown = format!("**{}** `{}`", package.attr, package.version);
+    if !package.description.is_empty() {
+        markdown.push_str("\n\n");
+        markdown.push_str(&package.description);
+    }
+    Some(HoverInfo {
+        range: index.range(value.span),
+        markdown,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shows_version_and_description_of_known_packages() {
+        // Arrange
+        let text = "devShell:\n  package:\n    stable:\n      - name: jq\n      - name: nope\n";
+        let packages = [PackageInfo {
+            attr: "jq".to_string(),
+            version: "1.7.1".to_string(),
+            description: "Lightweight and flexible command-line JSON processor".to_string(),
+        }];
+
+        // Act
+        let known = hover(text, TextPosition::new(3, 15), &packages);
+        let unknown = hover(text, TextPosition::new(4, 15), &packages);
+        let key = hover(text, TextPosition::new(3, 9), &packages);
+
+        // Assert
+        let known = known.expect("jq is in the index");
+        assert_eq!(
+            known.markdown,
+            "**jq** `1.7.1`\n\nLightweight and flexible command-line JSON processor"
+        );
+        assert_eq!(known.range.start, TextPosition::new(3, 14));
+        assert_eq!(unknown, None);
+        assert_eq!(key, None);
+    }
+}
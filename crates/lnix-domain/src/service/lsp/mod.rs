@@ -0,0 +1,17 @@
+//! Language-server logic for `lnix lsp`.
+//!
+//! Each feature takes a document's text and answers in LSP terms
+//! (positions in UTF-16 code units). Talking to the editor is I/O and
+//! lives behind [`crate::interface::lsp::LanguageClient`]; the package
+//! list behind [`crate::interface::gateway::PackageIndex`].
+
+mod code_action;
+mod completion;
+mod diagnostics;
+mod hover;
+mod outline;
+
+pub use code_action::pin_actions;
+pub use completion::complete;
+pub use diagnostics::diagnose;
+pub use hover::hover;
@@ -0,0 +1,590 @@
+//! A position-aware outline of a `lazynix.yaml` document.
+//!
+//! serde reports no spans for deserialized values, so the language
+//! server reads the block structure itself: every mapping key and
+//! sequence item, with where its key and scalar value sit. Only the
+//! YAML that `lazynix.yaml` needs is understood — block mappings and
+//! sequences, plain and quoted scalars, comments, block scalars (`|`,
+//! `>`), and one-line flow sequences. Lines it cannot read are skipped,
+//! so a half-typed document still yields an outline.
+
+use crate::interface::lsp::{TextPosition, TextRange};
+
+/// One step of a [`Node`]'s path from the document root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Byte offsets into the document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A scalar value, unquoted, and where it is written (inside any
+/// quotes).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Scalar {
+    pub text: String,
+    pub span: Span,
+}
+
+/// A mapping entry or sequence item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Node {
+    pub path: Vec<Segment>,
+    /// Zero-based line of the key or `-`.
+    pub line: usize,
+    /// Byte column of the key or `-` within its line.
+    pub column: usize,
+    /// The key of a mapping entry.
+    pub key: Option<Scalar>,
+    /// The value, when it is a scalar written on the node's line.
+    pub value: Option<Scalar>,
+    /// The last line of the node, its children included.
+    pub last_line: usize,
+}
+
+impl Node {
+    /// The path below the dev shell the node belongs to, with that
+    /// shell's name: `devShell` is `default`, `devShells.<name>` is
+    /// `<name>`.
+    pub fn in_shell(&self) -> Option<(&str, &[Segment])> {
+        match self.path.as_slice() {
+            [Segment::Key(root), rest @ ..] if root == "devShell" => Some(("default", rest)),
+            [Segment::Key(root), Segment::Key(shell), rest @ ..] if root == "devShells" => {
+                Some((shell, rest))
+            }
+            _ => None,
+        }
+    }
+
+    /// The key of the last path segment, if it is one.
+    pub fn key_name(&self) -> Option<&str> {
+        match self.path.last() {
+            Some(Segment::Key(key)) => Some(key),
+            _ => None,
+        }
+    }
+}
+
+/// Whether `path` matches `pattern`, segment by segment: `*` matches
+/// any key, `[]` any index, anything else that exact key.
+pub(crate) fn path_matches(path: &[Segment], pattern: &[&str]) -> bool {
+    path.len() == pattern.len()
+        && path
+            .iter()
+            .zip(pattern)
+            .all(|(segment, want)| match segment {
+                Segment::Key(key) => *want == "*" || want == key,
+                Segment::Index(_) => *want == "[]",
+            })
+}
+
+/// Converts between byte offsets and LSP positions.
+pub(crate) struct LineIndex<'a> {
+    text: &'a str,
+    starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(text: &'a str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { text, starts }
+    }
+
+    /// The byte offset where `line` starts, or the end of the text.
+    pub fn line_start(&self, line: usize) -> usize {
+        self.starts.get(line).copied().unwrap_or(self.text.len())
+    }
+
+    /// The text of `line`, without its line break.
+    pub fn line_text(&self, line: usize) -> &'a str {
+        let start = self.line_start(line);
+        let end = self.line_start(line + 1);
+        self.text[start..end].trim_end_matches(['\n', '\r'])
+    }
+
+    pub fn position(&self, offset: usize) -> TextPosition {
+        let line = self.starts.partition_point(|&start| start <= offset) - 1;
+        let start = self.starts[line];
+        let character = self.text[start..offset].encode_utf16().count();
+        TextPosition::new(line as u32, character as u32)
+    }
+
+    pub fn range(&self, span: Span) -> TextRange {
+        TextRange::new(self.position(span.start), self.position(span.end))
+    }
+
+    /// The byte offset of `position`, clamped to its line.
+    pub fn offset(&self, position: TextPosition) -> usize {
+        let line = position.line as usize;
+        if line >= self.starts.len() {
+            return self.text.len();
+        }
+        let start = self.starts[line];
+        let mut units = 0;
+        for (i, c) in self.line_text(line).char_indices() {
+            if units >= position.character as usize {
+                return start + i;
+            }
+            units += c.len_utf16();
+        }
+        start + self.line_text(line).len()
+    }
+}
+
+/// The outline of one document.
+pub(crate) struct Outline {
+    nodes: Vec<Node>,
+}
+
+/// An open mapping entry or sequence item that later lines may nest
+/// under.
+struct Frame {
+    column: usize,
+    node: usize,
+    is_item: bool,
+    next_index: usize,
+}
+
+impl Outline {
+    pub fn parse(text: &str) -> Self {
+        let mut scanner = Scanner {
+            nodes: Vec::new(),
+            frames: Vec::new(),
+            root_items: 0,
+            block_owner: None,
+        };
+        let mut offset = 0;
+        for (line, raw) in text.split('\n').enumerate() {
+            scanner.scan_line(line, offset, raw.trim_end_matches('\r'));
+            offset += raw.len() + 1;
+        }
+
+        let mut nodes = scanner.nodes;
+        for i in (0..nodes.len()).rev() {
+            let last_line = nodes[i + 1..]
+                .iter()
+                .take_while(|child| child.path.starts_with(&nodes[i].path))
+                .map(|child| child.last_line)
+                .fold(nodes[i].last_line, usize::max);
+            nodes[i].last_line = last_line;
+        }
+        Self { nodes }
+    }
+
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// The node at `path`, if the document has it.
+    pub fn get(&self, path: &[Segment]) -> Option<&Node> {
+        self.nodes.iter().find(|node| node.path == path)
+    }
+
+    /// The direct children of `parent`, in document order.
+    pub fn children<'a>(&'a self, parent: &'a Node) -> impl Iterator<Item = &'a Node> {
+        self.nodes.iter().filter(move |node| {
+            node.path.len() == parent.path.len() + 1 && node.path.starts_with(&parent.path)
+        })
+    }
+
+    /// The nodes of `line`, outermost first.
+    pub fn on_line(&self, line: usize) -> impl Iterator<Item = &Node> {
+        self.nodes.iter().filter(move |node| node.line == line)
+    }
+
+    /// Nodes of any dev shell whose path below the shell matches
+    /// `pattern` (see [`path_matches`]), with the shell's name.
+    pub fn in_shells<'a>(
+        &'a self,
+        pattern: &'a [&'a str],
+    ) -> impl Iterator<Item = (&'a str, &'a Node)> {
+        self.nodes.iter().filter_map(move |node| {
+            let (shell, rest) = node.in_shell()?;
+            path_matches(rest, pattern).then_some((shell, node))
+        })
+    }
+}
+
+struct Scanner {
+    nodes: Vec<Node>,
+    frames: Vec<Frame>,
+    root_items: usize,
+    /// The node whose block scalar (`|` / `>`) the following, more
+    /// indented lines belong to, with that node's column.
+    block_owner: Option<(usize, usize)>,
+}
+
+impl Scanner {
+    fn scan_line(&mut self, line: usize, offset: usize, raw: &str) {
+        let content = strip_comment(raw).trim_end();
+        let column = content.len() - content.trim_start().len();
+        if let Some((owner, owner_column)) = self.block_owner {
+            if content.trim().is_empty() || column > owner_column {
+                if !content.trim().is_empty() {
+                    self.nodes[owner].last_line = line;
+                }
+                return;
+            }
+            self.block_owner = None;
+        }
+        let rest = &content[column..];
+        if rest.is_empty() || (column == 0 && (rest.starts_with("---") || rest == "...")) {
+            return;
+        }
+        self.scan_entry(line, offset, column, rest, None);
+    }
+
+    /// Reads `rest`, which starts at byte `column` of `line`. `item` is
+    /// the sequence item `rest` follows the `-` of, if any.
+    fn scan_entry(
+        &mut self,
+        line: usize,
+        offset: usize,
+        column: usize,
+        rest: &str,
+        item: Option<usize>,
+    ) {
+        if rest == "-" || rest.starts_with("- ") {
+            while self
+                .frames
+                .last()
+                .is_some_and(|top| top.column > column || (top.column == column && top.is_item))
+            {
+                self.frames.pop();
+            }
+            let index = match self.frames.last_mut() {
+                Some(parent) => {
+                    parent.next_index += 1;
+                    parent.next_index - 1
+                }
+                None => {
+                    self.root_items += 1;
+                    self.root_items - 1
+                }
+            };
+            let node = self.push_node(Segment::Index(index), line, column, None);
+            self.frames.push(Frame {
+                column,
+                node,
+                is_item: true,
+                next_index: 0,
+            });
+            let after = &rest[1..];
+            let trimmed = after.trim_start();
+            if !trimmed.is_empty() {
+                let nested = column + 1 + (after.len() - trimmed.len());
+                self.scan_entry(line, offset, nested, trimmed, Some(node));
+            }
+            return;
+        }
+
+        if let Some((key, after_colon)) = split_key(rest) {
+            while self.frames.last().is_some_and(|top| top.column >= column) {
+                self.frames.pop();
+            }
+            let key = Scalar {
+                text: key.text,
+                span: shift(key.span, offset + column),
+            };
+            let node = self.push_node(Segment::Key(key.text.clone()), line, column, Some(key));
+            self.frames.push(Frame {
+                column,
+                node,
+                is_item: false,
+                next_index: 0,
+            });
+            let value_column = rest.len() - after_colon.len();
+            let trimmed = after_colon.trim_start();
+            let value_column = column + value_column + (after_colon.len() - trimmed.len());
+            self.scan_value(line, offset, value_column, trimmed, node);
+            return;
+        }
+
+        if let Some(item) = item {
+            self.scan_value(line, offset, column, rest, item);
+        }
+    }
+
+    /// Reads the value of `node`, written at byte `column` of `line`.
+    fn scan_value(&mut self, line: usize, offset: usize, column: usize, value: &str, node: usize) {
+        if value.is_empty() {
+            return;
+        }
+        if value.starts_with('|') || value.starts_with('>') {
+            self.block_owner = Some((node, self.nodes[node].column));
+            return;
+        }
+        if let Some(inner) = value.strip_prefix('[') {
+            let Some(inner) = inner.strip_suffix(']') else {
+                return;
+            };
+            let mut item_column = column + 1;
+            for (index, part) in split_flow(inner).into_iter().enumerate() {
+                let trimmed = part.trim_start();
+                let start = item_column + (part.len() - trimmed.len());
+                item_column += part.len() + 1;
+                if let Some(scalar) = read_scalar(trimmed.trim_end()) {
+                    // `node` is the innermost frame, so the item nests under it.
+                    let child = self.push_node(Segment::Index(index), line, start, None);
+                    self.nodes[child].value = Some(Scalar {
+                        text: scalar.text,
+                        span: shift(scalar.span, offset + start),
+                    });
+                }
+            }
+            return;
+        }
+        if value.starts_with('{') {
+            return;
+        }
+        if let Some(scalar) = read_scalar(value) {
+            self.nodes[node].value = Some(Scalar {
+                text: scalar.text,
+                span: shift(scalar.span, offset + column),
+            });
+        }
+    }
+
+    /// Appends a node under the innermost open frame.
+    fn push_node(
+        &mut self,
+        segment: Segment,
+        line: usize,
+        column: usize,
+        key: Option<Scalar>,
+    ) -> usize {
+        let mut path = self
+            .frames
+            .last()
+            .map(|frame| self.nodes[frame.node].path.clone())
+            .unwrap_or_default();
+        path.push(segment);
+        self.nodes.push(Node {
+            path,
+            line,
+            column,
+            key,
+            value: None,
+            last_line: line,
+        });
+        self.nodes.len() - 1
+    }
+}
+
+fn shift(span: Span, by: usize) -> Span {
+    Span {
+        start: span.start + by,
+        end: span.end + by,
+    }
+}
+
+/// `line` without its comment, if any: a `#` at the start or after
+/// whitespace, outside quotes.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(q) if c == q && !(q == '"' && previous == '\\') => quote = None,
+            Some(_) => {}
+            None if (c == '"' || c == '\'') && " :[,-{".contains(previous) => quote = Some(c),
+            None if c == '#' && previous.is_whitespace() => return &line[..i],
+            None => {}
+        }
+        previous = c;
+    }
+    line
+}
+
+/// Splits `key: value` into the key and what follows the colon.
+fn split_key(rest: &str) -> Option<(Scalar, &str)> {
+    let (key, after) = if rest.starts_with('"') || rest.starts_with('\'') {
+        let end = closing_quote(rest)?;
+        (read_scalar(&rest[..=end])?, rest[end + 1..].trim_start())
+    } else {
+        let colon = rest
+            .match_indices(':')
+            .map(|(i, _)| i)
+            .find(|&i| rest[i + 1..].is_empty() || rest[i + 1..].starts_with(' '))?;
+        let text = rest[..colon].trim_end();
+        if text.is_empty() || text.starts_with(['[', '{', '&', '*', '!', '|', '>']) {
+            return None;
+        }
+        let key = Scalar {
+            text: text.to_string(),
+            span: Span {
+                start: 0,
+                end: text.len(),
+            },
+        };
+        (key, &rest[colon..])
+    };
+    let after = after.strip_prefix(':')?;
+    if !after.is_empty() && !after.starts_with(' ') {
+        return None;
+    }
+    Some((key, after))
+}
+
+/// The byte index of the quote closing the one `text` starts with.
+fn closing_quote(text: &str) -> Option<usize> {
+    let quote = text.chars().next()?;
+    let mut chars = text.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if quote == '"' => {
+                chars.next();
+            }
+            '\'' if quote == '\'' && chars.peek().is_some_and(|&(_, next)| next == '\'') => {
+                chars.next();
+            }
+            c if c == quote => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// A scalar written as `text`, with its span relative to `text`.
+/// Quotes are removed (and escapes resolved) so the text compares
+/// equal to what serde sees.
+fn read_scalar(text: &str) -> Option<Scalar> {
+    if text.is_empty() {
+        return None;
+    }
+    let Some(quote) = text.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+        return Some(Scalar {
+            text: text.to_string(),
+            span: Span {
+                start: 0,
+                end: text.len(),
+            },
+        });
+    };
+    let end = closing_quote(text).unwrap_or(text.len());
+    let inner = &text[1..end];
+    let unquoted = if quote == '"' {
+        inner.replace("\\\"", "\"").replace("\\\\", "\\")
+    } else {
+        inner.replace("''", "'")
+    };
+    Some(Scalar {
+        text: unquoted,
+        span: Span { start: 1, end },
+    })
+}
+
+/// Splits the inside of a flow sequence on commas outside quotes.
+fn split_flow(inner: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut quote = None;
+    for (i, c) in inner.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == ',' => {
+                parts.push(&inner[start..i]);
+                start = i + 1;
+            }
+            None => {}
+        }
+    }
+    parts.push(&inner[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(text: &str) -> Segment {
+        Segment::Key(text.to_string())
+    }
+
+    #[test]
+    fn outlines_nested_mappings_and_sequences() {
+        // Arrange
+        let text = "\
+devShell:
+  package:
+    stable:
+      - name: python312 # comment
+      - name: \"go\"
+  shellHook:
+  - |
+    echo hi
+
+  task:
+    build:
+      deps: [lint, 'fmt']
+";
+
+        // Act
+        let outline = Outline::parse(text);
+
+        // Assert
+        let python = outline
+            .get(&[
+                key("devShell"),
+                key("package"),
+                key("stable"),
+                Segment::Index(0),
+                key("name"),
+            ])
+            .unwrap();
+        let value = python.value.as_ref().unwrap();
+        assert_eq!(value.text, "python312");
+        assert_eq!(&text[value.span.start..value.span.end], "python312");
+        let go = outline
+            .nodes()
+            .iter()
+            .find(|n| n.line == 4 && n.key.is_some())
+            .unwrap();
+        assert_eq!(go.value.as_ref().unwrap().text, "go");
+
+        let hook = outline.get(&[key("devShell"), key("shellHook")]).unwrap();
+        assert_eq!(hook.last_line, 7);
+
+        let deps: Vec<&str> = outline
+            .in_shells(&["task", "build", "deps", "[]"])
+            .map(|(shell, node)| {
+                assert_eq!(shell, "default");
+                node.value.as_ref().unwrap().text.as_str()
+            })
+            .collect();
+        assert_eq!(deps, vec!["lint", "fmt"]);
+
+        let package = outline.get(&[key("devShell"), key("package")]).unwrap();
+        assert_eq!(package.last_line, 4);
+        assert_eq!(outline.children(package).count(), 1);
+    }
+
+    #[test]
+    fn converts_offsets_to_utf16_positions() {
+        // Arrange
+        let text = "a: é😀x\nb: y\n";
+        let index = LineIndex::new(text);
+
+        // Act
+        let x = text.find('x').unwrap();
+        let position = index.position(x);
+
+        // Assert
+        assert_eq!(position, TextPosition::new(0, 6));
+        assert_eq!(index.offset(position), x);
+        assert_eq!(
+            index.position(text.find('y').unwrap()),
+            TextPosition::new(1, 3)
+        );
+    }
+}
@@ -4,6 +4,13 @@
 //! display-ready strings. Anything that touches the filesystem, a
 //! subprocess, or stdout belongs behind [`crate::interface`] instead.
 
+pub mod direnv;
+pub mod env;
 pub mod flake;
 pub mod lint;
+pub mod lsp;
+pub mod schema;
+pub mod services;
+pub(crate) mod shell;
 pub mod task;
+pub mod test;
@@ -0,0 +1,85 @@
+//! JSON Schemas for `lazynix.yaml` and `lazynix-settings.yaml`, derived
+//! from the serde types so that editors validate exactly what LazyNix
+//! parses.
+
+use schemars::generate::SchemaSettings;
+use schemars::transform::RecursiveTransform;
+use schemars::{JsonSchema, Schema};
+
+use crate::{DevShellDefinition, Settings};
+
+/// Which file a schema describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaTarget {
+    /// `lazynix.yaml`.
+    Config,
+    /// `lazynix-settings.yaml`.
+    Settings,
+}
+
+/// The schema for `target` as pretty-printed JSON, without a trailing
+/// newline.
+///
+/// Field descriptions come from the doc comments of the definition
+/// types. Unknown fields are rejected, so editors flag typos such as
+/// `shellhook` even where LazyNix itself ignores them.
+pub fn render_schema(target: SchemaTarget) -> String {
+    let schema = match target {
+        SchemaTarget::Config => root_schema::<DevShellDefinition>(
+            "lazynix.yaml",
+            "LazyNix project configuration: the dev shells that `lnix generate` renders into flake.nix.",
+        ),
+        SchemaTarget::Settings => root_schema::<Settings>(
+            "lazynix-settings.yaml",
+            "Per-user LazyNix settings, kept out of version control.",
+        ),
+    };
+    serde_json::to_string_pretty(&schema).expect("a schema always serializes")
+}
+
+fn root_schema<T: JsonSchema>(title: &str, description: &str) -> Schema {
+    let mut schema = SchemaSettings::draft2020_12()
+        .with_transform(RecursiveTransform(plain_description))
+        .into_generator()
+        .into_root_schema_for::<T>();
+    schema.insert("title".to_string(), title.into());
+    schema.insert("description".to_string(), description.into());
+    schema
+}
+
+/// Turns rustdoc links (``[`EnvVar`]``) in a description into plain
+/// code spans, as editors render descriptions as Markdown.
+fn plain_description(schema: &mut Schema) {
+    if let Some(description) = schema.get("description").and_then(|d| d.as_str()) {
+        let plain = description.replace("[`", "`").replace("`]", "`");
+        schema.insert("description".to_string(), plain.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_schema_constrains_names_and_rejects_unknown_fields() {
+        // Act
+        let schema = render_schema(SchemaTarget::Config);
+
+        // Assert
+        assert!(schema.contains(r#""title": "lazynix.yaml""#));
+        assert!(schema.contains(r#""^[A-Za-z_][A-Za-z0-9_-]*$": {"#));
+        assert!(schema.contains(r#""additionalProperties": false"#));
+        assert!(!schema.contains("[`"));
+        assert!(!schema.contains("crate::"));
+    }
+
+    #[test]
+    fn settings_schema_uses_kebab_case_keys() {
+        // Act
+        let schema = render_schema(SchemaTarget::Settings);
+
+        // Assert
+        assert!(schema.contains(r#""override-stable-package""#));
+        assert!(schema.contains(r#""pattern": "^github:"#));
+    }
+}
@@ -0,0 +1,8 @@
+//! Background services: the script each one runs and how `lnix ps`
+//! presents them.
+
+mod script;
+mod status;
+
+pub use script::service_script;
+pub use status::format_service_table;
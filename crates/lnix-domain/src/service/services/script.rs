@@ -0,0 +1,59 @@
+//! The shell scripts a service and its readiness probe run.
+
+use crate::ServiceDef;
+use crate::service::shell::script_in;
+
+/// `command` preceded by `service`'s own environment, as for tests
+/// (see [`crate::test_script`]). Used both for the service's `command`
+/// and for a `ready.command` probe, so that the probe sees what the
+/// service sees.
+pub fn service_script(service: &ServiceDef, command: &str) -> String {
+    script_in(service.dir.as_deref(), service.env.as_ref(), command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::shell::run_sh;
+
+    #[test]
+    fn runs_the_command_in_the_service_dir_and_env() {
+        // Arrange
+        let service: ServiceDef = serde_yaml::from_str(
+            r#"
+name: web
+command: npm start
+dir: web
+env:
+  envvar:
+    - name: PORT
+      value: "3000"
+"#,
+        )
+        .unwrap();
+
+        // Act
+        let script = service_script(&service, "curl -sf localhost:3000");
+
+        // Assert
+        assert_eq!(
+            script,
+            "cd web && export PORT=3000 || exit\ncurl -sf localhost:3000"
+        );
+    }
+
+    #[test]
+    fn does_not_start_in_the_config_root_when_its_dir_is_missing() {
+        // Arrange
+        let service: ServiceDef =
+            serde_yaml::from_str("name: web\ncommand: npm start\ndir: lazynix-missing-dir\n")
+                .unwrap();
+
+        // Act
+        let (code, stdout) = run_sh(&service_script(&service, "echo started"));
+
+        // Assert
+        assert_ne!(code, 0);
+        assert_eq!(stdout, "");
+    }
+}
@@ -0,0 +1,53 @@
+//! `lnix ps` output.
+
+use crate::interface::gateway::ServiceStatus;
+use crate::values::ServiceName;
+
+/// One line per service, in the given order, with the statuses
+/// aligned: `postgres  running (pid 4242)`.
+pub fn format_service_table(services: &[(ServiceName, ServiceStatus)]) -> Vec<String> {
+    let width = services
+        .iter()
+        .map(|(name, _)| name.as_str().len())
+        .max()
+        .unwrap_or_default();
+    services
+        .iter()
+        .map(|(name, status)| {
+            let status = match status {
+                ServiceStatus::Running { pid } => format!("running (pid {pid})"),
+                ServiceStatus::Exited => "exited".to_string(),
+                ServiceStatus::Stopped => "stopped".to_string(),
+            };
+            format!("{:<width$}  {}", name.as_str(), status)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligns_statuses_after_the_longest_name() {
+        // Arrange
+        let services = [
+            ("db".parse().unwrap(), ServiceStatus::Running { pid: 4242 }),
+            ("cache".parse().unwrap(), ServiceStatus::Exited),
+            ("mail".parse().unwrap(), ServiceStatus::Stopped),
+        ];
+
+        // Act
+        let lines = format_service_table(&services);
+
+        // Assert
+        assert_eq!(
+            lines,
+            vec![
+                "db     running (pid 4242)",
+                "cache  exited",
+                "mail   stopped"
+            ]
+        );
+    }
+}
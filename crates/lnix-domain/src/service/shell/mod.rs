@@ -0,0 +1,184 @@
+//! POSIX shell snippets shared by the generated `shellHook` and the
+//! scripts `lnix task` and `lnix test` run: word quoting, path
+//! anchoring, env var assignment, and per-command environments.
+
+mod path;
+
+pub(crate) use path::resolve_path;
+
+use crate::{Env, EnvVar, EnvVarOp};
+
+/// Scratch variable holding a `command` env var's output.
+const COMMAND_OUTPUT_VAR: &str = "__lazynix_value";
+
+/// Characters a POSIX shell never treats specially in an unquoted word.
+fn is_shell_safe(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/' | ':' | ',' | '+' | '@' | '%')
+}
+
+/// Quotes `value` as a single literal shell word.
+///
+/// Values made only of safe characters are left bare; anything else is
+/// single-quoted, with embedded `'` written as `'\''`.
+pub(crate) fn quote(value: &str) -> String {
+    if !value.is_empty() && value.chars().all(is_shell_safe) {
+        return value.to_string();
+    }
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Quotes `value` as a single shell word in which `$NAME` and
+/// `${NAME}` still expand: double quotes, with `\`, `"`, and `` ` ``
+/// escaped.
+pub(crate) fn quote_expanding(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        if matches!(c, '\\' | '"' | '`') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Renders one `envvar` entry as shell code, without indentation.
+/// `prepend` / `append` only insert the separator when the inherited
+/// value is non-empty. A `command` entry runs the command first and
+/// leaves the variable untouched, with a message on stderr, when it
+/// fails.
+pub(crate) fn render_envvar(envvar: &EnvVar) -> String {
+    let value = match &envvar.command {
+        Some(_) => format!("\"${}\"", COMMAND_OUTPUT_VAR),
+        None if envvar.expand => quote_expanding(&envvar.value),
+        None => quote(&envvar.value),
+    };
+    let line = render_envvar_op(envvar, &value);
+    let Some(command) = &envvar.command else {
+        return line;
+    };
+    format!(
+        r#"if {output}=$({command}); then
+  {line}
+else
+  echo "lazynix: failed to compute {name} from:" {quoted} >&2
+fi
+unset {output}"#,
+        output = COMMAND_OUTPUT_VAR,
+        command = command,
+        line = line,
+        name = envvar.name,
+        quoted = quote(command),
+    )
+}
+
+/// Commands that set up a task's, test's, or service's own
+/// environment: load the `dotenv` files, then `cd` into `dir`, then
+/// apply the `envvars`.
+///
+/// Like every other path in `lazynix.yaml`, dotenv files are relative
+/// to the config directory, which is why they load before the `cd`.
+/// Each command is a single `&&`-able entry, so a chain of them stops
+/// at the first one that fails.
+pub(crate) fn render_prelude<'a>(
+    dir: Option<&str>,
+    dotenv: impl IntoIterator<Item = &'a String>,
+    envvars: impl IntoIterator<Item = &'a EnvVar>,
+) -> Vec<String> {
+    let mut prelude = Vec::new();
+    for file in dotenv {
+        prelude.push("set -a".to_string());
+        prelude.push(format!(". \"{}\"", resolve_path(file)));
+        prelude.push("set +a".to_string());
+    }
+    if let Some(dir) = dir {
+        prelude.push(format!("cd {}", quote(dir)));
+    }
+    prelude.extend(envvars.into_iter().map(|envvar| {
+        let rendered = render_envvar(envvar);
+        if rendered.contains('\n') {
+            format!("{{ {}; }}", rendered)
+        } else {
+            rendered
+        }
+    }));
+    prelude
+}
+
+/// `command` run in `dir` with `env` applied (see [`render_prelude`]).
+/// The script exits with the prelude's status if any of it fails, so
+/// `command` never runs in the wrong directory or environment. It is
+/// kept verbatim on its own lines and may span several.
+pub(crate) fn script_in(dir: Option<&str>, env: Option<&Env>, command: &str) -> String {
+    let prelude = render_prelude(
+        dir,
+        env.iter().flat_map(|env| &env.dotenv),
+        env.iter().flat_map(|env| &env.envvar),
+    );
+    if prelude.is_empty() {
+        return command.to_string();
+    }
+    format!("{} || exit\n{}", prelude.join(" && "), command)
+}
+
+fn render_envvar_op(envvar: &EnvVar, value: &str) -> String {
+    let name = &envvar.name;
+    let separator = quote(&envvar.separator);
+    match envvar.op {
+        EnvVarOp::Set => format!("export {}={}", name, value),
+        EnvVarOp::Prepend => format!(
+            "export {}={}${{{}:+{}\"${}\"}}",
+            name, value, name, separator, name
+        ),
+        EnvVarOp::Append => format!(
+            "export {}=${{{}:+\"${}\"{}}}{}",
+            name, name, name, separator, value
+        ),
+        EnvVarOp::Unset => format!("unset {}", name),
+    }
+}
+
+/// Runs `script` with `sh`, returning its exit code and stdout.
+#[cfg(test)]
+pub(crate) fn run_sh(script: &str) -> (i32, String) {
+    let output = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(script)
+        .output()
+        .unwrap();
+    (
+        output.status.code().unwrap_or(-1),
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_safe_words_bare() {
+        // Arrange / Act / Assert
+        assert_eq!(quote("./src"), "./src");
+        assert_eq!(quote("true"), "true");
+    }
+
+    #[test]
+    fn single_quotes_words_with_special_characters() {
+        // Arrange / Act / Assert
+        assert_eq!(quote("hello world"), "'hello world'");
+        assert_eq!(quote("$HOME"), "'$HOME'");
+        assert_eq!(quote("it's"), r"'it'\''s'");
+        assert_eq!(quote(""), "''");
+    }
+
+    #[test]
+    fn double_quotes_expanding_words() {
+        // Arrange / Act / Assert
+        assert_eq!(quote_expanding("$PWD/bin"), "\"$PWD/bin\"");
+        assert_eq!(
+            quote_expanding(r#"say "hi" `x` \n"#),
+            r#""say \"hi\" \`x\` \\n""#
+        );
+    }
+}
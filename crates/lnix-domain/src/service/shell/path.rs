@@ -14,7 +14,7 @@ fn is_absolute_path(path: &str) -> bool {
 /// - `~/foo`      → `$HOME/foo`
 /// - `/etc/foo`   → `/etc/foo` (absolute, unchanged)
 /// - `./foo`, `foo` → `$PWD/foo`
-pub(super) fn resolve_path(path: &str) -> String {
+pub(crate) fn resolve_path(path: &str) -> String {
     if let Some(stripped) = path.strip_prefix("~/") {
         format!("$HOME/{}", stripped)
     } else if is_absolute_path(path) {
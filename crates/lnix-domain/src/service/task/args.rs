@@ -0,0 +1,284 @@
+//! Command-line parsing and usage text for a task's declared `args:`.
+
+use std::collections::BTreeMap;
+
+use crate::error::TaskArgError;
+use crate::values::{TaskArgName, TaskName};
+use crate::{TaskArg, TaskDef};
+
+/// Command-line arguments sorted against a task's declaration.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TaskArgs {
+    /// Every declared argument: given, defaulted, or, when optional
+    /// and neither, empty.
+    pub values: BTreeMap<TaskArgName, String>,
+
+    /// Positional arguments beyond the declared ones, for
+    /// `{{.CLI_ARGS}}`.
+    pub rest: Vec<String>,
+}
+
+/// Sorts `cli` into the arguments `task_def` declares.
+///
+/// `--<name> <value>` and `--<name>=<value>` set an argument by name;
+/// the remaining positional words fill the other declared arguments in
+/// order, and any left over end up in [`TaskArgs::rest`]. Everything
+/// after a `--` is positional. A task without `args:` passes the whole
+/// command line through to `rest`, unparsed.
+pub fn parse_task_args(
+    task_name: &TaskName,
+    task_def: &TaskDef,
+    cli: &[String],
+) -> Result<TaskArgs, TaskArgError> {
+    if task_def.args.is_empty() {
+        return Ok(TaskArgs {
+            values: BTreeMap::new(),
+            rest: cli.to_vec(),
+        });
+    }
+
+    let mut values = BTreeMap::new();
+    let mut positional = Vec::new();
+    let mut words = cli.iter();
+    while let Some(word) = words.next() {
+        if word == "--" {
+            positional.extend(words.by_ref().cloned());
+            break;
+        }
+        let Some(option) = word.strip_prefix("--") else {
+            positional.push(word.clone());
+            continue;
+        };
+        let (name, inline_value) = match option.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (option, None),
+        };
+        let Some(arg) = task_def.args.iter().find(|arg| arg.name.as_str() == name) else {
+            return Err(TaskArgError::Unknown {
+                task: task_name.to_string(),
+                arg: name.to_string(),
+            });
+        };
+        let value = match inline_value {
+            Some(value) => value,
+            None => words
+                .next()
+                .cloned()
+                .ok_or_else(|| TaskArgError::MissingValue {
+                    task: task_name.to_string(),
+                    arg: name.to_string(),
+                })?,
+        };
+        values.insert(arg.name.clone(), value);
+    }
+
+    let mut positional = positional.into_iter();
+    for arg in &task_def.args {
+        if values.contains_key(&arg.name) {
+            continue;
+        }
+        let value = positional.next().or_else(|| arg.default.clone());
+        match value {
+            Some(value) => {
+                values.insert(arg.name.clone(), value);
+            }
+            None if arg.required => {
+                return Err(TaskArgError::MissingRequired {
+                    task: task_name.to_string(),
+                    arg: arg.name.to_string(),
+                });
+            }
+            None => {
+                values.insert(arg.name.clone(), String::new());
+            }
+        }
+    }
+
+    Ok(TaskArgs {
+        values,
+        rest: positional.collect(),
+    })
+}
+
+fn usage_placeholder(arg: &TaskArg) -> String {
+    if arg.required && arg.default.is_none() {
+        format!("<{}>", arg.name)
+    } else {
+        format!("[{}]", arg.name)
+    }
+}
+
+/// The text `lnix task <name> --help` prints: a usage line, the
+/// description, and one line per declared argument.
+pub fn format_task_usage(task_name: &TaskName, task_def: &TaskDef) -> String {
+    let mut usage = format!("Usage: lnix task {}", task_name);
+    for arg in &task_def.args {
+        usage.push(' ');
+        usage.push_str(&usage_placeholder(arg));
+    }
+    let takes_extra = task_def
+        .commands
+        .iter()
+        .any(|command| command.contains("{{.CLI_ARGS}}"));
+    if takes_extra {
+        usage.push_str(" [ARGS]...");
+    }
+
+    let mut sections = vec![usage];
+    if let Some(description) = &task_def.description {
+        sections.push(description.clone());
+    }
+    if !task_def.args.is_empty() {
+        let width = task_def
+            .args
+            .iter()
+            .map(|arg| arg.name.as_str().len())
+            .max()
+            .unwrap_or_default();
+        let mut lines = vec!["Arguments:".to_string()];
+        for arg in &task_def.args {
+            let mut line = format!("  --{:<width$}", arg.name.as_str(), width = width);
+            if let Some(help) = &arg.help {
+                line.push_str("  ");
+                line.push_str(help);
+            }
+            if let Some(default) = &arg.default {
+                line.push_str(&format!(" [default: {}]", default));
+            } else if arg.required {
+                line.push_str(" [required]");
+            }
+            lines.push(line);
+        }
+        lines.push(String::new());
+        lines.push("Arguments can be given in order or as --<name> <value>.".to_string());
+        sections.push(lines.join("\n"));
+    }
+    sections.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEPLOY: &str = r#"
+description: Deploy the app
+commands:
+  - ./deploy {{.ARGS.target}} --env {{.ARGS.env}} {{.CLI_ARGS}}
+args:
+  - name: target
+    required: true
+    help: Host to deploy to
+  - name: env
+    default: staging
+    help: Target environment
+"#;
+
+    fn deploy() -> (TaskName, TaskDef) {
+        (
+            "deploy".parse().unwrap(),
+            serde_yaml::from_str(DEPLOY).unwrap(),
+        )
+    }
+
+    fn args(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    fn value<'a>(parsed: &'a TaskArgs, name: &str) -> Option<&'a str> {
+        parsed
+            .values
+            .get(&name.parse::<TaskArgName>().unwrap())
+            .map(String::as_str)
+    }
+
+    #[test]
+    fn fills_arguments_positionally_and_applies_defaults() {
+        // Arrange
+        let (name, task_def) = deploy();
+
+        // Act
+        let parsed = parse_task_args(&name, &task_def, &args(&["web-1"])).unwrap();
+
+        // Assert
+        assert_eq!(value(&parsed, "target"), Some("web-1"));
+        assert_eq!(value(&parsed, "env"), Some("staging"));
+        assert!(parsed.rest.is_empty());
+    }
+
+    #[test]
+    fn named_arguments_leave_positions_to_the_others() {
+        // Arrange
+        let (name, task_def) = deploy();
+
+        // Act
+        let parsed = parse_task_args(
+            &name,
+            &task_def,
+            &args(&["--env=prod", "web-1", "--", "--verbose"]),
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(value(&parsed, "target"), Some("web-1"));
+        assert_eq!(value(&parsed, "env"), Some("prod"));
+        assert_eq!(parsed.rest, vec!["--verbose"]);
+    }
+
+    #[test]
+    fn reports_missing_unknown_and_valueless_arguments() {
+        // Arrange
+        let (name, task_def) = deploy();
+
+        // Act
+        let missing = parse_task_args(&name, &task_def, &[]);
+        let unknown = parse_task_args(&name, &task_def, &args(&["--host", "x"]));
+        let valueless = parse_task_args(&name, &task_def, &args(&["--target"]));
+
+        // Assert
+        assert_eq!(
+            missing,
+            Err(TaskArgError::MissingRequired {
+                task: "deploy".to_string(),
+                arg: "target".to_string(),
+            })
+        );
+        assert!(matches!(unknown, Err(TaskArgError::Unknown { arg, .. }) if arg == "host"));
+        assert!(
+            matches!(valueless, Err(TaskArgError::MissingValue { arg, .. }) if arg == "target")
+        );
+    }
+
+    #[test]
+    fn task_without_declared_args_passes_everything_through() {
+        // Arrange
+        let task_def: TaskDef =
+            serde_yaml::from_str("commands: [\"pytest {{.CLI_ARGS}}\"]\n").unwrap();
+
+        // Act
+        let parsed =
+            parse_task_args(&"test".parse().unwrap(), &task_def, &args(&["--lf", "-x"])).unwrap();
+
+        // Assert
+        assert_eq!(parsed.rest, vec!["--lf", "-x"]);
+    }
+
+    #[test]
+    fn formats_usage_from_the_declaration() {
+        // Arrange
+        let (name, task_def) = deploy();
+
+        // Act
+        let usage = format_task_usage(&name, &task_def);
+
+        // Assert
+        assert_eq!(
+            usage,
+            "Usage: lnix task deploy <target> [env] [ARGS]...\n\n\
+             Deploy the app\n\n\
+             Arguments:\n  \
+             --target  Host to deploy to [required]\n  \
+             --env     Target environment [default: staging]\n\n\
+             Arguments can be given in order or as --<name> <value>."
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! `{{.ARGS.<name>}}` and `{{.CLI_ARGS}}` interpolation into task
+//! commands.
+
+use crate::service::shell::quote;
+use crate::service::task::TaskArgs;
+
+/// Interpolates CLI arguments into command templates
+///
+/// Replaces `{{.CLI_ARGS}}` placeholder with the provided CLI arguments.
+/// Each argument is quoted as a single shell word, as
+/// [`interpolate_args`] does, and they are joined with spaces.
+///
+/// # Arguments
+///
+/// * `command_list` - List of command strings that may contain `{{.CLI_ARGS}}` placeholder
+/// * `cli_args` - List of CLI arguments to interpolate
+///
+/// # Returns
+///
+/// A new vector of commands with placeholders replaced by CLI arguments
+///
+/// # Examples
+///
+/// ```
+/// use lnix_domain::interpolate_command;
+///
+/// let commands = vec!["uv run python {{.CLI_ARGS}}".to_string()];
+/// let args = vec!["main.py".to_string(), "--name=my app".to_string()];
+/// let result = interpolate_command(&commands, &args);
+/// assert_eq!(result, vec!["uv run python main.py '--name=my app'"]);
+/// ```
+pub fn interpolate_command(command_list: &[String], cli_args: &[String]) -> Vec<String> {
+    interpolate_args(
+        command_list,
+        &TaskArgs {
+            rest: cli_args.to_vec(),
+            ..TaskArgs::default()
+        },
+    )
+}
+
+/// Replaces each `{{.ARGS.<name>}}` placeholder with the value of the
+/// named argument, and `{{.CLI_ARGS}}` with the leftover arguments,
+/// each quoted as a single shell word. Both are substituted in one
+/// pass over the template, so a value that itself reads like a
+/// placeholder is never expanded. Placeholders for names not in
+/// `args.values` are left untouched.
+pub fn interpolate_args(command_list: &[String], args: &TaskArgs) -> Vec<String> {
+    let cli_args: Vec<String> = args.rest.iter().map(|arg| quote(arg)).collect();
+    let cli_args = cli_args.join(" ");
+    let lookup = |placeholder: &str| match placeholder.strip_prefix("ARGS.") {
+        Some(name) => args
+            .values
+            .iter()
+            .find(|(arg, _)| arg.as_str() == name)
+            .map(|(_, value)| quote(value)),
+        None => (placeholder == "CLI_ARGS").then(|| cli_args.clone()),
+    };
+    command_list
+        .iter()
+        .map(|cmd| substitute(cmd, lookup))
+        .collect()
+}
+
+/// Replaces each `{{.<placeholder>}}` in `template` that `lookup`
+/// knows, left to right, without looking at the replacements again.
+fn substitute(template: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut result = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{.") {
+        result.push_str(&rest[..start]);
+        let inner = &rest[start + 3..];
+        let replaced = inner
+            .find("}}")
+            .and_then(|end| lookup(&inner[..end]).map(|value| (value, end + 2)));
+        match replaced {
+            Some((value, consumed)) => {
+                result.push_str(&value);
+                rest = &inner[consumed..];
+            }
+            None => {
+                result.push_str("{{.");
+                rest = inner;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn test_interpolate_with_placeholder() {
+        let commands = vec!["uv run python {{.CLI_ARGS}}".to_string()];
+        let args = vec!["main.py".to_string(), "--verbose".to_string()];
+        let result = interpolate_command(&commands, &args);
+        assert_eq!(result, vec!["uv run python main.py --verbose"]);
+    }
+
+    #[test]
+    fn test_interpolate_without_placeholder() {
+        let commands = vec!["cargo build".to_string()];
+        let args = vec!["--release".to_string()];
+        let result = interpolate_command(&commands, &args);
+        assert_eq!(result, vec!["cargo build"]);
+    }
+
+    #[test]
+    fn test_interpolate_empty_args() {
+        let commands = vec!["uv run python {{.CLI_ARGS}}".to_string()];
+        let args: Vec<String> = vec![];
+        let result = interpolate_command(&commands, &args);
+        assert_eq!(result, vec!["uv run python "]);
+    }
+
+    #[test]
+    fn test_interpolate_multiple_commands() {
+        let commands = vec![
+            "echo Starting {{.CLI_ARGS}}".to_string(),
+            "python {{.CLI_ARGS}}".to_string(),
+            "echo Finished".to_string(),
+        ];
+        let args = vec!["app.py".to_string(), "--debug".to_string()];
+        let result = interpolate_command(&commands, &args);
+        assert_eq!(
+            result,
+            vec![
+                "echo Starting app.py --debug",
+                "python app.py --debug",
+                "echo Finished"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_interpolate_quotes_each_arg() {
+        let commands = vec!["echo {{.CLI_ARGS}}".to_string()];
+        let args = vec![
+            "file with spaces.txt".to_string(),
+            "--option=value".to_string(),
+            "it's; rm -rf /".to_string(),
+        ];
+        let result = interpolate_command(&commands, &args);
+        assert_eq!(
+            result,
+            vec!["echo 'file with spaces.txt' '--option=value' 'it'\\''s; rm -rf /'"]
+        );
+    }
+
+    #[test]
+    fn test_interpolate_multiple_placeholders() {
+        let commands = vec!["echo {{.CLI_ARGS}} and {{.CLI_ARGS}} again".to_string()];
+        let args = vec!["test".to_string()];
+        let result = interpolate_command(&commands, &args);
+        assert_eq!(result, vec!["echo test and test again"]);
+    }
+
+    #[test]
+    fn test_interpolate_no_commands() {
+        let commands: Vec<String> = vec![];
+        let args = vec!["arg1".to_string()];
+        let result = interpolate_command(&commands, &args);
+        assert_eq!(result, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_interpolate_args_quotes_each_value() {
+        let commands = vec!["deploy {{.ARGS.target}} --env {{.ARGS.env}}".to_string()];
+        let args = TaskArgs {
+            values: BTreeMap::from([
+                ("target".parse().unwrap(), "web 1".to_string()),
+                ("env".parse().unwrap(), String::new()),
+            ]),
+            rest: vec![],
+        };
+        let result = interpolate_args(&commands, &args);
+        assert_eq!(result, vec!["deploy 'web 1' --env ''"]);
+    }
+
+    #[test]
+    fn test_interpolate_args_never_expands_a_value_twice() {
+        let commands =
+            vec!["echo {{.ARGS.a}} {{.ARGS.b}} {{.CLI_ARGS}} {{.ARGS.unknown}}".to_string()];
+        let args = TaskArgs {
+            values: BTreeMap::from([
+                ("a".parse().unwrap(), "{{.CLI_ARGS}}".to_string()),
+                ("b".parse().unwrap(), "{{.ARGS.a}}".to_string()),
+            ]),
+            rest: vec!["--rest".to_string()],
+        };
+        let result = interpolate_args(&commands, &args);
+        assert_eq!(
+            result,
+            vec!["echo '{{.CLI_ARGS}}' '{{.ARGS.a}}' --rest {{.ARGS.unknown}}"]
+        );
+    }
+}
@@ -0,0 +1,15 @@
+//! Task planning and command assembly: dependency ordering, argument
+//! parsing and interpolation, the per-task environment prelude, and
+//! concurrent execution.
+
+mod args;
+mod interpolate;
+mod order;
+mod parallel;
+mod prelude;
+
+pub use args::{TaskArgs, format_task_usage, parse_task_args};
+pub use interpolate::{interpolate_args, interpolate_command};
+pub use order::resolve_task_order;
+pub use parallel::parallel_script;
+pub use prelude::task_prelude;
@@ -0,0 +1,157 @@
+//! Orders a task after its `deps`, treating the task map as a DAG.
+
+use std::collections::HashMap;
+
+use crate::TaskDef;
+use crate::error::ValidationError;
+use crate::values::TaskName;
+
+/// Depth-first walk that appends each task after its dependencies.
+/// `path` holds the tasks currently being visited, so meeting one of
+/// them again means a cycle.
+fn visit(
+    tasks: &HashMap<TaskName, TaskDef>,
+    name: &TaskName,
+    path: &mut Vec<TaskName>,
+    order: &mut Vec<TaskName>,
+) -> Result<(), ValidationError> {
+    if order.contains(name) {
+        return Ok(());
+    }
+    if let Some(start) = path.iter().position(|seen| seen == name) {
+        let cycle: Vec<&str> = path[start..]
+            .iter()
+            .chain(std::iter::once(name))
+            .map(TaskName::as_str)
+            .collect();
+        return Err(ValidationError::TaskCycle(cycle.join(" -> ")));
+    }
+    let Some(task_def) = tasks.get(name) else {
+        return Ok(());
+    };
+
+    path.push(name.clone());
+    for dependency in &task_def.deps {
+        if !tasks.contains_key(dependency) {
+            return Err(ValidationError::UnknownTaskDependency {
+                task: name.to_string(),
+                dependency: dependency.to_string(),
+            });
+        }
+        visit(tasks, dependency, path, order)?;
+    }
+    path.pop();
+    order.push(name.clone());
+    Ok(())
+}
+
+/// Returns the tasks to run for `target`, dependencies first and
+/// `target` last, each at most once even when several tasks depend on
+/// it. Dependencies run in the order they are listed.
+///
+/// An unknown `target` yields an empty order; callers report a missing
+/// task themselves. An unknown dependency or a dependency cycle is a
+/// [`ValidationError`].
+pub fn resolve_task_order(
+    tasks: &HashMap<TaskName, TaskDef>,
+    target: &TaskName,
+) -> Result<Vec<TaskName>, ValidationError> {
+    let mut order = Vec::new();
+    visit(tasks, target, &mut Vec::new(), &mut order)?;
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tasks_from_yaml(yaml: &str) -> HashMap<TaskName, TaskDef> {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn names(order: &[TaskName]) -> Vec<&str> {
+        order.iter().map(TaskName::as_str).collect()
+    }
+
+    #[test]
+    fn runs_shared_dependencies_once_before_their_dependents() {
+        // Arrange
+        let tasks = tasks_from_yaml(
+            r#"
+codegen:
+  commands: [make gen]
+build:
+  commands: [make]
+  deps: [codegen]
+lint:
+  commands: [make lint]
+  deps: [codegen]
+ci:
+  commands: [echo done]
+  deps: [build, lint]
+"#,
+        );
+
+        // Act
+        let order = resolve_task_order(&tasks, &"ci".parse().unwrap()).unwrap();
+
+        // Assert
+        assert_eq!(names(&order), vec!["codegen", "build", "lint", "ci"]);
+    }
+
+    #[test]
+    fn task_without_deps_runs_alone() {
+        // Arrange
+        let tasks = tasks_from_yaml("build:\n  commands: [make]\n");
+
+        // Act
+        let order = resolve_task_order(&tasks, &"build".parse().unwrap()).unwrap();
+
+        // Assert
+        assert_eq!(names(&order), vec!["build"]);
+    }
+
+    #[test]
+    fn reports_the_cycle() {
+        // Arrange
+        let tasks = tasks_from_yaml(
+            r#"
+build:
+  commands: [make]
+  deps: [codegen]
+codegen:
+  commands: [make gen]
+  deps: [build]
+"#,
+        );
+
+        // Act
+        let result = resolve_task_order(&tasks, &"build".parse().unwrap());
+
+        // Assert
+        assert_eq!(
+            result,
+            Err(ValidationError::TaskCycle(
+                "build -> codegen -> build".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn reports_unknown_dependency() {
+        // Arrange
+        let tasks = tasks_from_yaml("build:\n  commands: [make]\n  deps: [codegen]\n");
+
+        // Act
+        let result = resolve_task_order(&tasks, &"build".parse().unwrap());
+
+        // Assert
+        assert_eq!(
+            result,
+            Err(ValidationError::UnknownTaskDependency {
+                task: "build".to_string(),
+                dependency: "codegen".to_string(),
+            })
+        );
+    }
+}
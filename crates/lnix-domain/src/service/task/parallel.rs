@@ -0,0 +1,189 @@
+//! Bash script that runs a `parallel` task's commands concurrently.
+//!
+//! Each command is a background job in its own process group, so it can
+//! be stopped together with everything it spawned. Its stdout and
+//! stderr are merged and every line is prefixed with the command's
+//! label. Exit codes go through a scratch directory, since `wait` only
+//! sees the status of the prefixing loop.
+
+use crate::FailurePolicy;
+use crate::service::shell::quote;
+
+/// Label for a command: its first word after any leading `NAME=value`
+/// assignments.
+fn base_label(command: &str) -> &str {
+    command
+        .split_whitespace()
+        .find(|word| !word.contains('='))
+        .unwrap_or("command")
+}
+
+/// Labels for `commands`, in order. Commands that share a first word
+/// are numbered (`cargo#1`, `cargo#2`).
+fn command_labels(commands: &[String]) -> Vec<String> {
+    let bases: Vec<&str> = commands.iter().map(|c| base_label(c)).collect();
+    bases
+        .iter()
+        .enumerate()
+        .map(|(i, base)| {
+            if bases.iter().filter(|other| *other == base).count() == 1 {
+                return base.to_string();
+            }
+            let occurrence = bases[..=i].iter().filter(|other| *other == base).count();
+            format!("{}#{}", base, occurrence)
+        })
+        .collect()
+}
+
+/// Renders `commands` as one bash script that starts them all at once
+/// and prefixes their output with `[label]`. With
+/// [`FailurePolicy::FailFast`] the first failure stops the others; with
+/// [`FailurePolicy::WaitAll`] every command runs to completion. Each
+/// failed or stopped command is reported on stderr, and the script
+/// exits with the code of the first failed command in declaration
+/// order (0 when all succeed).
+pub fn parallel_script(commands: &[String], on_failure: FailurePolicy) -> String {
+    let fail_fast = on_failure == FailurePolicy::FailFast;
+    let labels = command_labels(commands);
+    let width = labels
+        .iter()
+        .map(|label| label.len() + 2)
+        .max()
+        .unwrap_or(0);
+
+    let mut script = String::from(
+        r#"{
+__lazynix_status=$(mktemp -d)
+__lazynix_pids=
+__lazynix_failed=
+__lazynix_stop() {
+  for __lazynix_pid in $__lazynix_pids; do kill -TERM -- "-$__lazynix_pid" 2>/dev/null; done
+}
+set -m
+trap '__lazynix_stop; rm -rf "$__lazynix_status"; exit 130' INT TERM
+"#,
+    );
+    if fail_fast {
+        script.push_str("trap '__lazynix_failed=1' USR1\n");
+    }
+    let notify = if fail_fast {
+        r#"[ "$__lazynix_code" -eq 0 ] || kill -USR1 $$; "#
+    } else {
+        ""
+    };
+    for (i, (command, label)) in commands.iter().zip(&labels).enumerate() {
+        let prefix = quote(&format!("{:width$}", format!("[{}]", label)));
+        script.push_str(&format!(
+            "( {{ ( {command}\n) 2>&1; __lazynix_code=$?; echo \"$__lazynix_code\" > \"$__lazynix_status/{job}\"; {notify}}} | while IFS= read -r __lazynix_line || [ -n \"$__lazynix_line\" ]; do printf '%s %s\\n' {prefix} \"$__lazynix_line\"; done ) &\n__lazynix_pids=\"$__lazynix_pids $!\"\n",
+            job = i + 1,
+        ));
+    }
+    if fail_fast {
+        script.push_str(
+            "until [ -z \"$__lazynix_failed\" ] && wait; do\n  __lazynix_stop\n  __lazynix_failed=\ndone\ntrap - USR1\n",
+        );
+    } else {
+        script.push_str("wait\n");
+    }
+    script.push_str(
+        r#"set +m
+trap - INT TERM
+__lazynix_exit=0
+__lazynix_report() {
+  if [ ! -f "$__lazynix_status/$2" ]; then
+    echo "lazynix: [$1] stopped" >&2
+  elif [ "$(cat "$__lazynix_status/$2")" != 0 ]; then
+    echo "lazynix: [$1] failed with exit code $(cat "$__lazynix_status/$2")" >&2
+    [ "$__lazynix_exit" != 0 ] || __lazynix_exit=$(cat "$__lazynix_status/$2")
+  fi
+}
+"#,
+    );
+    for (i, label) in labels.iter().enumerate() {
+        script.push_str(&format!("__lazynix_report {} {}\n", quote(label), i + 1));
+    }
+    script.push_str("rm -rf \"$__lazynix_status\"\n(exit \"$__lazynix_exit\")\n}");
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(commands: &[&str], on_failure: FailurePolicy) -> (i32, String, String) {
+        let commands: Vec<String> = commands.iter().map(|c| c.to_string()).collect();
+        let output = std::process::Command::new("bash")
+            .arg("-c")
+            .arg(parallel_script(&commands, on_failure))
+            .output()
+            .unwrap();
+        (
+            output.status.code().unwrap(),
+            String::from_utf8(output.stdout).unwrap(),
+            String::from_utf8(output.stderr).unwrap(),
+        )
+    }
+
+    #[test]
+    fn labels_commands_by_first_word_and_numbers_duplicates() {
+        // Arrange
+        let commands: Vec<String> = ["cargo clippy", "RUFF_CACHE=x ruff check", "cargo fmt"]
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+
+        // Act
+        let labels = command_labels(&commands);
+
+        // Assert
+        assert_eq!(labels, vec!["cargo#1", "ruff", "cargo#2"]);
+    }
+
+    #[test]
+    fn prefixes_every_output_line_and_succeeds() {
+        // Act
+        let (code, stdout, _) = run(
+            &["echo one; echo two >&2", "printf 'no newline'"],
+            FailurePolicy::FailFast,
+        );
+
+        // Assert
+        assert_eq!(code, 0);
+        let mut lines: Vec<&str> = stdout.lines().collect();
+        lines.sort();
+        assert_eq!(
+            lines,
+            vec!["[echo]   one", "[echo]   two", "[printf] no newline"]
+        );
+    }
+
+    #[test]
+    fn fail_fast_stops_the_other_commands() {
+        // Act
+        let (code, stdout, stderr) = run(
+            &["sleep 0.2; exit 3", "sleep 5; echo finished"],
+            FailurePolicy::FailFast,
+        );
+
+        // Assert
+        assert_eq!(code, 3);
+        assert!(!stdout.contains("finished"), "stdout: {stdout}");
+        assert!(stderr.contains("lazynix: [sleep#1] failed with exit code 3"));
+        assert!(stderr.contains("lazynix: [sleep#2] stopped"));
+    }
+
+    #[test]
+    fn wait_all_reports_the_first_failure_in_declaration_order() {
+        // Act
+        let (code, stdout, stderr) = run(
+            &["sleep 0.3; exit 4", "exit 2", "echo ok"],
+            FailurePolicy::WaitAll,
+        );
+
+        // Assert
+        assert_eq!(code, 4);
+        assert!(stdout.contains("[echo]  ok"));
+        assert!(stderr.contains("lazynix: [sleep] failed with exit code 4"));
+        assert!(stderr.contains("lazynix: [exit] failed with exit code 2"));
+    }
+}
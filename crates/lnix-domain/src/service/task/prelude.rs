@@ -0,0 +1,95 @@
+//! Shell commands that set up a task's own environment before its
+//! commands run.
+
+use crate::TaskDef;
+use crate::service::shell::render_prelude;
+
+/// Commands to run ahead of `task_def`'s own commands, in the same
+/// shell, each of which can be chained with `&&`: load the dotenv files
+/// (`dotenv`, then `env.dotenv`, relative to `lazynix.yaml`), then `cd`
+/// into `dir`, then set the `env.envvar` variables. Empty for a task
+/// without any of these.
+pub fn task_prelude(task_def: &TaskDef) -> Vec<String> {
+    let env_dotenv = task_def.env.iter().flat_map(|env| &env.dotenv);
+    render_prelude(
+        task_def.dir.as_deref(),
+        task_def.dotenv.iter().chain(env_dotenv),
+        task_def.env.iter().flat_map(|env| &env.envvar),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::shell::run_sh;
+
+    #[test]
+    fn loads_dotenv_then_changes_directory_then_sets_env() {
+        // Arrange
+        let task_def: TaskDef = serde_yaml::from_str(
+            r#"
+commands: [npm test]
+dir: frontend
+dotenv: [frontend/.env]
+env:
+  dotenv: [frontend/.env.test]
+  envvar:
+    - name: NODE_ENV
+      value: test
+"#,
+        )
+        .unwrap();
+
+        // Act
+        let prelude = task_prelude(&task_def);
+
+        // Assert
+        assert_eq!(
+            prelude,
+            vec![
+                "set -a",
+                ". \"$PWD/frontend/.env\"",
+                "set +a",
+                "set -a",
+                ". \"$PWD/frontend/.env.test\"",
+                "set +a",
+                "cd frontend",
+                "export NODE_ENV=test",
+            ]
+        );
+    }
+
+    #[test]
+    fn commands_do_not_run_when_the_prelude_fails() {
+        // Arrange
+        let task_def: TaskDef = serde_yaml::from_str(
+            r#"
+commands: [echo ran]
+dir: lazynix-missing-dir
+env:
+  envvar:
+    - name: VERSION
+      command: echo 1.0
+"#,
+        )
+        .unwrap();
+        let mut script = task_prelude(&task_def);
+        script.extend(task_def.commands.iter().cloned());
+
+        // Act
+        let (code, stdout) = run_sh(&script.join(" && "));
+
+        // Assert
+        assert_ne!(code, 0);
+        assert_eq!(stdout, "");
+    }
+
+    #[test]
+    fn plain_task_has_no_prelude() {
+        // Arrange
+        let task_def: TaskDef = serde_yaml::from_str("commands: [make]\n").unwrap();
+
+        // Act & Assert
+        assert!(task_prelude(&task_def).is_empty());
+    }
+}
@@ -0,0 +1,15 @@
+//! Test planning and reporting: choosing tests by name, assembling the
+//! script each one runs, and presenting their outcomes on the console
+//! and as reports.
+
+mod outcome;
+mod report;
+mod script;
+mod select;
+mod summary;
+
+pub use outcome::{TestOutcome, TestStatus};
+pub use report::{ReportFormat, TestReport, render_report};
+pub use script::test_script;
+pub use select::{matches_glob, select_tests};
+pub use summary::{format_test_result, format_test_summary};
@@ -0,0 +1,132 @@
+//! What happened to each test in a `lnix test` run.
+
+use std::time::Duration;
+
+use crate::TestDef;
+
+/// Exit code `timeout` uses for a command it had to stop.
+const TIMEOUT_EXIT_CODE: i32 = 124;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    /// Stopped after exceeding the test's `timeout`.
+    TimedOut,
+    /// Failed, but the test sets `allowFailure`.
+    AllowedFailure,
+    /// Not run because an earlier test failed in fail-fast mode.
+    Skipped,
+}
+
+impl TestStatus {
+    /// Whether this outcome fails the run.
+    pub fn is_failure(self) -> bool {
+        matches!(self, TestStatus::Failed | TestStatus::TimedOut)
+    }
+}
+
+/// One test's result: its status, exit code (`None` when skipped), how
+/// long it ran, and its combined stdout and stderr.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestOutcome {
+    pub name: String,
+    pub status: TestStatus,
+    pub exit_code: Option<i32>,
+    pub duration: Duration,
+    pub output: String,
+    /// The test's timeout, kept for the failure message.
+    pub timeout: Option<Duration>,
+}
+
+impl TestOutcome {
+    /// The outcome of running `test` to completion with `exit_code`.
+    pub fn finished(test: &TestDef, exit_code: i32, duration: Duration, output: String) -> Self {
+        let status = if exit_code == 0 {
+            TestStatus::Passed
+        } else if test.allow_failure {
+            TestStatus::AllowedFailure
+        } else if test.timeout.is_some() && exit_code == TIMEOUT_EXIT_CODE {
+            TestStatus::TimedOut
+        } else {
+            TestStatus::Failed
+        };
+        Self {
+            name: test.name.clone(),
+            status,
+            exit_code: Some(exit_code),
+            duration,
+            output,
+            timeout: test.timeout.as_ref().map(|timeout| timeout.as_duration()),
+        }
+    }
+
+    /// The outcome of a test that never ran.
+    pub fn skipped(test: &TestDef) -> Self {
+        Self {
+            name: test.name.clone(),
+            status: TestStatus::Skipped,
+            exit_code: None,
+            duration: Duration::ZERO,
+            output: String::new(),
+            timeout: test.timeout.as_ref().map(|timeout| timeout.as_duration()),
+        }
+    }
+
+    /// One-line reason for a non-passing outcome, e.g. `exit code 2`.
+    pub fn reason(&self) -> String {
+        let exit_code = self.exit_code.unwrap_or_default();
+        match self.status {
+            TestStatus::Passed => String::new(),
+            TestStatus::Failed => format!("exit code {}", exit_code),
+            TestStatus::TimedOut => format!(
+                "timed out after {}s",
+                self.timeout.unwrap_or_default().as_secs()
+            ),
+            TestStatus::AllowedFailure => format!("allowed to fail (exit code {})", exit_code),
+            TestStatus::Skipped => "skipped after an earlier failure".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_def(yaml: &str) -> TestDef {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn classifies_exit_codes() {
+        // Arrange
+        let plain = test_def("cargo test\n");
+        let allowed = test_def("name: flaky\ncommand: cargo test\nallowFailure: true\n");
+        let timed = test_def("name: slow\ncommand: cargo test\ntimeout: 2m\n");
+
+        // Act
+        let status = |test: &TestDef, code| {
+            TestOutcome::finished(test, code, Duration::ZERO, String::new()).status
+        };
+
+        // Assert
+        assert_eq!(status(&plain, 0), TestStatus::Passed);
+        assert_eq!(status(&plain, 124), TestStatus::Failed);
+        assert_eq!(status(&allowed, 1), TestStatus::AllowedFailure);
+        assert_eq!(status(&timed, 124), TestStatus::TimedOut);
+        assert_eq!(status(&timed, 1), TestStatus::Failed);
+    }
+
+    #[test]
+    fn explains_non_passing_outcomes() {
+        // Arrange
+        let timed = test_def("name: slow\ncommand: cargo test\ntimeout: 2m\n");
+
+        // Act
+        let outcome = TestOutcome::finished(&timed, 124, Duration::ZERO, String::new());
+
+        // Assert
+        assert_eq!(outcome.reason(), "timed out after 120s");
+        assert_eq!(TestOutcome::skipped(&timed).exit_code, None);
+    }
+}
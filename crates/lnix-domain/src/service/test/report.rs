@@ -0,0 +1,310 @@
+//! Machine-readable test reports for `lnix test --report`.
+//!
+//! Every format carries each test's status, exit code, duration, and
+//! captured output.
+
+use std::fmt::Write;
+use std::str::FromStr;
+
+use serde_json::json;
+
+use crate::error::ParseError;
+use crate::service::test::{TestOutcome, TestStatus};
+use crate::values::ShellName;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// JUnit XML, as read by GitLab and Jenkins.
+    Junit,
+    /// Test Anything Protocol, version 13.
+    Tap,
+    Json,
+}
+
+/// A report to write: `<format>=<path>` on the command line, e.g.
+/// `junit=reports/tests.xml`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestReport {
+    pub format: ReportFormat,
+    pub path: String,
+}
+
+impl FromStr for TestReport {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseError::InvalidTestReport(s.to_string());
+        let (format, path) = s.split_once('=').ok_or_else(invalid)?;
+        let format = match format {
+            "junit" => ReportFormat::Junit,
+            "tap" => ReportFormat::Tap,
+            "json" => ReportFormat::Json,
+            _ => return Err(invalid()),
+        };
+        if path.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            format,
+            path: path.to_string(),
+        })
+    }
+}
+
+/// Renders `outcomes` (in run order) as a `format` report for `shell`.
+pub fn render_report(format: ReportFormat, shell: &ShellName, outcomes: &[TestOutcome]) -> String {
+    match format {
+        ReportFormat::Junit => render_junit(shell, outcomes),
+        ReportFormat::Tap => render_tap(outcomes),
+        ReportFormat::Json => render_json(shell, outcomes),
+    }
+}
+
+fn count(outcomes: &[TestOutcome], status: TestStatus) -> usize {
+    outcomes.iter().filter(|o| o.status == status).count()
+}
+
+/// Characters XML 1.0 cannot carry at all (most C0 controls, such as
+/// the ESC of ANSI colour codes) are dropped.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() && c < ' ' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn render_junit(shell: &ShellName, outcomes: &[TestOutcome]) -> String {
+    let failures = count(outcomes, TestStatus::Failed) + count(outcomes, TestStatus::TimedOut);
+    let skipped =
+        count(outcomes, TestStatus::Skipped) + count(outcomes, TestStatus::AllowedFailure);
+    let time: f64 = outcomes.iter().map(|o| o.duration.as_secs_f64()).sum();
+    let shell = escape_xml(shell.as_str());
+    let totals = format!(
+        "tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\" time=\"{:.3}\"",
+        outcomes.len(),
+        failures,
+        skipped,
+        time
+    );
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(xml, "<testsuites name=\"lazynix\" {}>", totals);
+    let _ = writeln!(xml, "  <testsuite name=\"{}\" {}>", shell, totals);
+    for outcome in outcomes {
+        let _ = writeln!(
+            xml,
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\">",
+            escape_xml(&outcome.name),
+            shell,
+            outcome.duration.as_secs_f64()
+        );
+        if let Some(exit_code) = outcome.exit_code {
+            let _ = writeln!(
+                xml,
+                "      <properties>\n        <property name=\"exit_code\" value=\"{}\"/>\n      </properties>",
+                exit_code
+            );
+        }
+        let reason = escape_xml(&outcome.reason());
+        match outcome.status {
+            TestStatus::Passed => {}
+            TestStatus::Failed | TestStatus::TimedOut => {
+                let _ = writeln!(xml, "      <failure message=\"{}\"/>", reason);
+            }
+            TestStatus::AllowedFailure | TestStatus::Skipped => {
+                let _ = writeln!(xml, "      <skipped message=\"{}\"/>", reason);
+            }
+        }
+        if !outcome.output.is_empty() {
+            let _ = writeln!(
+                xml,
+                "      <system-out>{}</system-out>",
+                escape_xml(&outcome.output)
+            );
+        }
+        xml.push_str("    </testcase>\n");
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+/// Test points with a YAML diagnostic block, as in TAP 13.
+fn render_tap(outcomes: &[TestOutcome]) -> String {
+    let mut tap = format!("TAP version 13\n1..{}\n", outcomes.len());
+    for (i, outcome) in outcomes.iter().enumerate() {
+        // TAP descriptions end at a `#`, which starts a directive.
+        let name = outcome.name.replace('\n', " ").replace('#', "\\#");
+        let (ok, directive) = match outcome.status {
+            TestStatus::Passed => ("ok", String::new()),
+            TestStatus::Failed | TestStatus::TimedOut => ("not ok", String::new()),
+            TestStatus::AllowedFailure => ("not ok", format!(" # TODO {}", outcome.reason())),
+            TestStatus::Skipped => ("ok", format!(" # SKIP {}", outcome.reason())),
+        };
+        let _ = writeln!(tap, "{} {} - {}{}", ok, i + 1, name, directive);
+        let Some(exit_code) = outcome.exit_code else {
+            continue;
+        };
+        let _ = writeln!(
+            tap,
+            "  ---\n  duration_ms: {}\n  exit_code: {}",
+            outcome.duration.as_millis(),
+            exit_code
+        );
+        if outcome.status.is_failure() {
+            // A JSON string is also a YAML one, escapes included.
+            let _ = writeln!(tap, "  message: {}", json!(outcome.reason()));
+        }
+        if !outcome.output.is_empty() {
+            tap.push_str("  output: |\n");
+            for line in outcome.output.lines() {
+                let _ = writeln!(tap, "    {}", line);
+            }
+        }
+        tap.push_str("  ...\n");
+    }
+    tap
+}
+
+fn status_name(status: TestStatus) -> &'static str {
+    match status {
+        TestStatus::Passed => "passed",
+        TestStatus::Failed => "failed",
+        TestStatus::TimedOut => "timed-out",
+        TestStatus::AllowedFailure => "allowed-failure",
+        TestStatus::Skipped => "skipped",
+    }
+}
+
+fn render_json(shell: &ShellName, outcomes: &[TestOutcome]) -> String {
+    let tests: Vec<_> = outcomes
+        .iter()
+        .map(|outcome| {
+            json!({
+                "name": outcome.name,
+                "status": status_name(outcome.status),
+                "exitCode": outcome.exit_code,
+                "durationMs": outcome.duration.as_millis() as u64,
+                "output": outcome.output,
+            })
+        })
+        .collect();
+    let report = json!({
+        "shell": shell.as_str(),
+        "summary": {
+            "passed": count(outcomes, TestStatus::Passed),
+            "failed": count(outcomes, TestStatus::Failed) + count(outcomes, TestStatus::TimedOut),
+            "allowedToFail": count(outcomes, TestStatus::AllowedFailure),
+            "skipped": count(outcomes, TestStatus::Skipped),
+        },
+        "tests": tests,
+    });
+    let mut json = serde_json::to_string_pretty(&report).expect("a JSON value always serializes");
+    json.push('\n');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::TestDef;
+
+    fn outcomes() -> Vec<TestOutcome> {
+        let unit: TestDef = serde_yaml::from_str("name: unit\ncommand: cargo test\n").unwrap();
+        let lint: TestDef = serde_yaml::from_str("name: lint <all>\ncommand: clippy\n").unwrap();
+        let e2e: TestDef = serde_yaml::from_str("name: e2e\ncommand: make e2e\n").unwrap();
+        vec![
+            TestOutcome::finished(&unit, 0, Duration::from_millis(1500), "ok\n".to_string()),
+            TestOutcome::finished(
+                &lint,
+                2,
+                Duration::from_millis(250),
+                "warning: \"x\" & \x1b[31my\x1b[0m\n".to_string(),
+            ),
+            TestOutcome::skipped(&e2e),
+        ]
+    }
+
+    fn shell() -> ShellName {
+        "default".parse().unwrap()
+    }
+
+    #[test]
+    fn parses_report_specs() {
+        // Act
+        let report: TestReport = "junit=reports/tests.xml".parse().unwrap();
+
+        // Assert
+        assert_eq!(report.format, ReportFormat::Junit);
+        assert_eq!(report.path, "reports/tests.xml");
+        assert!("xml=out.xml".parse::<TestReport>().is_err());
+        assert!("tap=".parse::<TestReport>().is_err());
+        assert!("results.json".parse::<TestReport>().is_err());
+    }
+
+    #[test]
+    fn renders_junit_with_escaped_output() {
+        // Act
+        let xml = render_report(ReportFormat::Junit, &shell(), &outcomes());
+
+        // Assert
+        assert!(xml.contains(
+            "<testsuite name=\"default\" tests=\"3\" failures=\"1\" errors=\"0\" skipped=\"1\" time=\"1.750\">"
+        ));
+        assert!(
+            xml.contains(
+                "<testcase name=\"lint &lt;all&gt;\" classname=\"default\" time=\"0.250\">"
+            )
+        );
+        assert!(xml.contains("<property name=\"exit_code\" value=\"2\"/>"));
+        assert!(xml.contains("<failure message=\"exit code 2\"/>"));
+        assert!(xml.contains("<system-out>warning: &quot;x&quot; &amp; [31my[0m\n</system-out>"));
+        assert!(xml.contains("<skipped message=\"skipped after an earlier failure\"/>"));
+    }
+
+    #[test]
+    fn renders_tap_with_diagnostics() {
+        // Act
+        let tap = render_report(ReportFormat::Tap, &shell(), &outcomes());
+
+        // Assert
+        assert!(tap.starts_with("TAP version 13\n1..3\nok 1 - unit\n  ---\n  duration_ms: 1500\n  exit_code: 0\n  output: |\n    ok\n  ...\n"));
+        assert!(tap.contains("not ok 2 - lint <all>\n  ---\n  duration_ms: 250\n  exit_code: 2\n  message: \"exit code 2\"\n"));
+        assert!(tap.ends_with("ok 3 - e2e # SKIP skipped after an earlier failure\n"));
+    }
+
+    #[test]
+    fn renders_json() {
+        // Act
+        let json = render_report(ReportFormat::Json, &shell(), &outcomes());
+
+        // Assert
+        let report: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(json.ends_with("}\n"));
+        assert_eq!(report["shell"], "default");
+        assert_eq!(
+            report["summary"],
+            json!({ "passed": 1, "failed": 1, "allowedToFail": 0, "skipped": 1 })
+        );
+        assert_eq!(
+            report["tests"][0],
+            json!({ "name": "unit", "status": "passed", "exitCode": 0, "durationMs": 1500, "output": "ok\n" })
+        );
+        assert_eq!(
+            report["tests"][1]["output"],
+            "warning: \"x\" & \u{1b}[31my\u{1b}[0m\n"
+        );
+        assert!(json.contains("\"exitCode\": null"));
+    }
+}
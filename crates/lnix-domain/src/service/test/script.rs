@@ -0,0 +1,67 @@
+//! The shell script a single test runs.
+
+use crate::TestDef;
+use crate::service::shell::script_in;
+
+/// `test`'s command preceded by its own environment: `env.dotenv`,
+/// then `cd` into `dir`, then `env.envvar` (see the task prelude,
+/// which works the same way). The command does not run when any of
+/// that fails.
+pub fn test_script(test: &TestDef) -> String {
+    script_in(test.dir.as_deref(), test.env.as_ref(), &test.command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::shell::run_sh;
+
+    #[test]
+    fn plain_test_is_just_its_command() {
+        // Arrange
+        let test: TestDef = serde_yaml::from_str("cargo test\n").unwrap();
+
+        // Act & Assert
+        assert_eq!(test_script(&test), "cargo test");
+    }
+
+    #[test]
+    fn runs_the_command_in_its_own_dir_and_env() {
+        // Arrange
+        let test: TestDef = serde_yaml::from_str(
+            r#"
+command: npm test
+dir: web
+env:
+  dotenv: [web/.env.test]
+  envvar:
+    - name: CI
+      value: "true"
+"#,
+        )
+        .unwrap();
+
+        // Act
+        let script = test_script(&test);
+
+        // Assert
+        assert_eq!(
+            script,
+            "set -a && . \"$PWD/web/.env.test\" && set +a && cd web && export CI=true || exit\nnpm test"
+        );
+    }
+
+    #[test]
+    fn does_not_run_the_command_when_its_dir_is_missing() {
+        // Arrange
+        let test: TestDef =
+            serde_yaml::from_str("command: echo ran\ndir: lazynix-missing-dir\n").unwrap();
+
+        // Act
+        let (code, stdout) = run_sh(&test_script(&test));
+
+        // Assert
+        assert_ne!(code, 0);
+        assert_eq!(stdout, "");
+    }
+}
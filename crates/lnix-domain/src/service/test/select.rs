@@ -0,0 +1,95 @@
+//! Selects tests by name for `lnix test <name-or-glob>...`.
+
+use crate::TestDef;
+
+/// Whether `name` matches `pattern`, where `*` matches any run of
+/// characters (including none) and `?` exactly one. Everything else
+/// matches itself.
+pub fn matches_glob(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` and the name position it was tried at,
+    // to backtrack to when the rest fails to match.
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    star = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Positions (in declaration order) of the tests whose name matches at
+/// least one of `patterns`; every test when `patterns` is empty.
+pub fn select_tests(tests: &[TestDef], patterns: &[String]) -> Vec<usize> {
+    tests
+        .iter()
+        .enumerate()
+        .filter(|(_, test)| {
+            patterns.is_empty()
+                || patterns
+                    .iter()
+                    .any(|pattern| matches_glob(pattern, &test.name))
+        })
+        .map(|(position, _)| position)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_wildcards() {
+        // Arrange
+        let cases = [
+            ("unit", "unit", true),
+            ("unit", "unit-fast", false),
+            ("unit*", "unit-fast", true),
+            ("*-fast", "unit-fast", true),
+            ("u?it", "unit", true),
+            ("*e2e*", "web e2e suite", true),
+            ("a*b*c", "abxbc", true),
+            ("a*b*c", "abxbd", false),
+            ("*", "", true),
+        ];
+
+        // Act & Assert
+        for (pattern, name, expected) in cases {
+            assert_eq!(matches_glob(pattern, name), expected, "{pattern} vs {name}");
+        }
+    }
+
+    #[test]
+    fn selects_tests_matching_any_pattern_in_declaration_order() {
+        // Arrange
+        let tests: Vec<TestDef> = serde_yaml::from_str(
+            "- name: lint\n  command: ruff check .\n- name: unit\n  command: pytest\n- name: e2e\n  command: playwright test\n",
+        )
+        .unwrap();
+        let patterns = vec!["e2e".to_string(), "l*".to_string()];
+
+        // Act
+        let selected = select_tests(&tests, &patterns);
+
+        // Assert
+        assert_eq!(selected, vec![0, 2]);
+        assert_eq!(select_tests(&tests, &[]), vec![0, 1, 2]);
+    }
+}
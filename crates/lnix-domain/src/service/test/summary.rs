@@ -0,0 +1,96 @@
+//! Console lines `lnix test` prints for each result and for the run.
+
+use crate::service::test::{TestOutcome, TestStatus};
+
+/// `[PASS] name`, or `[FAIL] name` with the reason when it is not a
+/// plain failure.
+pub fn format_test_result(outcome: &TestOutcome) -> String {
+    match outcome.status {
+        TestStatus::Passed => format!("[PASS] {}", outcome.name),
+        TestStatus::Failed => format!("[FAIL] {}", outcome.name),
+        TestStatus::TimedOut => format!("[FAIL] {} ({})", outcome.name, outcome.reason()),
+        TestStatus::AllowedFailure => format!("[FAIL] {} (allowed to fail)", outcome.name),
+        TestStatus::Skipped => format!("[SKIP] {}", outcome.name),
+    }
+}
+
+/// `Test Results: 3 passed, 1 failed, 0 allowed to fail`, plus the
+/// number skipped by fail-fast when there are any.
+pub fn format_test_summary(outcomes: &[TestOutcome]) -> String {
+    let count = |matches: fn(TestStatus) -> bool| {
+        outcomes
+            .iter()
+            .filter(|outcome| matches(outcome.status))
+            .count()
+    };
+    let mut summary = format!(
+        "Test Results: {} passed, {} failed, {} allowed to fail",
+        count(|s| s == TestStatus::Passed),
+        count(TestStatus::is_failure),
+        count(|s| s == TestStatus::AllowedFailure)
+    );
+    let skipped = count(|s| s == TestStatus::Skipped);
+    if skipped > 0 {
+        summary.push_str(&format!(", {} skipped", skipped));
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::TestDef;
+
+    fn finished(yaml: &str, exit_code: i32) -> TestOutcome {
+        let test: TestDef = serde_yaml::from_str(yaml).unwrap();
+        TestOutcome::finished(&test, exit_code, Duration::ZERO, String::new())
+    }
+
+    #[test]
+    fn formats_each_status() {
+        // Arrange
+        let slow = finished("name: slow\ncommand: sleep 9\ntimeout: 5s\n", 124);
+        let flaky = finished("name: flaky\ncommand: 'false'\nallowFailure: true\n", 1);
+        let skipped = TestOutcome::skipped(&serde_yaml::from_str("pytest\n").unwrap());
+
+        // Act & Assert
+        assert_eq!(
+            format_test_result(&finished("pytest\n", 0)),
+            "[PASS] pytest"
+        );
+        assert_eq!(
+            format_test_result(&finished("pytest\n", 2)),
+            "[FAIL] pytest"
+        );
+        assert_eq!(
+            format_test_result(&slow),
+            "[FAIL] slow (timed out after 5s)"
+        );
+        assert_eq!(format_test_result(&flaky), "[FAIL] flaky (allowed to fail)");
+        assert_eq!(format_test_result(&skipped), "[SKIP] pytest");
+    }
+
+    #[test]
+    fn summarizes_counts() {
+        // Arrange
+        let outcomes = vec![
+            finished("pytest\n", 0),
+            finished("name: slow\ncommand: sleep 9\ntimeout: 5s\n", 124),
+            finished("name: flaky\ncommand: 'false'\nallowFailure: true\n", 1),
+        ];
+
+        // Act & Assert
+        assert_eq!(
+            format_test_summary(&outcomes),
+            "Test Results: 1 passed, 1 failed, 1 allowed to fail"
+        );
+        assert_eq!(
+            format_test_summary(&[TestOutcome::skipped(
+                &serde_yaml::from_str("pytest\n").unwrap()
+            )]),
+            "Test Results: 0 passed, 0 failed, 0 allowed to fail, 1 skipped"
+        );
+    }
+}
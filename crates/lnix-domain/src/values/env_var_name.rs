@@ -29,6 +29,8 @@ fn is_valid_env_var_name(name: &str) -> bool {
     chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
 }
 
+string_schema!(EnvVarName, pattern = r"^[A-Za-z_][A-Za-z0-9_]*$");
+
 impl TryFrom<String> for EnvVarName {
     type Error = ParseError;
 
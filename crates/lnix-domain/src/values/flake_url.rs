@@ -0,0 +1,97 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ParseError;
+
+/// A flake reference URL for a custom input, such as
+/// `github:nix-community/fenix` or `git+https://example.com/flake.git`.
+///
+/// Invariants (checked at construction):
+/// - non-empty
+/// - no whitespace, `"`, `\`, or `$`
+///
+/// The flake reference grammar is left to Nix; the restriction only
+/// guarantees the URL can be embedded in a Nix string literal verbatim.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct FlakeUrl(String);
+
+impl FlakeUrl {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+fn is_valid_flake_url(url: &str) -> bool {
+    !url.is_empty()
+        && !url
+            .chars()
+            .any(|c| c.is_whitespace() || c == '"' || c == '\\' || c == '$')
+}
+
+string_schema!(FlakeUrl, pattern = r#"^[^\s"\\$]+$"#);
+
+impl TryFrom<String> for FlakeUrl {
+    type Error = ParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if !is_valid_flake_url(&value) {
+            return Err(ParseError::InvalidFlakeUrl(value));
+        }
+        Ok(Self(value))
+    }
+}
+
+impl FromStr for FlakeUrl {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s.to_string())
+    }
+}
+
+impl From<FlakeUrl> for String {
+    fn from(url: FlakeUrl) -> Self {
+        url.0
+    }
+}
+
+impl fmt::Display for FlakeUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_flake_references() {
+        // Arrange
+        let valid_urls = [
+            "github:nix-community/fenix",
+            "github:oxalica/rust-overlay?ref=stable",
+            "git+https://example.com/flake.git",
+            "path:./nix/local-flake",
+        ];
+
+        // Act & Assert
+        for url in valid_urls {
+            assert!(url.parse::<FlakeUrl>().is_ok(), "should accept {url}");
+        }
+    }
+
+    #[test]
+    fn rejects_urls_that_break_a_nix_string() {
+        // Arrange
+        let invalid_urls = ["", "github:a/b c", "github:a/\"b", "github:${x}", "a\\b"];
+
+        // Act & Assert
+        for url in invalid_urls {
+            assert!(url.parse::<FlakeUrl>().is_err(), "should reject {url:?}");
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ParseError;
+
+/// A flake input name: the `name` of an `inputs:` entry in
+/// `lazynix.yaml`, rendered as `<name>.url = ...` and as an `outputs`
+/// parameter in the generated flake.
+///
+/// Invariants (checked at construction):
+/// - starts with an ASCII letter or `_`
+/// - only ASCII alphanumerics, `-`, and `_`
+///
+/// Together these make the name a valid Nix identifier, so it can be
+/// used both as an attribute name and as a function parameter.
+/// Whether a name collides with an input LazyNix declares itself is a
+/// cross-field concern checked by [`crate::validate_config`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct InputName(String);
+
+impl InputName {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+fn is_valid_input_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    if !first.is_ascii_alphabetic() && first != '_' {
+        return false;
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+string_schema!(InputName, pattern = r"^[A-Za-z_][A-Za-z0-9_-]*$");
+
+impl TryFrom<String> for InputName {
+    type Error = ParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if !is_valid_input_name(&value) {
+            return Err(ParseError::InvalidInputName(value));
+        }
+        Ok(Self(value))
+    }
+}
+
+impl FromStr for InputName {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s.to_string())
+    }
+}
+
+impl From<InputName> for String {
+    fn from(name: InputName) -> Self {
+        name.0
+    }
+}
+
+impl fmt::Display for InputName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_nix_identifiers() {
+        // Arrange
+        let valid_names = ["fenix", "rust-overlay", "company_flake", "_local"];
+
+        // Act & Assert
+        for name in valid_names {
+            assert!(name.parse::<InputName>().is_ok(), "should accept {name}");
+        }
+    }
+
+    #[test]
+    fn rejects_non_identifiers() {
+        // Arrange
+        let invalid_names = ["", "1flake", "-flake", "rust.overlay", "my flake", "a\"b"];
+
+        // Act & Assert
+        for name in invalid_names {
+            assert!(name.parse::<InputName>().is_err(), "should reject {name:?}");
+        }
+    }
+}
@@ -2,16 +2,145 @@
 //!
 //! Each type wraps a `String` and enforces its invariant in
 //! `TryFrom<String>`. Serde integration (`try_from` / `into`) ensures
-//! that deserialized values are always valid.
+//! that deserialized values are always valid. Their JSON Schema is a
+//! string `pattern` that mirrors the same invariant, so that editors
+//! can flag an invalid value before LazyNix parses it.
+
+/// Implements `JsonSchema` for a value object as a string with the
+/// given `pattern` (or other string keywords).
+macro_rules! string_schema {
+    ($type:ident, pattern = $pattern:literal) => {
+        string_schema!($type, { "pattern": $pattern });
+    };
+    ($type:ident, { $($keyword:literal: $value:expr),* }) => {
+        impl schemars::JsonSchema for $type {
+            fn schema_name() -> std::borrow::Cow<'static, str> {
+                stringify!($type).into()
+            }
+
+            fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+                schemars::json_schema!({ "type": "string", $($keyword: $value),* })
+            }
+        }
+    };
+}
 
 mod env_var_name;
+mod flake_url;
+mod input_name;
+mod nix_file_path;
+mod nix_system;
+mod overlay_name;
 mod package_name;
 mod package_version;
 mod registry_url;
+mod service_name;
+mod shell_name;
+mod task_arg_name;
 mod task_name;
+mod timeout;
 
 pub use env_var_name::EnvVarName;
+pub use flake_url::FlakeUrl;
+pub use input_name::InputName;
+pub use nix_file_path::NixFilePath;
+pub use nix_system::NixSystem;
+pub use overlay_name::OverlayName;
 pub use package_name::PackageName;
 pub use package_version::PackageVersion;
 pub use registry_url::RegistryUrl;
+pub use service_name::ServiceName;
+pub use shell_name::ShellName;
+pub use task_arg_name::TaskArgName;
 pub use task_name::TaskName;
+pub use timeout::Timeout;
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+    use schemars::{JsonSchema, schema_for};
+
+    use super::*;
+
+    /// Asserts that the schema `pattern` of `T` accepts exactly the
+    /// samples its `TryFrom<String>` accepts.
+    fn assert_pattern_agrees<T: JsonSchema + TryFrom<String>>(samples: &[&str]) {
+        let schema = schema_for!(T);
+        let pattern = schema.get("pattern").and_then(|p| p.as_str()).unwrap();
+        let regex = Regex::new(pattern).unwrap();
+        for sample in samples {
+            assert_eq!(
+                regex.is_match(sample),
+                T::try_from(sample.to_string()).is_ok(),
+                "{pattern} disagrees with the parser on {sample:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn schema_patterns_agree_with_parsers() {
+        let names = [
+            "", "a", "_a", "A1", "1a", "-a", "a-", "a-b", "a--b", "a_b", "a.b", "a b", "a@b", "é",
+            "aé",
+        ];
+        assert_pattern_agrees::<EnvVarName>(&names);
+        assert_pattern_agrees::<InputName>(&names);
+        assert_pattern_agrees::<OverlayName>(&names);
+        assert_pattern_agrees::<ServiceName>(&names);
+        assert_pattern_agrees::<ShellName>(&names);
+        assert_pattern_agrees::<TaskArgName>(&names);
+        assert_pattern_agrees::<TaskName>(&names);
+        assert_pattern_agrees::<PackageName>(&[
+            "python312",
+            "python312Packages.pip",
+            "nodePackages.@angular/cli",
+            "-",
+            "a-.b",
+            "-a-.-b-",
+            ".a",
+            "a.",
+            "a..b",
+            "a--b",
+            "a.-",
+            "a-b.c--d",
+            "pythön",
+        ]);
+        assert_pattern_agrees::<RegistryUrl>(&[
+            "github:NixOS/nixpkgs/nixos-25.06",
+            "github:NixOS/nixpkgs",
+            "github:NixOS//main",
+            "github:a/b/c/d",
+            "gitlab:a/b/c",
+            "github:NixOS/nixpkgs/nixös",
+        ]);
+        assert_pattern_agrees::<FlakeUrl>(&[
+            "github:nix-community/fenix",
+            "git+https://example.com/flake.git?ref=main",
+            "",
+            "a b",
+            "a\"b",
+            "a\\b",
+            "$HOME",
+        ]);
+        assert_pattern_agrees::<NixFilePath>(&[
+            "./nix/overlay.nix",
+            "../shared/c++.nix",
+            "overlay.nix",
+            "",
+            "/abs.nix",
+            "~/x.nix",
+            "nix/",
+            "nix//x.nix",
+            "a b.nix",
+        ]);
+        assert_pattern_agrees::<NixSystem>(&[
+            "x86_64-linux",
+            "aarch64-darwin",
+            "linux",
+            "-linux",
+            "x86_64-",
+            "a-b-c",
+        ]);
+        assert_pattern_agrees::<Timeout>(&["90s", "5m", "1h", "010s", "0s", "00h", "5", "5d", "s"]);
+    }
+}
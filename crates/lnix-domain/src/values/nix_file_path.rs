@@ -0,0 +1,132 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ParseError;
+
+/// A `.nix` file inside the project, relative to `flake.nix`, such as
+/// `./nix/overlay.nix`. Rendered as a Nix path literal.
+///
+/// Invariants (checked at construction):
+/// - relative: no leading `/` or `~`
+/// - only ASCII alphanumerics, `.`, `_`, `-`, `+`, and `/`
+/// - no empty segment and no trailing `/`
+///
+/// The restriction matches what a Nix path literal accepts unquoted;
+/// absolute paths are rejected because a flake cannot read them under
+/// pure evaluation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct NixFilePath(String);
+
+impl NixFilePath {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The path as a Nix path literal: `./`-prefixed unless it already
+    /// starts with `./` or `../`.
+    pub fn to_nix_literal(&self) -> String {
+        if self.0.starts_with("./") || self.0.starts_with("../") {
+            self.0.clone()
+        } else {
+            format!("./{}", self.0)
+        }
+    }
+}
+
+fn is_valid_nix_file_path(path: &str) -> bool {
+    let allowed = |c: char| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '+' | '/');
+    !path.is_empty()
+        && !path.starts_with('/')
+        && path.chars().all(allowed)
+        && path.split('/').all(|segment| !segment.is_empty())
+}
+
+string_schema!(
+    NixFilePath,
+    pattern = r"^[A-Za-z0-9._+-]+(?:/[A-Za-z0-9._+-]+)*$"
+);
+
+impl TryFrom<String> for NixFilePath {
+    type Error = ParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if !is_valid_nix_file_path(&value) {
+            return Err(ParseError::InvalidNixFilePath(value));
+        }
+        Ok(Self(value))
+    }
+}
+
+impl FromStr for NixFilePath {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s.to_string())
+    }
+}
+
+impl From<NixFilePath> for String {
+    fn from(path: NixFilePath) -> Self {
+        path.0
+    }
+}
+
+impl fmt::Display for NixFilePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_relative_paths() {
+        // Arrange
+        let valid_paths = ["./nix/overlay.nix", "overlay.nix", "../shared/fixes.nix"];
+
+        // Act & Assert
+        for path in valid_paths {
+            assert!(path.parse::<NixFilePath>().is_ok(), "should accept {path}");
+        }
+    }
+
+    #[test]
+    fn rejects_absolute_and_unquotable_paths() {
+        // Arrange
+        let invalid_paths = [
+            "",
+            "/etc/overlay.nix",
+            "~/overlay.nix",
+            "nix//overlay.nix",
+            "nix/",
+            "my overlay.nix",
+            "${x}.nix",
+        ];
+
+        // Act & Assert
+        for path in invalid_paths {
+            assert!(
+                path.parse::<NixFilePath>().is_err(),
+                "should reject {path:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn renders_as_dot_slash_prefixed_literal() {
+        // Arrange
+        let bare: NixFilePath = "nix/overlay.nix".parse().unwrap();
+        let dotted: NixFilePath = "./nix/overlay.nix".parse().unwrap();
+        let parent: NixFilePath = "../overlay.nix".parse().unwrap();
+
+        // Act & Assert
+        assert_eq!(bare.to_nix_literal(), "./nix/overlay.nix");
+        assert_eq!(dotted.to_nix_literal(), "./nix/overlay.nix");
+        assert_eq!(parent.to_nix_literal(), "../overlay.nix");
+    }
+}
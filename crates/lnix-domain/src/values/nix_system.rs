@@ -0,0 +1,111 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ParseError;
+
+/// A Nix system double such as `x86_64-linux` or `aarch64-darwin`: the
+/// value of `system` inside the `eachDefaultSystem` closure.
+///
+/// Invariants (checked at construction):
+/// - exactly one `-`, separating a non-empty CPU and OS part
+/// - only ASCII alphanumerics and `_` in either part
+///
+/// The character restriction lets the value be embedded in a Nix
+/// string literal and passed to `nix eval --system` without quoting.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct NixSystem(String);
+
+impl NixSystem {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+fn is_valid_part(part: &str) -> bool {
+    !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+string_schema!(NixSystem, pattern = r"^[A-Za-z0-9_]+-[A-Za-z0-9_]+$");
+
+impl TryFrom<String> for NixSystem {
+    type Error = ParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let valid = value
+            .split_once('-')
+            .is_some_and(|(cpu, os)| is_valid_part(cpu) && is_valid_part(os));
+        if !valid {
+            return Err(ParseError::InvalidSystem(value));
+        }
+        Ok(Self(value))
+    }
+}
+
+impl FromStr for NixSystem {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s.to_string())
+    }
+}
+
+impl From<NixSystem> for String {
+    fn from(system: NixSystem) -> Self {
+        system.0
+    }
+}
+
+impl fmt::Display for NixSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_system_doubles() {
+        // Arrange
+        let valid_systems = [
+            "x86_64-linux",
+            "aarch64-linux",
+            "x86_64-darwin",
+            "aarch64-darwin",
+            "armv7l-linux",
+        ];
+
+        // Act & Assert
+        for system in valid_systems {
+            assert!(
+                system.parse::<NixSystem>().is_ok(),
+                "should accept {system}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_anything_else() {
+        // Arrange
+        let invalid_systems = [
+            "",
+            "linux",
+            "-linux",
+            "x86_64-",
+            "x86_64-unknown-linux",
+            "x86_64-linux\"",
+        ];
+
+        // Act & Assert
+        for system in invalid_systems {
+            assert!(
+                system.parse::<NixSystem>().is_err(),
+                "should reject {system:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ParseError;
+
+/// Name of an overlay exported by a flake input: the `<name>` in
+/// `<input>.overlays.<name>`.
+///
+/// Invariants (checked at construction):
+/// - starts with an ASCII letter or `_`
+/// - only ASCII alphanumerics, `-`, and `_`
+///
+/// This keeps `<input>.overlays.<name>` a valid unquoted attribute path.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct OverlayName(String);
+
+impl OverlayName {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// `overlays.default`, the conventional name of a flake's main overlay.
+impl Default for OverlayName {
+    fn default() -> Self {
+        Self("default".to_string())
+    }
+}
+
+fn is_valid_overlay_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    if !first.is_ascii_alphabetic() && first != '_' {
+        return false;
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+string_schema!(OverlayName, pattern = r"^[A-Za-z_][A-Za-z0-9_-]*$");
+
+impl TryFrom<String> for OverlayName {
+    type Error = ParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if !is_valid_overlay_name(&value) {
+            return Err(ParseError::InvalidOverlayName(value));
+        }
+        Ok(Self(value))
+    }
+}
+
+impl FromStr for OverlayName {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s.to_string())
+    }
+}
+
+impl From<OverlayName> for String {
+    fn from(name: OverlayName) -> Self {
+        name.0
+    }
+}
+
+impl fmt::Display for OverlayName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_attribute_names() {
+        // Arrange
+        let valid_names = ["default", "rust-overlay", "python_fixes", "_local"];
+
+        // Act & Assert
+        for name in valid_names {
+            assert!(name.parse::<OverlayName>().is_ok(), "should accept {name}");
+        }
+    }
+
+    #[test]
+    fn rejects_non_attribute_names() {
+        // Arrange
+        let invalid_names = ["", "1fix", "-fix", "rust.overlay", "my fix", "a\"b"];
+
+        // Act & Assert
+        for name in invalid_names {
+            assert!(
+                name.parse::<OverlayName>().is_err(),
+                "should reject {name:?}"
+            );
+        }
+    }
+}
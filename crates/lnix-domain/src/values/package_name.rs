@@ -9,7 +9,7 @@ use crate::error::ParseError;
 ///
 /// Invariants (checked at construction):
 /// - non-empty
-/// - only alphanumerics, `-`, `_`, and `.`
+/// - only ASCII alphanumerics, `-`, `_`, and `.`
 /// - dots may appear only between segments (no leading/trailing/double dots)
 /// - no consecutive hyphens (`--`); the pinned-input naming scheme
 ///   uses `--` as a name/version separator and must stay unambiguous
@@ -36,9 +36,15 @@ fn is_valid_package_name(name: &str) -> bool {
         return false;
     }
     name.chars()
-        .all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.')
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
 }
 
+// Dot-separated segments, none of which contains `--`.
+string_schema!(
+    PackageName,
+    pattern = r"^(?:(?:-?[A-Za-z0-9_])+-?|-)(?:\.(?:(?:-?[A-Za-z0-9_])+-?|-))*$"
+);
+
 impl TryFrom<String> for PackageName {
     type Error = ParseError;
 
@@ -16,6 +16,8 @@ use crate::error::ParseError;
 #[serde(try_from = "String", into = "String")]
 pub struct PackageVersion(String);
 
+string_schema!(PackageVersion, { "minLength": 1 });
+
 impl PackageVersion {
     pub fn as_str(&self) -> &str {
         &self.0
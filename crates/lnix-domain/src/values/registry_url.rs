@@ -9,7 +9,7 @@ use crate::error::ParseError;
 /// such as `github:NixOS/nixpkgs/nixos-25.06`.
 ///
 /// Invariant: `github:OWNER/REPO/BRANCH` where each part is non-empty
-/// and contains only alphanumerics, `-`, `_`, and `.`.
+/// and contains only ASCII alphanumerics, `-`, `_`, and `.`.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(try_from = "String", into = "String")]
 pub struct RegistryUrl(String);
@@ -33,10 +33,15 @@ fn is_valid_registry_url(url: &str) -> bool {
         !part.is_empty()
             && part
                 .chars()
-                .all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.')
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
     })
 }
 
+string_schema!(
+    RegistryUrl,
+    pattern = r"^github:[A-Za-z0-9._-]+/[A-Za-z0-9._-]+/[A-Za-z0-9._-]+$"
+);
+
 impl TryFrom<String> for RegistryUrl {
     type Error = ParseError;
 
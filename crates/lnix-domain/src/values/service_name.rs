@@ -0,0 +1,92 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ParseError;
+
+/// The name of a background service from the `services:` section of
+/// `lazynix.yaml`. It also names the service's files under `.lazynix/`.
+///
+/// Invariants (checked at construction):
+/// - non-empty
+/// - only ASCII alphanumerics, `-`, and `_`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ServiceName(String);
+
+impl ServiceName {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+fn is_valid_service_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+string_schema!(ServiceName, pattern = r"^[A-Za-z0-9_-]+$");
+
+impl TryFrom<String> for ServiceName {
+    type Error = ParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if !is_valid_service_name(&value) {
+            return Err(ParseError::InvalidServiceName(value));
+        }
+        Ok(Self(value))
+    }
+}
+
+impl FromStr for ServiceName {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s.to_string())
+    }
+}
+
+impl From<ServiceName> for String {
+    fn from(name: ServiceName) -> Self {
+        name.0
+    }
+}
+
+impl fmt::Display for ServiceName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_alphanumeric_hyphen_underscore() {
+        // Arrange
+        let valid_names = ["postgres", "redis-6_2", "API"];
+
+        // Act & Assert
+        for name in valid_names {
+            assert!(name.parse::<ServiceName>().is_ok(), "should accept {name}");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_names() {
+        // Arrange
+        let invalid_names = ["", "..", "redis/cache", "my service"];
+
+        // Act & Assert
+        for name in invalid_names {
+            assert!(
+                name.parse::<ServiceName>().is_err(),
+                "should reject {name:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ParseError;
+
+/// Name of the dev shell that `devShell:` maps to, and the one
+/// `nix develop` picks when no installable is given.
+const DEFAULT_SHELL_NAME: &str = "default";
+
+/// A dev shell name: a key of the `devShells:` map in `lazynix.yaml`,
+/// rendered as `devShells.<name>` in the generated flake.
+///
+/// Invariants (checked at construction):
+/// - starts with an ASCII letter or `_`
+/// - only ASCII alphanumerics, `-`, and `_`
+///
+/// The leading-character rule keeps `devShells.<name>` a valid
+/// unquoted Nix attribute path, and the character restriction lets the
+/// name be embedded in a `.#<name>` installable without quoting.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ShellName(String);
+
+impl ShellName {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// The `default` shell, which the legacy `devShell:` section defines.
+impl Default for ShellName {
+    fn default() -> Self {
+        Self(DEFAULT_SHELL_NAME.to_string())
+    }
+}
+
+fn is_valid_shell_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    if !first.is_ascii_alphabetic() && first != '_' {
+        return false;
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+string_schema!(ShellName, pattern = r"^[A-Za-z_][A-Za-z0-9_-]*$");
+
+impl TryFrom<String> for ShellName {
+    type Error = ParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if !is_valid_shell_name(&value) {
+            return Err(ParseError::InvalidShellName(value));
+        }
+        Ok(Self(value))
+    }
+}
+
+impl FromStr for ShellName {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s.to_string())
+    }
+}
+
+impl From<ShellName> for String {
+    fn from(name: ShellName) -> Self {
+        name.0
+    }
+}
+
+impl fmt::Display for ShellName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_nix_attribute_style_names() {
+        // Arrange
+        let valid_names = ["default", "ci", "docs-build", "_private", "py312"];
+
+        // Act & Assert
+        for name in valid_names {
+            assert!(name.parse::<ShellName>().is_ok(), "should accept {name}");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_names() {
+        // Arrange
+        let invalid_names = ["", "1ci", "-ci", "ci.docs", "ci docs", "ci#docs"];
+
+        // Act & Assert
+        for name in invalid_names {
+            assert!(name.parse::<ShellName>().is_err(), "should reject {name:?}");
+        }
+    }
+
+    #[test]
+    fn default_is_the_default_shell() {
+        // Arrange / Act
+        let name = ShellName::default();
+
+        // Assert
+        assert_eq!(name.as_str(), "default");
+    }
+}
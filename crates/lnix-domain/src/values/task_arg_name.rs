@@ -0,0 +1,96 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ParseError;
+
+/// The name of a task argument declared under `args:`, passed as
+/// `--<name> <value>` and referenced as `{{.ARGS.<name>}}`.
+///
+/// Invariants (checked at construction):
+/// - starts with an ASCII letter
+/// - only ASCII alphanumerics, `-`, and `_`
+///
+/// The leading letter keeps `--<name>` from looking like a negative
+/// number or a bare `--`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TaskArgName(String);
+
+impl TaskArgName {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+fn is_valid_task_arg_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    first.is_ascii_alphabetic() && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+string_schema!(TaskArgName, pattern = r"^[A-Za-z][A-Za-z0-9_-]*$");
+
+impl TryFrom<String> for TaskArgName {
+    type Error = ParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if !is_valid_task_arg_name(&value) {
+            return Err(ParseError::InvalidTaskArgName(value));
+        }
+        Ok(Self(value))
+    }
+}
+
+impl FromStr for TaskArgName {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s.to_string())
+    }
+}
+
+impl From<TaskArgName> for String {
+    fn from(name: TaskArgName) -> Self {
+        name.0
+    }
+}
+
+impl fmt::Display for TaskArgName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_option_like_names() {
+        // Arrange
+        let valid_names = ["target", "dry-run", "max_jobs", "v2"];
+
+        // Act & Assert
+        for name in valid_names {
+            assert!(name.parse::<TaskArgName>().is_ok(), "should accept {name}");
+        }
+    }
+
+    #[test]
+    fn rejects_names_that_cannot_be_options() {
+        // Arrange
+        let invalid_names = ["", "-target", "1st", "_x", "a.b", "my arg"];
+
+        // Act & Assert
+        for name in invalid_names {
+            assert!(
+                name.parse::<TaskArgName>().is_err(),
+                "should reject {name:?}"
+            );
+        }
+    }
+}
@@ -10,7 +10,7 @@ use crate::error::ParseError;
 /// Invariants (checked at construction):
 /// - non-empty
 /// - only ASCII alphanumerics, `-`, and `_`
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(try_from = "String", into = "String")]
 pub struct TaskName(String);
 
@@ -27,6 +27,8 @@ fn is_valid_task_name(name: &str) -> bool {
             .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
 }
 
+string_schema!(TaskName, pattern = r"^[A-Za-z0-9_-]+$");
+
 impl TryFrom<String> for TaskName {
     type Error = ParseError;
 
@@ -0,0 +1,113 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ParseError;
+
+/// How long a test may run, written as a whole number with a unit:
+/// `90s`, `5m`, or `1h`.
+///
+/// Invariants (checked at construction):
+/// - one or more ASCII digits followed by exactly one of `s`, `m`, `h`
+/// - greater than zero
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Timeout(String);
+
+impl Timeout {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The timeout in whole seconds.
+    pub fn as_secs(&self) -> u64 {
+        let (amount, unit) = self.0.split_at(self.0.len() - 1);
+        let amount: u64 = amount.parse().unwrap_or_default();
+        match unit {
+            "h" => amount * 3600,
+            "m" => amount * 60,
+            _ => amount,
+        }
+    }
+
+    pub fn as_duration(&self) -> Duration {
+        Duration::from_secs(self.as_secs())
+    }
+}
+
+fn is_valid_timeout(value: &str) -> bool {
+    let Some(amount) = value.strip_suffix(['s', 'm', 'h']) else {
+        return false;
+    };
+    !amount.is_empty()
+        && amount.chars().all(|c| c.is_ascii_digit())
+        && amount
+            .parse::<u64>()
+            .is_ok_and(|amount| amount > 0 && amount <= u32::MAX.into())
+}
+
+string_schema!(Timeout, pattern = r"^0*[1-9][0-9]*[smh]$");
+
+impl TryFrom<String> for Timeout {
+    type Error = ParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if !is_valid_timeout(&value) {
+            return Err(ParseError::InvalidTimeout(value));
+        }
+        Ok(Self(value))
+    }
+}
+
+impl FromStr for Timeout {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s.to_string())
+    }
+}
+
+impl From<Timeout> for String {
+    fn from(timeout: Timeout) -> Self {
+        timeout.0
+    }
+}
+
+impl fmt::Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_units_to_seconds() {
+        // Arrange
+        let cases = [("90s", 90), ("5m", 300), ("1h", 3600)];
+
+        // Act & Assert
+        for (value, secs) in cases {
+            assert_eq!(value.parse::<Timeout>().unwrap().as_secs(), secs);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_timeouts() {
+        // Arrange
+        let invalid = ["", "30", "s", "0s", "1.5m", "-1s", "10d", "5 m"];
+
+        // Act & Assert
+        for value in invalid {
+            assert_eq!(
+                value.parse::<Timeout>(),
+                Err(ParseError::InvalidTimeout(value.to_string())),
+                "should reject {value}"
+            );
+        }
+    }
+}
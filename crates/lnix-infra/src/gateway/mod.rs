@@ -2,8 +2,12 @@
 
 mod nix_evaluator;
 mod nix_runner;
+mod package_index;
+mod service_supervisor;
 mod version_resolver;
 
 pub use nix_evaluator::SubprocessNixEvaluator;
 pub use nix_runner::SubprocessNixRunner;
+pub use package_index::NixPackageIndex;
+pub use service_supervisor::SubprocessServiceSupervisor;
 pub use version_resolver::NixVersionsResolver;
@@ -1,11 +1,16 @@
 //! Subprocess-backed [`NixRunner`] (interactive `nix` invocations).
 
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
 
-use lnix_domain::NixError;
-use lnix_domain::interface::gateway::NixRunner;
+use lnix_domain::interface::gateway::{DevEnv, NixRunner, TestExecution, TestInvocation};
+use lnix_domain::{NixError, ShellName, Timeout};
 
-use crate::process::run_inherit;
+use crate::process::{run_inherit, run_stdout, run_tee};
+use rayon::prelude::*;
 
 /// Runs `nix` with inherited stdio via [`run_inherit`].
 pub struct SubprocessNixRunner;
@@ -14,45 +19,229 @@ fn nix() -> Command {
     Command::new("nix")
 }
 
+/// `nix develop .#<shell>`, the prefix shared by every shell-entering
+/// invocation.
+fn nix_develop(shell: &ShellName) -> Command {
+    let mut command = nix();
+    command.arg("develop").arg(format!(".#{}", shell));
+    command
+}
+
+/// Variables `nix print-dev-env` leaves out of the dev environment
+/// because they only make sense inside the build sandbox.
+const SANDBOX_VARIABLES: &[&str] = &[
+    "BASHOPTS",
+    "HOME",
+    "NIX_BUILD_TOP",
+    "NIX_ENFORCE_PURITY",
+    "NIX_LOG_FD",
+    "NIX_REMOTE",
+    "PPID",
+    "SHELLOPTS",
+    "SSL_CERT_FILE",
+    "TEMP",
+    "TEMPDIR",
+    "TERM",
+    "TMP",
+    "TMPDIR",
+    "TZ",
+    "UID",
+];
+
+/// Variables bash sets for itself.
+const BASH_VARIABLES: &[&str] = &["OLDPWD", "PWD", "SHLVL", "_"];
+
+/// The exported string variables of `nix print-dev-env --json` output.
+fn exported_variables(json: &str) -> Result<BTreeMap<String, String>, NixError> {
+    let invalid = |message: &str| NixError::InvalidDevEnv(message.to_string());
+    let parsed: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| invalid(&e.to_string()))?;
+    let variables = parsed
+        .get("variables")
+        .and_then(serde_json::Value::as_object)
+        .ok_or_else(|| invalid("no variables"))?;
+    Ok(variables
+        .iter()
+        .filter(|(name, _)| !SANDBOX_VARIABLES.contains(&name.as_str()))
+        .filter(|(_, variable)| variable["type"] == "exported")
+        .filter_map(|(name, variable)| {
+            Some((name.clone(), variable["value"].as_str()?.to_string()))
+        })
+        .collect())
+}
+
+/// Runs `setup` with bash in the inherited environment overlaid with
+/// `exported`, and returns `exported` as updated by `setup`, plus the
+/// variables `setup` added or changed.
+fn apply_setup(
+    mut exported: BTreeMap<String, String>,
+    setup: &str,
+) -> Result<BTreeMap<String, String>, NixError> {
+    let mut base: BTreeMap<String, String> = std::env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+        .collect();
+    if let Some(host_path) = base.get("PATH")
+        && let Some(path) = exported.get_mut("PATH")
+    {
+        path.push(':');
+        path.push_str(host_path);
+    }
+    base.extend(exported.clone());
+
+    let mut command = Command::new("bash");
+    command
+        .arg("-c")
+        .arg(format!("{}\nexec env -0", setup))
+        .env_clear()
+        .envs(&base);
+    let after: BTreeMap<String, String> = run_stdout(command)?
+        .split('\0')
+        .filter_map(|entry| entry.split_once('='))
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+
+    Ok(after
+        .into_iter()
+        .filter(|(name, _)| !BASH_VARIABLES.contains(&name.as_str()))
+        .filter(|(name, value)| exported.contains_key(name) || base.get(name) != Some(value))
+        .collect())
+}
+
+/// Script [`in_dev_env`] feeds to `bash -s <timeout> <script>`. The
+/// arguments are saved before the dev environment is sourced, since its
+/// `shellHook` may change them. The environment's own output is
+/// dropped (its errors still reach stderr), so that a hook's greeting
+/// is neither repeated per test nor kept as test output. The script
+/// itself reads from `/dev/null`: bash's stdin is this wrapper.
+const DEV_ENV_WRAPPER_HEAD: &str = "__lazynix_timeout=$1\n__lazynix_script=$2\n{\n";
+
+const DEV_ENV_WRAPPER_TAIL: &str = r#"
+:
+} >/dev/null
+exec 2>&1
+if [ -n "$__lazynix_timeout" ]; then
+    exec timeout "$__lazynix_timeout" bash -c "$__lazynix_script" </dev/null
+fi
+exec bash -c "$__lazynix_script" </dev/null
+"#;
+
+/// A bash command, and the input to feed it on stdin, that runs
+/// `script` inside `env` (killed after `timeout`), with stderr merged
+/// into stdout. Shared by tests and services.
+pub(crate) fn in_dev_env(
+    env: &DevEnv,
+    script: &str,
+    timeout: Option<&Timeout>,
+) -> (Command, String) {
+    let timeout = timeout
+        .map(|timeout| timeout.as_secs().to_string())
+        .unwrap_or_default();
+    let mut command = Command::new("bash");
+    command.arg("-s").arg(timeout).arg(script);
+    let input = format!(
+        "{}{}{}",
+        DEV_ENV_WRAPPER_HEAD,
+        env.script(),
+        DEV_ENV_WRAPPER_TAIL
+    );
+    (command, input)
+}
+
+/// Runs `script` in `env` via [`in_dev_env`], echoing its output to
+/// the terminal when `echo` is set.
+fn execute_test(
+    env: &DevEnv,
+    script: &str,
+    timeout: Option<&Timeout>,
+    echo: bool,
+) -> Result<TestExecution, NixError> {
+    let (command, input) = in_dev_env(env, script, timeout);
+    let started = Instant::now();
+    let teed = run_tee(command, &input, echo)?;
+    Ok(TestExecution {
+        exit_code: teed.code,
+        duration: started.elapsed(),
+        output: teed.stdout,
+    })
+}
+
 impl NixRunner for SubprocessNixRunner {
-    fn develop(&self) -> Result<(), NixError> {
-        let mut command = nix();
-        command.arg("develop");
-        match run_inherit(command)? {
+    fn develop(&self, shell: &ShellName) -> Result<(), NixError> {
+        match run_inherit(nix_develop(shell))? {
             0 => Ok(()),
             code => Err(NixError::NonZeroExit(code)),
         }
     }
 
-    fn develop_command(&self, args: &[String]) -> Result<i32, NixError> {
-        let mut command = nix();
-        command.arg("develop").arg("-c").args(args);
+    fn develop_command(&self, shell: &ShellName, args: &[String]) -> Result<i32, NixError> {
+        let mut command = nix_develop(shell);
+        command.arg("-c").args(args);
         run_inherit(command)
     }
 
-    fn test(&self) -> Result<i32, NixError> {
-        // LAZYNIX_TEST_MODE makes the generated shellHook run the
-        // declared test commands instead of opening a shell.
+    fn dev_env(&self, shell: &ShellName) -> Result<DevEnv, NixError> {
         let mut command = nix();
-        command
-            .arg("develop")
-            .arg("-c")
-            .arg("bash")
-            .arg("-c")
-            .arg("true")
-            .env("LAZYNIX_TEST_MODE", "1");
-        run_inherit(command)
+        command.arg("print-dev-env").arg(format!(".#{}", shell));
+        Ok(DevEnv::new(run_stdout(command)?))
     }
 
-    fn run_task(&self, commands: &[String]) -> Result<i32, NixError> {
-        let script = commands.join(" && ");
+    fn dev_env_vars(
+        &self,
+        shell: &ShellName,
+        setup: &str,
+    ) -> Result<BTreeMap<String, String>, NixError> {
         let mut command = nix();
         command
-            .arg("develop")
-            .arg("-c")
-            .arg("sh")
-            .arg("-c")
-            .arg(&script);
+            .arg("print-dev-env")
+            .arg("--json")
+            .arg(format!(".#{}", shell));
+        apply_setup(exported_variables(&run_stdout(command)?)?, setup)
+    }
+
+    fn run_test(
+        &self,
+        env: &DevEnv,
+        script: &str,
+        timeout: Option<&Timeout>,
+    ) -> Result<TestExecution, NixError> {
+        execute_test(env, script, timeout, true)
+    }
+
+    /// Each test is a slow independent subprocess, so a rayon pool of
+    /// `jobs` threads runs them. Results are collected in input order.
+    fn run_tests(
+        &self,
+        env: &DevEnv,
+        tests: &[TestInvocation],
+        jobs: NonZeroUsize,
+        fail_fast: bool,
+    ) -> Result<Vec<Option<TestExecution>>, NixError> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs.get())
+            .build()
+            .map_err(|e| NixError::Spawn(std::io::Error::other(e)))?;
+        let stop = AtomicBool::new(false);
+        pool.install(|| {
+            tests
+                .par_iter()
+                .map(|test| {
+                    if stop.load(Ordering::SeqCst) {
+                        return Ok(None);
+                    }
+                    let execution = execute_test(env, &test.script, test.timeout.as_ref(), false)?;
+                    if fail_fast && execution.exit_code != 0 && !test.allow_failure {
+                        stop.store(true, Ordering::SeqCst);
+                    }
+                    Ok(Some(execution))
+                })
+                .collect()
+        })
+    }
+
+    fn run_task(&self, shell: &ShellName, commands: &[String]) -> Result<i32, NixError> {
+        let script = commands.join(" && ");
+        let mut command = nix_develop(shell);
+        command.arg("-c").arg("bash").arg("-c").arg(&script);
         run_inherit(command)
     }
 
@@ -65,3 +254,150 @@ impl NixRunner for SubprocessNixRunner {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn run(env: &str, script: &str, timeout: Option<&str>) -> TestExecution {
+        let timeout: Option<Timeout> = timeout.map(|timeout| timeout.parse().unwrap());
+        SubprocessNixRunner
+            .run_test(&DevEnv::new(env.to_string()), script, timeout.as_ref())
+            .unwrap()
+    }
+
+    fn invocation(script: &str, allow_failure: bool) -> TestInvocation {
+        TestInvocation {
+            script: script.to_string(),
+            timeout: None,
+            allow_failure,
+        }
+    }
+
+    #[test]
+    fn keeps_exported_string_variables_outside_the_sandbox() {
+        // Arrange
+        let json = r#"{"bashFunctions": {}, "variables": {
+            "PATH": {"type": "exported", "value": "/nix/store/x/bin"},
+            "HOME": {"type": "exported", "value": "/homeless-shelter"},
+            "shellHook": {"type": "var", "value": "echo hi"},
+            "outputs": {"type": "associative", "value": {"out": "/nix/store/y"}}
+        }}"#;
+
+        // Act
+        let exported = exported_variables(json).unwrap();
+
+        // Assert
+        assert_eq!(
+            exported,
+            BTreeMap::from([("PATH".to_string(), "/nix/store/x/bin".to_string())])
+        );
+        assert!(matches!(
+            exported_variables("[]"),
+            Err(NixError::InvalidDevEnv(_))
+        ));
+    }
+
+    #[test]
+    fn applies_setup_on_top_of_the_exported_variables() {
+        // Arrange
+        let exported = BTreeMap::from([
+            ("PATH".to_string(), "/nix/store/x/bin".to_string()),
+            ("GREETING".to_string(), "hi".to_string()),
+        ]);
+        let setup = r#"export GREETING="$GREETING there"
+export MULTILINE='a
+b'
+export HOME="$HOME""#;
+
+        // Act
+        let vars = apply_setup(exported, setup).unwrap();
+
+        // Assert
+        assert_eq!(vars["GREETING"], "hi there");
+        assert_eq!(vars["MULTILINE"], "a\nb");
+        assert!(vars["PATH"].starts_with("/nix/store/x/bin:"));
+        assert_eq!(vars.len(), 3, "unchanged inherited variables are left out");
+    }
+
+    #[test]
+    fn runs_the_script_inside_the_environment() {
+        // Act
+        let execution = run(
+            "export GREETING=hi\necho 'hook output'\nset -- clobbered",
+            r#"echo "$GREETING \"quoted\"" && echo err >&2 && exit 3"#,
+            None,
+        );
+
+        // Assert
+        assert_eq!(execution.exit_code, 3);
+        assert_eq!(execution.output, "hi \"quoted\"\nerr\n");
+    }
+
+    #[test]
+    fn stops_the_script_after_the_timeout() {
+        // Act
+        let execution = run("", "sleep 5", Some("1s"));
+
+        // Assert
+        assert_eq!(execution.exit_code, 124);
+        assert!(execution.duration < Duration::from_secs(4));
+    }
+
+    #[test]
+    fn runs_tests_concurrently_keeping_output_per_test() {
+        // Arrange
+        let tests: Vec<TestInvocation> = (0..4)
+            .map(|i| invocation(&format!("sleep 0.5; echo test {i}"), false))
+            .collect();
+        let started = Instant::now();
+
+        // Act
+        let executions = SubprocessNixRunner
+            .run_tests(
+                &DevEnv::new(String::new()),
+                &tests,
+                NonZeroUsize::new(4).unwrap(),
+                false,
+            )
+            .unwrap();
+
+        // Assert
+        assert!(started.elapsed() < Duration::from_millis(1500));
+        let outputs: Vec<String> = executions
+            .into_iter()
+            .map(|execution| execution.unwrap().output)
+            .collect();
+        assert_eq!(
+            outputs,
+            vec!["test 0\n", "test 1\n", "test 2\n", "test 3\n"]
+        );
+    }
+
+    #[test]
+    fn fail_fast_starts_no_test_after_a_failure() {
+        // Arrange
+        let tests = vec![
+            invocation("exit 1", true),
+            invocation("exit 2", false),
+            invocation("echo never", false),
+        ];
+
+        // Act
+        let executions = SubprocessNixRunner
+            .run_tests(
+                &DevEnv::new(String::new()),
+                &tests,
+                NonZeroUsize::new(1).unwrap(),
+                true,
+            )
+            .unwrap();
+
+        // Assert
+        assert_eq!(executions[0].as_ref().unwrap().exit_code, 1);
+        assert_eq!(executions[1].as_ref().unwrap().exit_code, 2);
+        assert_eq!(executions[2], None);
+    }
+}
@@ -0,0 +1,143 @@
+//! `nix search`-backed [`PackageIndex`].
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use serde::Deserialize;
+
+use lnix_domain::NixError;
+use lnix_domain::interface::gateway::{PackageIndex, PackageInfo};
+
+use crate::paths::WorkspacePaths;
+use crate::process::run_capture;
+
+/// One entry of `nix search --json` output.
+#[derive(Debug, Deserialize)]
+struct SearchEntry {
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    description: String,
+}
+
+/// Lists the project's stable nixpkgs with `nix search`, cached in
+/// `.lazynix/packages.json` until `flake.lock` changes.
+pub struct NixPackageIndex {
+    paths: WorkspacePaths,
+}
+
+impl NixPackageIndex {
+    pub fn new(paths: WorkspacePaths) -> Self {
+        Self { paths }
+    }
+
+    /// Whether the cache exists and is no older than `flake.lock`.
+    fn cache_is_fresh(&self) -> bool {
+        let modified = |path| fs::metadata(path).and_then(|meta| meta.modified()).ok();
+        match (
+            modified(self.cache_file()),
+            modified(self.paths.flake_lock_file()),
+        ) {
+            (Some(cache), Some(lock)) => cache >= lock,
+            (cache, _) => cache.is_some(),
+        }
+    }
+
+    fn cache_file(&self) -> PathBuf {
+        self.paths.state_dir().join("packages.json")
+    }
+
+    fn search(&self) -> Result<String, NixError> {
+        let mut command = Command::new("nix");
+        command
+            .arg("search")
+            .arg("--inputs-from")
+            .arg(self.paths.config_dir())
+            .arg("nixpkgs")
+            .arg("^")
+            .arg("--json");
+        let captured = run_capture(command)?;
+        if !captured.success {
+            return Err(NixError::PackageIndex(captured.stderr));
+        }
+        fs::create_dir_all(self.paths.state_dir())?;
+        fs::write(self.cache_file(), &captured.stdout)?;
+        Ok(captured.stdout)
+    }
+}
+
+impl PackageIndex for NixPackageIndex {
+    fn packages(&self) -> Result<Vec<PackageInfo>, NixError> {
+        let json = if self.cache_is_fresh() {
+            fs::read_to_string(self.cache_file())?
+        } else {
+            self.search()?
+        };
+        parse_search_output(&json)
+    }
+}
+
+/// Turns `nix search --json` output, keyed by
+/// `legacyPackages.<system>.<attr>`, into packages sorted by attribute.
+fn parse_search_output(json: &str) -> Result<Vec<PackageInfo>, NixError> {
+    let entries: BTreeMap<String, SearchEntry> = serde_json::from_str(json)
+        .map_err(|e| NixError::PackageIndex(format!("failed to parse nix search output: {e}")))?;
+    let mut packages: Vec<PackageInfo> = entries
+        .into_iter()
+        .map(|(key, entry)| PackageInfo {
+            attr: key.splitn(3, '.').nth(2).unwrap_or(&key).to_string(),
+            version: entry.version,
+            description: entry.description,
+        })
+        .collect();
+    packages.sort_by(|a, b| a.attr.cmp(&b.attr));
+    Ok(packages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn parses_search_output_into_sorted_packages() {
+        // Arrange
+        let json = r#"{
+            "legacyPackages.x86_64-linux.ripgrep": {"pname": "ripgrep", "version": "14.1.1", "description": "A fast grep"},
+            "legacyPackages.x86_64-linux.python312Packages.numpy": {"pname": "numpy", "version": "2.2.1", "description": ""},
+            "legacyPackages.x86_64-linux.jq": {"pname": "jq", "version": "1.7.1", "description": "JSON processor"}
+        }"#;
+
+        // Act
+        let packages = parse_search_output(json).unwrap();
+
+        // Assert
+        let attrs: Vec<&str> = packages.iter().map(|p| p.attr.as_str()).collect();
+        assert_eq!(attrs, vec!["jq", "python312Packages.numpy", "ripgrep"]);
+        assert_eq!(packages[0].version, "1.7.1");
+        assert_eq!(packages[0].description, "JSON processor");
+    }
+
+    #[test]
+    fn reads_a_fresh_cache_without_running_nix() {
+        // Arrange
+        let dir = TempDir::new().unwrap();
+        let index = NixPackageIndex::new(WorkspacePaths::new(dir.path()));
+        fs::write(dir.path().join("flake.lock"), "{}").unwrap();
+        fs::create_dir(dir.path().join(".lazynix")).unwrap();
+        fs::write(
+            dir.path().join(".lazynix/packages.json"),
+            r#"{"legacyPackages.x86_64-linux.jq": {"version": "1.7.1", "description": "JSON processor"}}"#,
+        )
+        .unwrap();
+
+        // Act
+        let packages = index.packages().unwrap();
+
+        // Assert
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0].attr, "jq");
+    }
+}
@@ -0,0 +1,507 @@
+//! Subprocess-backed [`ServiceSupervisor`].
+//!
+//! A service runs as the leader of its own process group, so that
+//! stopping it also stops whatever it forked, and so that Ctrl-C in
+//! the terminal that ran `lnix up` does not reach it. Its pid and log
+//! are kept at `.lazynix/services/<shell>/<name>.pid` and `.log`, and
+//! its data directory is `.lazynix/state/<shell>/<name>`, so that
+//! same-named services of two dev shells stay apart. The pid file also
+//! records when the process started, so that a stale one (left by a
+//! reboot, say) never gets a process that has since reused the pid
+//! signalled.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, ErrorKind};
+use std::net::TcpStream;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use lnix_domain::interface::gateway::{DevEnv, Readiness, ServiceStatus, ServiceSupervisor};
+use lnix_domain::{ReadyCheck, ServiceError, ServiceName, ShellName};
+
+use crate::gateway::nix_runner::in_dev_env;
+use crate::paths::WorkspacePaths;
+use crate::process::{run_inherit, spawn_fed};
+
+/// How often `wait_ready` probes, and `stop` checks for the exit.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// How long `stop` waits after SIGTERM before sending SIGKILL.
+const STOP_GRACE: Duration = Duration::from_secs(10);
+
+/// Starts services with `bash`, signals them with `kill`, and keeps
+/// their files under [`WorkspacePaths::state_dir`].
+pub struct SubprocessServiceSupervisor {
+    paths: WorkspacePaths,
+    /// Services started by this process, so they can be reaped: an
+    /// exited child that is never waited for still counts as alive.
+    children: RefCell<HashMap<(ShellName, ServiceName), Child>>,
+}
+
+impl SubprocessServiceSupervisor {
+    pub fn new(paths: WorkspacePaths) -> Self {
+        Self {
+            paths,
+            children: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn services_dir(&self, shell: &ShellName) -> PathBuf {
+        self.paths.state_dir().join("services").join(shell.as_str())
+    }
+
+    fn pid_file(&self, shell: &ShellName, service: &ServiceName) -> PathBuf {
+        self.services_dir(shell).join(format!("{}.pid", service))
+    }
+
+    fn log_file(&self, shell: &ShellName, service: &ServiceName) -> PathBuf {
+        self.services_dir(shell).join(format!("{}.log", service))
+    }
+
+    fn data_dir(&self, shell: &ShellName, service: &ServiceName) -> PathBuf {
+        self.paths
+            .state_dir()
+            .join("state")
+            .join(shell.as_str())
+            .join(service.as_str())
+    }
+
+    /// The pid in `service`'s pid file, and the start time recorded
+    /// with it.
+    fn recorded_pid(
+        &self,
+        shell: &ShellName,
+        service: &ServiceName,
+    ) -> io::Result<Option<(u32, String)>> {
+        match fs::read_to_string(self.pid_file(shell, service)) {
+            Ok(contents) => {
+                let (pid, started) = contents.split_once('\n').unwrap_or((&contents, ""));
+                Ok(pid
+                    .trim()
+                    .parse()
+                    .ok()
+                    .map(|pid| (pid, started.trim().to_string())))
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Whether any process of the group led by `pid` is still alive.
+    ///
+    /// A process that has `pid` but did not start at `started` reused
+    /// the pid of a service that is long gone. While the leader lives
+    /// its start time tells; once it has exited, its pid cannot be
+    /// reused until the rest of its group is gone too.
+    fn is_alive(
+        &self,
+        shell: &ShellName,
+        service: &ServiceName,
+        pid: u32,
+        started: &str,
+    ) -> io::Result<bool> {
+        let key = (shell.clone(), service.clone());
+        if let Some(child) = self.children.borrow_mut().get_mut(&key) {
+            child.try_wait()?;
+        }
+        if process_start(pid)?.is_some_and(|current| current != started) {
+            return Ok(false);
+        }
+        signal_group(pid, "0")
+    }
+
+    /// Runs `check` once.
+    fn probe(&self, env: &DevEnv, data_dir: &Path, check: &ReadyCheck) -> io::Result<bool> {
+        match check {
+            ReadyCheck::Port(port) => Ok(TcpStream::connect(("localhost", *port)).is_ok()),
+            ReadyCheck::Command(script) => {
+                let (mut command, input) = in_dev_env(env, script, None);
+                command
+                    .current_dir(self.paths.config_dir())
+                    .env("LAZYNIX_SERVICE_DIR", data_dir)
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null());
+                Ok(spawn_fed(command, &input)?.wait()?.success())
+            }
+        }
+    }
+}
+
+/// When the process `pid` started, as `ps` prints it, if it is running.
+fn process_start(pid: u32) -> io::Result<Option<String>> {
+    let output = Command::new("ps")
+        .args(["-o", "lstart=", "-p", &pid.to_string()])
+        .stderr(Stdio::null())
+        .output()?;
+    let started = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok((output.status.success() && !started.is_empty()).then_some(started))
+}
+
+/// Sends `signal` to the process group led by `pid`; `0` only checks
+/// that the group exists. Returns whether a process received it.
+fn signal_group(pid: u32, signal: &str) -> io::Result<bool> {
+    let status = Command::new("kill")
+        .args(["-s", signal, "--", &format!("-{pid}")])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+    Ok(status.success())
+}
+
+impl ServiceSupervisor for SubprocessServiceSupervisor {
+    fn status(
+        &self,
+        shell: &ShellName,
+        service: &ServiceName,
+    ) -> Result<ServiceStatus, ServiceError> {
+        let state_error = |source| ServiceError::State {
+            service: service.to_string(),
+            source,
+        };
+        let Some((pid, started)) = self.recorded_pid(shell, service).map_err(state_error)? else {
+            return Ok(ServiceStatus::Stopped);
+        };
+        if self
+            .is_alive(shell, service, pid, &started)
+            .map_err(state_error)?
+        {
+            Ok(ServiceStatus::Running { pid })
+        } else {
+            Ok(ServiceStatus::Exited)
+        }
+    }
+
+    fn start(
+        &self,
+        env: &DevEnv,
+        shell: &ShellName,
+        service: &ServiceName,
+        script: &str,
+    ) -> Result<u32, ServiceError> {
+        let start = || -> io::Result<Child> {
+            let data_dir = self.data_dir(shell, service);
+            fs::create_dir_all(&data_dir)?;
+            fs::create_dir_all(self.services_dir(shell))?;
+            let log = File::create(self.log_file(shell, service))?;
+
+            let (mut command, input) = in_dev_env(env, script, None);
+            command
+                .current_dir(self.paths.config_dir())
+                .env("LAZYNIX_SERVICE_DIR", fs::canonicalize(&data_dir)?)
+                .stdout(log.try_clone()?)
+                .stderr(log)
+                .process_group(0);
+            let child = spawn_fed(command, &input)?;
+            let started = process_start(child.id())?.unwrap_or_default();
+            fs::write(
+                self.pid_file(shell, service),
+                format!("{}\n{}\n", child.id(), started),
+            )?;
+            Ok(child)
+        };
+        let child = start().map_err(|source| ServiceError::Start {
+            service: service.to_string(),
+            source,
+        })?;
+        let pid = child.id();
+        self.children
+            .borrow_mut()
+            .insert((shell.clone(), service.clone()), child);
+        Ok(pid)
+    }
+
+    fn wait_ready(
+        &self,
+        env: &DevEnv,
+        shell: &ShellName,
+        service: &ServiceName,
+        check: &ReadyCheck,
+        limit: Duration,
+    ) -> Result<Readiness, ServiceError> {
+        let state_error = |source| ServiceError::State {
+            service: service.to_string(),
+            source,
+        };
+        let data_dir = fs::canonicalize(self.data_dir(shell, service)).map_err(state_error)?;
+        let deadline = Instant::now() + limit;
+        loop {
+            if !matches!(self.status(shell, service)?, ServiceStatus::Running { .. }) {
+                return Ok(Readiness::Exited);
+            }
+            if self.probe(env, &data_dir, check).map_err(state_error)? {
+                return Ok(Readiness::Ready);
+            }
+            if Instant::now() >= deadline {
+                return Ok(Readiness::TimedOut);
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    fn stop(&self, shell: &ShellName, service: &ServiceName) -> Result<bool, ServiceError> {
+        let stop_error = |source| ServiceError::Stop {
+            service: service.to_string(),
+            source,
+        };
+        let was_running = match self.status(shell, service)? {
+            ServiceStatus::Running { pid } => {
+                let started = process_start(pid).map_err(stop_error)?.unwrap_or_default();
+                signal_group(pid, "TERM").map_err(stop_error)?;
+                let deadline = Instant::now() + STOP_GRACE;
+                while self
+                    .is_alive(shell, service, pid, &started)
+                    .map_err(stop_error)?
+                {
+                    if Instant::now() >= deadline {
+                        signal_group(pid, "KILL").map_err(stop_error)?;
+                        break;
+                    }
+                    thread::sleep(POLL_INTERVAL);
+                }
+                true
+            }
+            ServiceStatus::Exited => false,
+            ServiceStatus::Stopped => return Ok(false),
+        };
+        self.children
+            .borrow_mut()
+            .remove(&(shell.clone(), service.clone()));
+        fs::remove_file(self.pid_file(shell, service)).map_err(stop_error)?;
+        Ok(was_running)
+    }
+
+    fn read_logs(
+        &self,
+        shell: &ShellName,
+        service: &ServiceName,
+    ) -> Result<Option<String>, ServiceError> {
+        match fs::read(self.log_file(shell, service)) {
+            Ok(bytes) => Ok(Some(String::from_utf8_lossy(&bytes).into_owned())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(ServiceError::State {
+                service: service.to_string(),
+                source,
+            }),
+        }
+    }
+
+    fn follow_logs(&self, shell: &ShellName, service: &ServiceName) -> Result<(), ServiceError> {
+        let mut command = Command::new("tail");
+        command
+            .args(["-n", "+1", "-F"])
+            .arg(self.log_file(shell, service));
+        run_inherit(command).map_err(|e| ServiceError::State {
+            service: service.to_string(),
+            source: io::Error::other(e),
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn supervisor(dir: &TempDir) -> SubprocessServiceSupervisor {
+        SubprocessServiceSupervisor::new(WorkspacePaths::new(dir.path()))
+    }
+
+    fn name(service: &str) -> ServiceName {
+        service.parse().unwrap()
+    }
+
+    fn shell(name: &str) -> ShellName {
+        name.parse().unwrap()
+    }
+
+    #[test]
+    fn starts_a_service_and_stops_its_whole_process_group() {
+        // Arrange
+        let dir = TempDir::new().unwrap();
+        let services = supervisor(&dir);
+        let db = name("db");
+        let default = shell("default");
+        let env = DevEnv::new("export GREETING=hi".to_string());
+
+        // Act
+        let pid = services
+            .start(
+                &env,
+                &default,
+                &db,
+                r#"echo "$GREETING from $LAZYNIX_SERVICE_DIR"; sleep 30 & wait"#,
+            )
+            .unwrap();
+        let ready = services
+            .wait_ready(
+                &env,
+                &default,
+                &db,
+                &ReadyCheck::Command(
+                    r#"grep -q hi "$LAZYNIX_SERVICE_DIR/../../../services/default/db.log""#
+                        .to_string(),
+                ),
+                Duration::from_secs(5),
+            )
+            .unwrap();
+        let running = services.status(&default, &db).unwrap();
+        let stopped = services.stop(&default, &db).unwrap();
+
+        // Assert
+        assert_eq!(ready, Readiness::Ready);
+        assert_eq!(running, ServiceStatus::Running { pid });
+        assert!(stopped);
+        assert_eq!(
+            services.status(&default, &db).unwrap(),
+            ServiceStatus::Stopped
+        );
+        assert!(!signal_group(pid, "0").unwrap(), "the forked sleep is gone");
+        let logs = services.read_logs(&default, &db).unwrap().unwrap();
+        assert!(logs.starts_with("hi from /"), "got: {logs}");
+        assert!(
+            logs.trim_end().ends_with(".lazynix/state/default/db"),
+            "got: {logs}"
+        );
+        assert!(!services.stop(&default, &db).unwrap());
+    }
+
+    #[test]
+    fn reports_a_service_that_exits_before_it_is_ready() {
+        // Arrange
+        let dir = TempDir::new().unwrap();
+        let services = supervisor(&dir);
+        let db = name("db");
+        let default = shell("default");
+        let env = DevEnv::new(String::new());
+        services
+            .start(&env, &default, &db, "echo bad config; exit 3")
+            .unwrap();
+
+        // Act
+        let ready = services
+            .wait_ready(
+                &env,
+                &default,
+                &db,
+                &ReadyCheck::Command("false".to_string()),
+                Duration::from_secs(5),
+            )
+            .unwrap();
+
+        // Assert
+        assert_eq!(ready, Readiness::Exited);
+        assert_eq!(
+            services.status(&default, &db).unwrap(),
+            ServiceStatus::Exited
+        );
+        assert_eq!(
+            services.read_logs(&default, &db).unwrap().as_deref(),
+            Some("bad config\n")
+        );
+        assert!(!services.stop(&default, &db).unwrap());
+        assert_eq!(
+            services.status(&default, &db).unwrap(),
+            ServiceStatus::Stopped
+        );
+    }
+
+    #[test]
+    fn port_probe_passes_once_the_port_accepts_connections() {
+        // Arrange
+        let dir = TempDir::new().unwrap();
+        let services = supervisor(&dir);
+        let web = name("web");
+        let default = shell("default");
+        let env = DevEnv::new(String::new());
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        services.start(&env, &default, &web, "sleep 30").unwrap();
+
+        // Act
+        let ready = services
+            .wait_ready(
+                &env,
+                &default,
+                &web,
+                &ReadyCheck::Port(port),
+                Duration::from_secs(5),
+            )
+            .unwrap();
+        services.stop(&default, &web).unwrap();
+
+        // Assert
+        assert_eq!(ready, Readiness::Ready);
+        assert_eq!(services.read_logs(&default, &name("never")).unwrap(), None);
+    }
+
+    #[test]
+    fn leaves_alone_a_process_that_reused_a_stale_pid() {
+        // Arrange
+        let dir = TempDir::new().unwrap();
+        let services = supervisor(&dir);
+        let db = name("db");
+        let default = shell("default");
+        let mut unrelated = Command::new("sleep")
+            .arg("30")
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        fs::create_dir_all(services.services_dir(&default)).unwrap();
+        fs::write(
+            services.pid_file(&default, &db),
+            format!("{}\nThu Jan  1 00:00:00 1970\n", unrelated.id()),
+        )
+        .unwrap();
+
+        // Act
+        let status = services.status(&default, &db).unwrap();
+        let stopped = services.stop(&default, &db).unwrap();
+
+        // Assert
+        assert_eq!(status, ServiceStatus::Exited);
+        assert!(!stopped);
+        assert!(unrelated.try_wait().unwrap().is_none(), "still running");
+        assert_eq!(
+            services.status(&default, &db).unwrap(),
+            ServiceStatus::Stopped
+        );
+        unrelated.kill().unwrap();
+        unrelated.wait().unwrap();
+    }
+
+    #[test]
+    fn keeps_same_named_services_of_two_shells_apart() {
+        // Arrange
+        let dir = TempDir::new().unwrap();
+        let services = supervisor(&dir);
+        let db = name("db");
+        let (default, ci) = (shell("default"), shell("ci"));
+        let env = DevEnv::new(String::new());
+        let pid = services
+            .start(&env, &default, &db, "echo default; sleep 30")
+            .unwrap();
+
+        // Act
+        let ci_status = services.status(&ci, &db).unwrap();
+        let ci_stopped = services.stop(&ci, &db).unwrap();
+
+        // Assert
+        assert_eq!(ci_status, ServiceStatus::Stopped);
+        assert!(!ci_stopped);
+        assert_eq!(services.read_logs(&ci, &db).unwrap(), None);
+        assert_eq!(
+            services.status(&default, &db).unwrap(),
+            ServiceStatus::Running { pid }
+        );
+        assert!(services.stop(&default, &db).unwrap());
+    }
+}
@@ -10,6 +10,7 @@
 //!   two private helpers (`run_inherit` / `run_capture`) so stdio
 //!   wiring and error mapping live in one place.
 //! - [`output`] — the terminal sink.
+//! - [`lsp`] — the editor, spoken to in JSON-RPC over stdio.
 //!
 //! The composition root (the `lnix` binary) constructs these and lends
 //! them to use-cases via `lnix_app::Deps`.
@@ -18,6 +19,7 @@ mod paths;
 mod process;
 
 pub mod gateway;
+pub mod lsp;
 pub mod output;
 pub mod persistence;
 
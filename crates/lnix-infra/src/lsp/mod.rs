@@ -0,0 +1,7 @@
+//! Adapter for the domain's language-client port: JSON-RPC over
+//! stdio, as editors run `lnix lsp`.
+
+mod protocol;
+mod stdio;
+
+pub use stdio::StdioLanguageClient;
@@ -0,0 +1,440 @@
+//! JSON shapes of the Language Server Protocol messages LazyNix
+//! exchanges, to and from the domain's [`ClientMessage`] and
+//! [`ServerMessage`].
+
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+use lnix_domain::interface::lsp::{
+    ClientMessage, CodeAction, CompletionItem, CompletionKind, LspDiagnostic, RequestId,
+    ServerMessage, Severity, TextPosition, TextRange,
+};
+
+/// JSON-RPC `ParseError`.
+const PARSE_ERROR: i64 = -32700;
+/// JSON-RPC `MethodNotFound`.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// JSON-RPC `InvalidParams`.
+const INVALID_PARAMS: i64 = -32602;
+/// `TextDocumentSyncKind.Full`: every change sends the whole text.
+const FULL_SYNC: i64 = 1;
+
+#[derive(Deserialize)]
+struct Incoming {
+    id: Option<Value>,
+    method: Option<String>,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct DocumentId {
+    uri: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DidOpenParams {
+    text_document: OpenedDocument,
+}
+
+#[derive(Deserialize)]
+struct OpenedDocument {
+    uri: String,
+    text: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DidChangeParams {
+    text_document: DocumentId,
+    content_changes: Vec<ContentChange>,
+}
+
+#[derive(Deserialize)]
+struct ContentChange {
+    text: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DocumentParams {
+    text_document: DocumentId,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PositionParams {
+    text_document: DocumentId,
+    position: Position,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RangeParams {
+    text_document: DocumentId,
+    range: Range,
+}
+
+#[derive(Deserialize)]
+struct Position {
+    line: u32,
+    character: u32,
+}
+
+#[derive(Deserialize)]
+struct Range {
+    start: Position,
+    end: Position,
+}
+
+impl From<Position> for TextPosition {
+    fn from(position: Position) -> Self {
+        TextPosition::new(position.line, position.character)
+    }
+}
+
+impl From<Range> for TextRange {
+    fn from(range: Range) -> Self {
+        TextRange::new(range.start.into(), range.end.into())
+    }
+}
+
+/// Reads one message body.
+///
+/// Unknown notifications, and notifications whose params cannot be
+/// read, are [`ClientMessage::Ignored`]: the protocol forbids
+/// answering them. Requests get an answer either way, and so does a
+/// body that is not JSON-RPC at all.
+pub(crate) fn decode(body: &str) -> ClientMessage {
+    let incoming: Incoming = match serde_json::from_str(body) {
+        Ok(incoming) => incoming,
+        Err(e) => {
+            return ClientMessage::Malformed {
+                reason: e.to_string(),
+            };
+        }
+    };
+    // Responses to requests we never send carry no method.
+    let Some(method) = incoming.method else {
+        return ClientMessage::Ignored;
+    };
+    let id = incoming.id.map(|id| RequestId::new(id.to_string()));
+    let params = incoming.params;
+    match (method.as_str(), id) {
+        ("initialize", Some(id)) => ClientMessage::Initialize { id },
+        ("shutdown", Some(id)) => ClientMessage::Shutdown { id },
+        ("exit", None) => ClientMessage::Exit,
+        ("textDocument/didOpen", None) => read_notification(params, |p: DidOpenParams| {
+            Some(ClientMessage::DidOpen {
+                uri: p.text_document.uri,
+                text: p.text_document.text,
+            })
+        }),
+        ("textDocument/didChange", None) => read_notification(params, |mut p: DidChangeParams| {
+            let change = p.content_changes.pop()?;
+            Some(ClientMessage::DidChange {
+                uri: p.text_document.uri,
+                text: change.text,
+            })
+        }),
+        ("textDocument/didClose", None) => read_notification(params, |p: DocumentParams| {
+            Some(ClientMessage::DidClose {
+                uri: p.text_document.uri,
+            })
+        }),
+        ("textDocument/completion", Some(id)) => {
+            read_request(id, params, |id, p: PositionParams| {
+                ClientMessage::Completion {
+                    id,
+                    uri: p.text_document.uri,
+                    position: p.position.into(),
+                }
+            })
+        }
+        ("textDocument/hover", Some(id)) => {
+            read_request(id, params, |id, p: PositionParams| ClientMessage::Hover {
+                id,
+                uri: p.text_document.uri,
+                position: p.position.into(),
+            })
+        }
+        ("textDocument/codeAction", Some(id)) => {
+            read_request(id, params, |id, p: RangeParams| ClientMessage::CodeAction {
+                id,
+                uri: p.text_document.uri,
+                range: p.range.into(),
+            })
+        }
+        (_, Some(id)) => ClientMessage::UnknownRequest {
+            id,
+            method: method.clone(),
+        },
+        (_, None) => ClientMessage::Ignored,
+    }
+}
+
+/// A notification built from its params, or ignored when they cannot
+/// be read.
+fn read_notification<T: DeserializeOwned>(
+    params: Value,
+    build: impl FnOnce(T) -> Option<ClientMessage>,
+) -> ClientMessage {
+    serde_json::from_value(params)
+        .ok()
+        .and_then(build)
+        .unwrap_or(ClientMessage::Ignored)
+}
+
+/// A request built from its params, or the answer that they are
+/// invalid.
+fn read_request<T: DeserializeOwned>(
+    id: RequestId,
+    params: Value,
+    build: impl FnOnce(RequestId, T) -> ClientMessage,
+) -> ClientMessage {
+    match serde_json::from_value(params) {
+        Ok(params) => build(id, params),
+        Err(e) => ClientMessage::InvalidRequest {
+            id,
+            reason: e.to_string(),
+        },
+    }
+}
+
+/// Writes one message body.
+pub(crate) fn encode(message: &ServerMessage) -> String {
+    let value = match message {
+        ServerMessage::Initialized { id } => response(
+            id,
+            json!({
+                "capabilities": {
+                    "textDocumentSync": FULL_SYNC,
+                    "completionProvider": {},
+                    "hoverProvider": true,
+                    "codeActionProvider": true,
+                },
+                "serverInfo": {
+                    "name": "lnix",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            }),
+        ),
+        ServerMessage::Diagnostics { uri, diagnostics } => notification(
+            "textDocument/publishDiagnostics",
+            json!({
+                "uri": uri,
+                "diagnostics": diagnostics.iter().map(diagnostic_json).collect::<Vec<_>>(),
+            }),
+        ),
+        ServerMessage::Completions { id, list } => response(
+            id,
+            json!({
+                "isIncomplete": list.is_incomplete,
+                "items": list.items.iter().map(completion_json).collect::<Vec<_>>(),
+            }),
+        ),
+        ServerMessage::Hover { id, hover } => response(
+            id,
+            match hover {
+                Some(hover) => json!({
+                    "contents": { "kind": "markdown", "value": hover.markdown },
+                    "range": range_json(&hover.range),
+                }),
+                None => Value::Null,
+            },
+        ),
+        ServerMessage::CodeActions { id, uri, actions } => response(
+            id,
+            Value::Array(
+                actions
+                    .iter()
+                    .map(|action| code_action_json(uri, action))
+                    .collect(),
+            ),
+        ),
+        ServerMessage::ShutdownAck { id } => response(id, Value::Null),
+        ServerMessage::MethodNotFound { id, method } => {
+            error_response(id, METHOD_NOT_FOUND, &format!("Method not found: {method}"))
+        }
+        ServerMessage::InvalidParams { id, reason } => {
+            error_response(id, INVALID_PARAMS, &format!("Invalid params: {reason}"))
+        }
+        ServerMessage::ParseError { reason } => json!({
+            "jsonrpc": "2.0",
+            "id": Value::Null,
+            "error": { "code": PARSE_ERROR, "message": format!("Parse error: {reason}") },
+        }),
+        ServerMessage::Log { severity, message } => notification(
+            "window/logMessage",
+            json!({ "type": severity_code(*severity), "message": message }),
+        ),
+    };
+    value.to_string()
+}
+
+fn id_json(id: &RequestId) -> Value {
+    serde_json::from_str(id.as_json()).unwrap_or(Value::Null)
+}
+
+fn response(id: &RequestId, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id_json(id), "result": result })
+}
+
+fn error_response(id: &RequestId, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id_json(id),
+        "error": { "code": code, "message": message },
+    })
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+/// `DiagnosticSeverity` and `MessageType` agree on these.
+fn severity_code(severity: Severity) -> i64 {
+    match severity {
+        Severity::Error => 1,
+        Severity::Warning => 2,
+    }
+}
+
+fn position_json(position: &TextPosition) -> Value {
+    json!({ "line": position.line, "character": position.character })
+}
+
+fn range_json(range: &TextRange) -> Value {
+    json!({ "start": position_json(&range.start), "end": position_json(&range.end) })
+}
+
+fn diagnostic_json(diagnostic: &LspDiagnostic) -> Value {
+    json!({
+        "range": range_json(&diagnostic.range),
+        "severity": severity_code(diagnostic.severity),
+        "source": "lnix",
+        "message": diagnostic.message,
+    })
+}
+
+fn completion_json(item: &CompletionItem) -> Value {
+    // `CompletionItemKind.Function` and `.Module`.
+    let kind = match item.kind {
+        CompletionKind::Task => 3,
+        CompletionKind::Package => 9,
+    };
+    let mut value = json!({
+        "label": item.label,
+        "kind": kind,
+        "textEdit": { "range": range_json(&item.range), "newText": item.label },
+    });
+    if let Some(detail) = &item.detail {
+        value["detail"] = json!(detail);
+    }
+    if let Some(documentation) = &item.documentation {
+        value["documentation"] = json!(documentation);
+    }
+    value
+}
+
+fn code_action_json(uri: &str, action: &CodeAction) -> Value {
+    let edits: Vec<Value> = action
+        .edits
+        .iter()
+        .map(|edit| json!({ "range": range_json(&edit.range), "newText": edit.new_text }))
+        .collect();
+    json!({
+        "title": action.title,
+        "kind": "quickfix",
+        "edit": { "changes": { uri: edits } },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_requests_and_notifications() {
+        // Arrange
+        let change = r#"{"jsonrpc":"2.0","method":"textDocument/didChange","params":{
+            "textDocument":{"uri":"file:///p/lazynix.yaml","version":2},
+            "contentChanges":[{"text":"old"},{"text":"new"}]}}"#;
+        let hover = r#"{"jsonrpc":"2.0","id":"h1","method":"textDocument/hover","params":{
+            "textDocument":{"uri":"file:///p/lazynix.yaml"},"position":{"line":3,"character":14}}}"#;
+        let broken = r#"{"jsonrpc":"2.0","id":7,"method":"textDocument/completion","params":{}}"#;
+        let unknown = r#"{"jsonrpc":"2.0","id":8,"method":"textDocument/formatting","params":{}}"#;
+
+        // Act / Assert
+        assert_eq!(
+            decode(change),
+            ClientMessage::DidChange {
+                uri: "file:///p/lazynix.yaml".to_string(),
+                text: "new".to_string(),
+            }
+        );
+        assert_eq!(
+            decode(hover),
+            ClientMessage::Hover {
+                id: RequestId::new("\"h1\"".to_string()),
+                uri: "file:///p/lazynix.yaml".to_string(),
+                position: TextPosition::new(3, 14),
+            }
+        );
+        assert!(matches!(
+            decode(broken),
+            ClientMessage::InvalidRequest { .. }
+        ));
+        assert_eq!(
+            decode(unknown),
+            ClientMessage::UnknownRequest {
+                id: RequestId::new("8".to_string()),
+                method: "textDocument/formatting".to_string(),
+            }
+        );
+        assert_eq!(
+            decode(r#"{"jsonrpc":"2.0","method":"initialized","params":{}}"#),
+            ClientMessage::Ignored
+        );
+        assert!(matches!(
+            decode("{not json"),
+            ClientMessage::Malformed { .. }
+        ));
+    }
+
+    #[test]
+    fn encodes_diagnostics_and_responses() {
+        // Arrange
+        let range = TextRange::new(TextPosition::new(4, 15), TextPosition::new(4, 23));
+        let diagnostics = ServerMessage::Diagnostics {
+            uri: "file:///p/lazynix.yaml".to_string(),
+            diagnostics: vec![LspDiagnostic {
+                range,
+                severity: Severity::Error,
+                message: "Invalid package name".to_string(),
+            }],
+        };
+        let shutdown = ServerMessage::ShutdownAck {
+            id: RequestId::new("\"s\"".to_string()),
+        };
+
+        // Act
+        let diagnostics: Value = serde_json::from_str(&encode(&diagnostics)).unwrap();
+        let shutdown: Value = serde_json::from_str(&encode(&shutdown)).unwrap();
+
+        // Assert
+        assert_eq!(diagnostics["method"], "textDocument/publishDiagnostics");
+        let first = &diagnostics["params"]["diagnostics"][0];
+        assert_eq!(
+            first["range"]["start"],
+            json!({ "line": 4, "character": 15 })
+        );
+        assert_eq!(first["severity"], 1);
+        assert_eq!(
+            shutdown,
+            json!({ "jsonrpc": "2.0", "id": "s", "result": null })
+        );
+    }
+}
@@ -0,0 +1,170 @@
+//! stdio-backed [`LanguageClient`].
+
+use std::cell::RefCell;
+use std::io::{self, BufRead, BufReader, Write};
+
+use lnix_domain::LspError;
+use lnix_domain::interface::lsp::{ClientMessage, LanguageClient, ServerMessage};
+
+use crate::lsp::protocol::{decode, encode};
+
+/// Talks to the editor that started `lnix lsp` over stdin and stdout,
+/// one `Content-Length`-framed JSON-RPC message at a time.
+pub struct StdioLanguageClient {
+    input: RefCell<Box<dyn BufRead>>,
+    output: RefCell<Box<dyn Write>>,
+}
+
+impl StdioLanguageClient {
+    pub fn new() -> Self {
+        Self::over(BufReader::new(io::stdin()), io::stdout())
+    }
+
+    fn over(input: impl BufRead + 'static, output: impl Write + 'static) -> Self {
+        Self {
+            input: RefCell::new(Box::new(input)),
+            output: RefCell::new(Box::new(output)),
+        }
+    }
+}
+
+impl Default for StdioLanguageClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageClient for StdioLanguageClient {
+    fn receive(&self) -> Result<Option<ClientMessage>, LspError> {
+        let mut input = self.input.borrow_mut();
+        let Some(body) = read_frame(&mut *input)? else {
+            return Ok(None);
+        };
+        Ok(Some(decode(&body)))
+    }
+
+    fn send(&self, message: ServerMessage) -> Result<(), LspError> {
+        let body = encode(&message);
+        let mut output = self.output.borrow_mut();
+        write!(output, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+        output.flush()?;
+        Ok(())
+    }
+}
+
+/// Reads the headers and body of one message, or `None` at the end of
+/// the input. Headers other than `Content-Length` are skipped.
+fn read_frame(input: &mut dyn BufRead) -> Result<Option<String>, LspError> {
+    let mut length = None;
+    let mut header = String::new();
+    loop {
+        header.clear();
+        if input.read_line(&mut header)? == 0 {
+            return match length {
+                None => Ok(None),
+                Some(_) => Err(LspError::Protocol("input ended in a header".to_string())),
+            };
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("Content-Length")
+        {
+            length = Some(value.trim().parse::<usize>().map_err(|_| {
+                LspError::Protocol(format!("invalid Content-Length: {}", value.trim()))
+            })?);
+        }
+    }
+    let length =
+        length.ok_or_else(|| LspError::Protocol("missing Content-Length header".to_string()))?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    String::from_utf8(body)
+        .map(Some)
+        .map_err(|_| LspError::Protocol("message body is not UTF-8".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::rc::Rc;
+
+    use lnix_domain::interface::lsp::RequestId;
+
+    /// A writer whose bytes the test can still read after handing it
+    /// to the client.
+    #[derive(Clone, Default)]
+    struct Shared(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn frame(body: &str) -> String {
+        format!("Content-Length: {}\r\n\r\n{body}", body.len())
+    }
+
+    #[test]
+    fn reads_framed_messages_until_the_input_ends() {
+        // Arrange
+        let input = frame(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#)
+            + "Content-Type: application/vscode-jsonrpc; charset=utf-8\r\n"
+            + &frame(r#"{"jsonrpc":"2.0","method":"exit"}"#);
+        let client = StdioLanguageClient::over(Cursor::new(input), io::sink());
+
+        // Act
+        let first = client.receive().unwrap();
+        let second = client.receive().unwrap();
+        let end = client.receive().unwrap();
+
+        // Assert
+        assert_eq!(
+            first,
+            Some(ClientMessage::Initialize {
+                id: RequestId::new("1".to_string())
+            })
+        );
+        assert_eq!(second, Some(ClientMessage::Exit));
+        assert_eq!(end, None);
+    }
+
+    #[test]
+    fn writes_messages_with_a_content_length_header() {
+        // Arrange
+        let written = Shared::default();
+        let client = StdioLanguageClient::over(Cursor::new(""), written.clone());
+
+        // Act
+        client
+            .send(ServerMessage::ShutdownAck {
+                id: RequestId::new("2".to_string()),
+            })
+            .unwrap();
+
+        // Assert
+        let body = r#"{"id":2,"jsonrpc":"2.0","result":null}"#;
+        assert_eq!(String::from_utf8(written.0.take()).unwrap(), frame(body));
+    }
+
+    #[test]
+    fn rejects_a_frame_without_content_length() {
+        // Arrange
+        let client = StdioLanguageClient::over(Cursor::new("X-Other: 1\r\n\r\n{}"), io::sink());
+
+        // Act
+        let result = client.receive();
+
+        // Assert
+        assert!(matches!(result, Err(LspError::Protocol(_))));
+    }
+}
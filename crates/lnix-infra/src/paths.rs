@@ -41,6 +41,21 @@ impl WorkspacePaths {
     pub fn flake_file(&self) -> PathBuf {
         self.config_dir.join("flake.nix")
     }
+
+    /// `{config_dir}/flake.lock`, written by nix next to the flake.
+    pub fn flake_lock_file(&self) -> PathBuf {
+        self.config_dir.join("flake.lock")
+    }
+
+    /// `{config_dir}/.envrc`, read by direnv.
+    pub fn envrc_file(&self) -> PathBuf {
+        self.config_dir.join(".envrc")
+    }
+
+    /// `{config_dir}/.lazynix`, lazynix's own state (never committed).
+    pub fn state_dir(&self) -> PathBuf {
+        self.config_dir.join(".lazynix")
+    }
 }
 
 #[cfg(test)]
@@ -59,5 +74,11 @@ mod tests {
             PathBuf::from("./configs/lazynix-settings.yaml")
         );
         assert_eq!(paths.flake_file(), PathBuf::from("./configs/flake.nix"));
+        assert_eq!(
+            paths.flake_lock_file(),
+            PathBuf::from("./configs/flake.lock")
+        );
+        assert_eq!(paths.envrc_file(), PathBuf::from("./configs/.envrc"));
+        assert_eq!(paths.state_dir(), PathBuf::from("./configs/.lazynix"));
     }
 }
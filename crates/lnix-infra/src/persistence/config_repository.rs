@@ -1,9 +1,12 @@
 //! Filesystem-backed [`ConfigRepository`].
 
 use std::fs;
+use std::path::{Path, PathBuf};
 
 use lnix_domain::interface::persistence::ConfigRepository;
-use lnix_domain::{ConfigError, DevShellDefinition, Settings};
+use lnix_domain::{
+    ConfigError, DevShellDefinition, Settings, YamlError, merge_definitions, rebase_paths,
+};
 
 use crate::paths::WorkspacePaths;
 
@@ -26,8 +29,19 @@ impl ConfigRepository for FsConfigRepository {
                 self.paths.config_dir().display().to_string(),
             ));
         }
-        let text = fs::read_to_string(&path)?;
-        serde_yaml::from_str(&text).map_err(|e| ConfigError::Parse(e.to_string()))
+        let root = fs::canonicalize(self.paths.config_dir())?;
+        read_with_extends(&path, &root, &mut Vec::new(), &mut Vec::new())
+    }
+
+    fn extended_files(&self) -> Result<Vec<String>, ConfigError> {
+        let path = self.paths.config_file();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let root = fs::canonicalize(self.paths.config_dir())?;
+        let mut extended = Vec::new();
+        read_with_extends(&path, &root, &mut Vec::new(), &mut extended)?;
+        Ok(extended)
     }
 
     fn read_settings(&self) -> Result<Option<Settings>, ConfigError> {
@@ -40,6 +54,104 @@ impl ConfigRepository for FsConfigRepository {
             serde_yaml::from_str(&text).map_err(|e| ConfigError::Parse(e.to_string()))?;
         Ok(Some(settings))
     }
+
+    fn parse_config(&self, text: &str) -> Result<DevShellDefinition, YamlError> {
+        serde_yaml::from_str(text).map_err(|e| {
+            let message = e.to_string();
+            let location = e.location().map(|l| (l.line(), l.column()));
+            // The location is kept apart, so it is cut from the message.
+            let message = match location {
+                Some((line, column)) => message
+                    .strip_suffix(&format!(" at line {line} column {column}"))
+                    .map_or(message.clone(), str::to_string),
+                None => message,
+            };
+            YamlError { message, location }
+        })
+    }
+}
+
+/// Reads the config at `path` and merges in every file it `extends`,
+/// depth-first and in declaration order, so later entries and finally
+/// `path` itself take precedence. The relative paths of an extended
+/// file are rebased onto `root`, the config directory, so that they
+/// keep pointing where they did from the file. `chain` holds the files
+/// currently being read and is how cycles are detected; every extended
+/// file read is added to `extended`, relative to `root`.
+fn read_with_extends(
+    path: &Path,
+    root: &Path,
+    chain: &mut Vec<PathBuf>,
+    extended: &mut Vec<String>,
+) -> Result<DevShellDefinition, ConfigError> {
+    let canonical = fs::canonicalize(path)?;
+    if chain.contains(&canonical) {
+        let cycle: Vec<String> = chain
+            .iter()
+            .skip_while(|seen| **seen != canonical)
+            .chain([&canonical])
+            .map(|seen| seen.display().to_string())
+            .collect();
+        return Err(ConfigError::ExtendsCycle(cycle.join(" -> ")));
+    }
+
+    let text = fs::read_to_string(&canonical)?;
+    let mut config: DevShellDefinition = serde_yaml::from_str(&text).map_err(|e| {
+        if chain.is_empty() {
+            ConfigError::Parse(e.to_string())
+        } else {
+            ConfigError::Parse(format!("{}: {}", path.display(), e))
+        }
+    })?;
+
+    let extends = std::mem::take(&mut config.extends);
+    let dir = canonical.parent().unwrap_or(Path::new("."));
+    if !chain.is_empty() {
+        rebase_paths(&mut config, &relative_path(root, dir));
+        let file = relative_path(root, &canonical);
+        if !extended.contains(&file) {
+            extended.push(file);
+        }
+    }
+    chain.push(canonical.clone());
+    let mut merged: Option<DevShellDefinition> = None;
+    for entry in &extends {
+        let base_path = dir.join(entry);
+        if !base_path.exists() {
+            return Err(ConfigError::ExtendsNotFound(
+                base_path.display().to_string(),
+            ));
+        }
+        let base = read_with_extends(&base_path, root, chain, extended)?;
+        merged = Some(match merged {
+            Some(acc) => merge_definitions(acc, base)?,
+            None => base,
+        });
+    }
+    chain.pop();
+
+    match merged {
+        Some(base) => merge_definitions(base, config),
+        None => Ok(config),
+    }
+}
+
+/// `path` relative to `root`, both canonical, going up with `..` when
+/// `path` is not inside `root`.
+fn relative_path(root: &Path, path: &Path) -> String {
+    let root: Vec<_> = root.components().collect();
+    let path: Vec<_> = path.components().collect();
+    let common = root.iter().zip(&path).take_while(|(a, b)| a == b).count();
+    let up = root[common..].iter().map(|_| "..".to_string());
+    let down = path[common..]
+        .iter()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned());
+    let parts: Vec<String> = up.chain(down).collect();
+    if parts.is_empty() {
+        ".".to_string()
+    } else {
+        parts.join("/")
+    }
 }
 
 #[cfg(test)]
@@ -63,7 +175,9 @@ mod tests {
         let config = repository_in(&dir).read_config().unwrap();
 
         assert_eq!(
-            config.dev_shell.package.stable[0].name.as_str(),
+            config.dev_shells[&Default::default()].package.stable[0]
+                .name
+                .as_str(),
             "python312"
         );
     }
@@ -87,6 +201,137 @@ mod tests {
         assert!(matches!(result, Err(ConfigError::Parse(_))));
     }
 
+    #[test]
+    fn merges_extended_configs_relative_to_the_extending_file() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("shared")).unwrap();
+        fs::write(
+            dir.path().join("shared/base.yaml"),
+            "devShell:\n  package:\n    stable:\n      - name: git\n      - name: jq\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("lazynix.yaml"),
+            "extends:\n  - shared/base.yaml\ndevShell:\n  package:\n    stable:\n      - name: jq\n      - name: python312\n",
+        )
+        .unwrap();
+
+        let config = repository_in(&dir).read_config().unwrap();
+
+        let names: Vec<&str> = config.dev_shells[&Default::default()]
+            .package
+            .stable
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["git", "jq", "python312"]);
+        assert!(config.extends.is_empty());
+    }
+
+    #[test]
+    fn config_may_take_every_shell_from_what_it_extends() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("base.yaml"),
+            "devShell:\n  package:\n    stable:\n      - name: git\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("lazynix.yaml"), "extends:\n  - base.yaml\n").unwrap();
+
+        let config = repository_in(&dir).read_config().unwrap();
+
+        assert_eq!(config.dev_shells.len(), 1);
+    }
+
+    #[test]
+    fn rebases_paths_of_nested_extended_configs_onto_the_config_dir() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("project")).unwrap();
+        fs::create_dir_all(dir.path().join("shared/lang")).unwrap();
+        fs::write(
+            dir.path().join("shared/lang/go.yaml"),
+            "devShell:\n  package:\n    stable: []\n  env:\n    dotenv: [.env.go]\n  task:\n    vet:\n      commands: [go vet]\n      dir: ./cmd\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("shared/base.yaml"),
+            "extends:\n  - lang/go.yaml\ndevShell:\n  package:\n    stable: []\n  shellAlias: [aliases.sh]\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("project/lazynix.yaml"),
+            "extends:\n  - ../shared/base.yaml\ndevShell:\n  package:\n    stable: []\n  env:\n    dotenv: [.env]\n",
+        )
+        .unwrap();
+        let repository = FsConfigRepository::new(WorkspacePaths::new(dir.path().join("project")));
+
+        let config = repository.read_config().unwrap();
+        let extended = repository.extended_files().unwrap();
+
+        assert_eq!(
+            extended,
+            vec!["../shared/base.yaml", "../shared/lang/go.yaml"]
+        );
+        let shell = &config.dev_shells[&Default::default()];
+        assert_eq!(shell.shell_alias, vec!["../shared/aliases.sh"]);
+        assert_eq!(
+            shell.env.as_ref().unwrap().dotenv,
+            vec!["../shared/lang/.env.go", ".env"]
+        );
+        let vet = &shell.task.as_ref().unwrap()[&"vet".parse().unwrap()];
+        assert_eq!(vet.dir.as_deref(), Some("../shared/lang/cmd"));
+    }
+
+    #[test]
+    fn reports_cyclic_extends() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("lazynix.yaml"),
+            "extends:\n  - base.yaml\ndevShell:\n  package:\n    stable: []\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("base.yaml"),
+            "extends:\n  - lazynix.yaml\ndevShell:\n  package:\n    stable: []\n",
+        )
+        .unwrap();
+
+        let result = repository_in(&dir).read_config();
+
+        let Err(ConfigError::ExtendsCycle(cycle)) = result else {
+            panic!("expected a cycle, got {result:?}");
+        };
+        assert!(cycle.contains("base.yaml"), "got: {cycle}");
+    }
+
+    #[test]
+    fn reports_missing_extended_config() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("lazynix.yaml"),
+            "extends:\n  - missing.yaml\ndevShell:\n  package:\n    stable: []\n",
+        )
+        .unwrap();
+
+        let result = repository_in(&dir).read_config();
+
+        assert!(
+            matches!(result, Err(ConfigError::ExtendsNotFound(path)) if path.ends_with("missing.yaml"))
+        );
+    }
+
+    #[test]
+    fn parses_a_buffer_with_the_error_location_kept_apart() {
+        let dir = TempDir::new().unwrap();
+        let text = "devShell:\n  package:\n    stable:\n      - name: \"bad name\"\n";
+
+        let error = repository_in(&dir).parse_config(text).unwrap_err();
+
+        assert_eq!(error.location, Some((4, 9)));
+        assert!(error.message.contains("'bad name'"));
+        assert!(!error.message.contains("at line"));
+    }
+
     #[test]
     fn missing_settings_is_none() {
         let dir = TempDir::new().unwrap();
@@ -0,0 +1,125 @@
+//! Polling [`ConfigWatcher`].
+//!
+//! The watched files are few and small, so their contents are simply
+//! read again every [`POLL_INTERVAL`]; this avoids a platform file
+//! notification dependency and sees through editors that replace a
+//! file rather than writing to it.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use lnix_domain::ConfigError;
+use lnix_domain::interface::persistence::ConfigWatcher;
+
+use crate::paths::WorkspacePaths;
+
+/// How often the watched files are read.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long the files must stay unchanged before a change is reported.
+const SETTLE_TIME: Duration = Duration::from_millis(200);
+
+/// Watches files under [`WorkspacePaths::config_dir`].
+pub struct FsConfigWatcher {
+    paths: WorkspacePaths,
+}
+
+/// Contents of each watched file; `None` when it does not exist.
+type Snapshot = Vec<Option<Vec<u8>>>;
+
+impl FsConfigWatcher {
+    pub fn new(paths: WorkspacePaths) -> Self {
+        Self { paths }
+    }
+
+    fn resolve(&self, path: &str) -> PathBuf {
+        if path.starts_with('/') {
+            PathBuf::from(path)
+        } else {
+            self.paths.config_dir().join(path.trim_start_matches("./"))
+        }
+    }
+
+    fn snapshot(&self, files: &[PathBuf]) -> Result<Snapshot, ConfigError> {
+        files
+            .iter()
+            .map(|file| match fs::read(file) {
+                Ok(bytes) => Ok(Some(bytes)),
+                Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(ConfigError::Io(e)),
+            })
+            .collect()
+    }
+}
+
+impl ConfigWatcher for FsConfigWatcher {
+    fn wait_for_change(&self, files: &[String]) -> Result<Vec<String>, ConfigError> {
+        let mut names = vec![
+            "lazynix.yaml".to_string(),
+            "lazynix-settings.yaml".to_string(),
+        ];
+        for file in files {
+            if !names.contains(file) {
+                names.push(file.clone());
+            }
+        }
+        let watched: Vec<PathBuf> = names.iter().map(|name| self.resolve(name)).collect();
+
+        let initial = self.snapshot(&watched)?;
+        let mut latest = loop {
+            thread::sleep(POLL_INTERVAL);
+            let current = self.snapshot(&watched)?;
+            if current != initial {
+                break current;
+            }
+        };
+        loop {
+            thread::sleep(SETTLE_TIME);
+            let current = self.snapshot(&watched)?;
+            if current == latest {
+                break;
+            }
+            latest = current;
+        }
+
+        Ok(names
+            .into_iter()
+            .zip(initial.iter().zip(&latest))
+            .filter(|(_, (before, after))| before != after)
+            .map(|(name, _)| name)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn reports_changed_files_once_writes_settle() {
+        // Arrange
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("lazynix.yaml"), "devShell: {}\n").unwrap();
+        fs::write(dir.path().join(".env"), "A=1\n").unwrap();
+        let watcher = FsConfigWatcher::new(WorkspacePaths::new(dir.path()));
+        let root = dir.path().to_path_buf();
+        let writer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(300));
+            fs::write(root.join(".env"), "A=2\n").unwrap();
+            fs::write(root.join("aliases.sh"), "alias ll='ls -l'\n").unwrap();
+        });
+
+        // Act
+        let changed = watcher
+            .wait_for_change(&["./.env".to_string(), "aliases.sh".to_string()])
+            .unwrap();
+        writer.join().unwrap();
+
+        // Assert
+        assert_eq!(changed, vec!["./.env", "aliases.sh"]);
+    }
+}
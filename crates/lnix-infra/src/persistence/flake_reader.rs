@@ -403,4 +403,36 @@ mod tests {
         expected.insert(key("go", "1.21.13"), resolution("5ed6275", "go_1_21"));
         assert_eq!(inputs, expected);
     }
+
+    #[test]
+    fn round_trips_pinned_entries_next_to_custom_inputs() {
+        let config: lnix_domain::DevShellDefinition = serde_yaml::from_str(
+            r#"
+inputs:
+  - name: fenix
+    url: github:nix-community/fenix
+    follows:
+      nixpkgs: nixpkgs
+devShell:
+  package:
+    stable:
+      - name: stable.toolchain
+        from: fenix
+    pinned:
+      - name: go
+        version: "1.21.13"
+        resolvedCommit: "5ed6275"
+        resolvedAttr: "go_1_21"
+"#,
+        )
+        .unwrap();
+        let dir = TempDir::new().unwrap();
+        write_flake(&dir, &lnix_domain::render_flake(&config, None));
+
+        let inputs = reader_for(&dir).read_pinned_inputs().unwrap();
+
+        let mut expected = HashMap::new();
+        expected.insert(key("go", "1.21.13"), resolution("5ed6275", "go_1_21"));
+        assert_eq!(inputs, expected);
+    }
 }
@@ -1,13 +1,19 @@
 //! Filesystem adapters for the domain's persistence ports.
 
 mod config_repository;
+mod config_watcher;
 mod env_checker;
 mod flake_reader;
 mod flake_writer;
+mod report_writer;
 mod scaffolder;
+mod task_state;
 
 pub use config_repository::FsConfigRepository;
+pub use config_watcher::FsConfigWatcher;
 pub use env_checker::FsEnvFileChecker;
 pub use flake_reader::FsFlakeReader;
 pub use flake_writer::FsFlakeWriter;
+pub use report_writer::FsReportWriter;
 pub use scaffolder::FsProjectScaffolder;
+pub use task_state::FsTaskStateStore;
@@ -0,0 +1,50 @@
+//! Filesystem-backed [`ReportWriter`].
+
+use std::fs;
+use std::path::Path;
+
+use lnix_domain::ReportError;
+use lnix_domain::interface::persistence::ReportWriter;
+
+/// Writes test reports where the command line asked for them.
+///
+/// Unlike the other persistence adapters this one is not anchored to
+/// `WorkspacePaths`: a report path is an argument the user typed, so a
+/// relative path is resolved against the current directory.
+pub struct FsReportWriter;
+
+impl ReportWriter for FsReportWriter {
+    fn write_report(&self, path: &str, contents: &str) -> Result<(), ReportError> {
+        let error = |source| ReportError::Write {
+            path: path.to_string(),
+            source,
+        };
+        if let Some(parent) = Path::new(path).parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent).map_err(error)?;
+        }
+        fs::write(path, contents).map_err(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn creates_missing_parent_directories() {
+        // Arrange
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("reports/ci/junit.xml");
+
+        // Act
+        FsReportWriter
+            .write_report(path.to_str().unwrap(), "<testsuites/>")
+            .unwrap();
+
+        // Assert
+        assert_eq!(fs::read_to_string(path).unwrap(), "<testsuites/>");
+    }
+}
@@ -52,6 +52,19 @@ impl ProjectScaffolder for FsProjectScaffolder {
         fs::write(self.paths.flake_file(), FLAKE_TEMPLATE)?;
         Ok(())
     }
+
+    fn envrc_exists(&self) -> bool {
+        self.paths.envrc_file().exists()
+    }
+
+    fn envrc_path_display(&self) -> String {
+        self.paths.envrc_file().display().to_string()
+    }
+
+    fn write_envrc(&self, contents: &str) -> Result<(), ConfigError> {
+        fs::write(self.paths.envrc_file(), contents)?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -76,6 +89,25 @@ mod tests {
         assert!(yaml.contains("devShell"));
     }
 
+    #[test]
+    fn writes_envrc_next_to_the_config() {
+        // Arrange
+        let dir = TempDir::new().unwrap();
+        let scaffolder = FsProjectScaffolder::new(WorkspacePaths::new(dir.path()));
+
+        // Act
+        let existed = scaffolder.envrc_exists();
+        scaffolder.write_envrc("use flake\n").unwrap();
+
+        // Assert
+        assert!(!existed);
+        assert!(scaffolder.envrc_exists());
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".envrc")).unwrap(),
+            "use flake\n"
+        );
+    }
+
     #[test]
     fn refuses_config_template_when_directory_is_missing() {
         // Arrange
@@ -0,0 +1,231 @@
+//! Filesystem-backed [`TaskStateStore`].
+//!
+//! A fingerprint is the SHA-256 of the task's commands followed by each
+//! matched source file (path relative to the task directory, then
+//! contents) in sorted order. Fingerprints are kept one file per task
+//! at `.lazynix/tasks/<shell>/<task>`.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use lnix_domain::interface::persistence::TaskStateStore;
+use lnix_domain::{ShellName, TaskName, TaskStateError};
+use sha2::{Digest, Sha256};
+
+use crate::paths::WorkspacePaths;
+
+/// Hashes task sources relative to the config directory and records
+/// fingerprints under [`WorkspacePaths::state_dir`].
+pub struct FsTaskStateStore {
+    paths: WorkspacePaths,
+}
+
+impl FsTaskStateStore {
+    pub fn new(paths: WorkspacePaths) -> Self {
+        Self { paths }
+    }
+
+    fn task_dir(&self, dir: Option<&str>) -> PathBuf {
+        self.paths.config_dir().join(dir.unwrap_or_default())
+    }
+
+    fn state_file(&self, shell: &ShellName, task: &TaskName) -> PathBuf {
+        self.paths
+            .state_dir()
+            .join("tasks")
+            .join(shell.as_str())
+            .join(task.as_str())
+    }
+}
+
+/// Files (not directories) matching `pattern`, relative to `base`
+/// unless the pattern is absolute.
+fn matching_files(base: &Path, pattern: &str) -> Result<Vec<PathBuf>, TaskStateError> {
+    let full = if Path::new(pattern).is_absolute() {
+        pattern.to_string()
+    } else {
+        format!(
+            "{}/{}",
+            glob::Pattern::escape(&base.to_string_lossy()),
+            pattern.trim_start_matches("./")
+        )
+    };
+    let entries = glob::glob(&full).map_err(|e| TaskStateError::InvalidPattern {
+        pattern: pattern.to_string(),
+        reason: e.msg.to_string(),
+    })?;
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| TaskStateError::Read(e.into()))?;
+        if path.is_file() {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+impl TaskStateStore for FsTaskStateStore {
+    fn fingerprint(
+        &self,
+        dir: Option<&str>,
+        sources: &[String],
+        commands: &[String],
+    ) -> Result<String, TaskStateError> {
+        let base = self.task_dir(dir);
+        let mut files = BTreeSet::new();
+        for pattern in sources {
+            files.extend(matching_files(&base, pattern)?);
+        }
+
+        let mut hasher = Sha256::new();
+        for command in commands {
+            hasher.update(command.as_bytes());
+            hasher.update([0]);
+        }
+        for file in files {
+            let relative = file.strip_prefix(&base).unwrap_or(&file);
+            hasher.update(relative.to_string_lossy().as_bytes());
+            hasher.update([0]);
+            hasher.update(fs::read(&file).map_err(TaskStateError::Read)?);
+            hasher.update([0]);
+        }
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+
+    fn outputs_exist(
+        &self,
+        dir: Option<&str>,
+        generates: &[String],
+    ) -> Result<bool, TaskStateError> {
+        let base = self.task_dir(dir);
+        for pattern in generates {
+            if matching_files(&base, pattern)?.is_empty() {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn recorded(
+        &self,
+        shell: &ShellName,
+        task: &TaskName,
+    ) -> Result<Option<String>, TaskStateError> {
+        match fs::read_to_string(self.state_file(shell, task)) {
+            Ok(contents) => Ok(Some(contents.trim().to_string())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(TaskStateError::Read(e)),
+        }
+    }
+
+    fn record(
+        &self,
+        shell: &ShellName,
+        task: &TaskName,
+        fingerprint: &str,
+    ) -> Result<(), TaskStateError> {
+        let file = self.state_file(shell, task);
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(file, format!("{}\n", fingerprint))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    fn store_with_files(files: &[(&str, &str)]) -> (TempDir, FsTaskStateStore) {
+        let dir = TempDir::new().unwrap();
+        for (path, contents) in files {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        let store = FsTaskStateStore::new(WorkspacePaths::new(dir.path()));
+        (dir, store)
+    }
+
+    #[test]
+    fn fingerprint_changes_with_source_contents_and_commands() {
+        // Arrange
+        let (dir, store) = store_with_files(&[("api/schema.graphql", "type Query")]);
+        let sources = strings(&["api/**/*.graphql"]);
+        let commands = strings(&["make gen"]);
+
+        // Act
+        let before = store.fingerprint(None, &sources, &commands).unwrap();
+        let unchanged = store.fingerprint(None, &sources, &commands).unwrap();
+        let other_commands = store
+            .fingerprint(None, &sources, &strings(&["make gen -B"]))
+            .unwrap();
+        fs::write(dir.path().join("api/schema.graphql"), "type Mutation").unwrap();
+        let after = store.fingerprint(None, &sources, &commands).unwrap();
+
+        // Assert
+        assert_eq!(before, unchanged);
+        assert_ne!(before, other_commands);
+        assert_ne!(before, after);
+    }
+
+    #[test]
+    fn globs_resolve_against_the_task_dir() {
+        // Arrange
+        let (_dir, store) = store_with_files(&[("web/src/app.ts", "x"), ("web/dist/app.js", "y")]);
+
+        // Act / Assert
+        assert!(
+            store
+                .outputs_exist(Some("web"), &strings(&["dist/*.js"]))
+                .unwrap()
+        );
+        assert!(
+            !store
+                .outputs_exist(Some("web"), &strings(&["dist/*.js", "dist/*.css"]))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn records_fingerprints_per_shell_and_task() {
+        // Arrange
+        let (dir, store) = store_with_files(&[]);
+        let shell: ShellName = "default".parse().unwrap();
+        let task: TaskName = "codegen".parse().unwrap();
+
+        // Act
+        let before = store.recorded(&shell, &task).unwrap();
+        store.record(&shell, &task, "abc123").unwrap();
+
+        // Assert
+        assert_eq!(before, None);
+        assert_eq!(
+            store.recorded(&shell, &task).unwrap(),
+            Some("abc123".to_string())
+        );
+        assert!(dir.path().join(".lazynix/tasks/default/codegen").is_file());
+    }
+
+    #[test]
+    fn invalid_glob_is_an_error() {
+        // Arrange
+        let (_dir, store) = store_with_files(&[]);
+
+        // Act
+        let result = store.fingerprint(None, &strings(&["src/[a"]), &[]);
+
+        // Assert
+        assert!(
+            matches!(result, Err(TaskStateError::InvalidPattern { pattern, .. }) if pattern == "src/[a")
+        );
+    }
+}
@@ -1,14 +1,22 @@
 //! Shared subprocess execution for the gateway adapters.
 //!
-//! Two helpers, one per execution shape, so stdio wiring and
-//! error mapping live in exactly one place:
+//! One helper per execution shape, so stdio wiring and error mapping
+//! live in exactly one place:
 //!
 //! - [`run_inherit`] — interactive commands; the child owns the
 //!   terminal and only the exit code comes back.
 //! - [`run_capture`] — capturing commands; stdout/stderr come back for
 //!   the caller to interpret.
+//! - [`run_stdout`] — commands whose stdout is a result while their
+//!   stderr (progress, errors) belongs to the user.
+//! - [`run_tee`] — commands fed a script on stdin whose stdout is kept
+//!   and, optionally, also copied to the terminal as it arrives.
+//! - [`spawn_fed`] — commands fed a script on stdin that are left
+//!   running; the caller decides where their output goes.
 
-use std::process::{Command, Stdio};
+use std::io::{Read, Write};
+use std::process::{Child, Command, Stdio};
+use std::thread;
 
 use lnix_domain::NixError;
 
@@ -43,6 +51,87 @@ pub(crate) fn run_capture(mut command: Command) -> Result<Captured, NixError> {
     })
 }
 
+/// Runs `command` with stderr inherited, returning its stdout.
+///
+/// Unlike [`run_capture`], a non-zero exit is an error: the child has
+/// already explained the failure on the terminal.
+pub(crate) fn run_stdout(mut command: Command) -> Result<String, NixError> {
+    let output = command
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()?;
+    match output.status.code() {
+        Some(0) => Ok(String::from_utf8(output.stdout)?),
+        Some(code) => Err(NixError::NonZeroExit(code)),
+        None => Err(NixError::NoExitCode),
+    }
+}
+
+/// Exit code and stdout of a [`run_tee`] run.
+#[derive(Debug)]
+pub(crate) struct Teed {
+    pub code: i32,
+    /// Lossily decoded: test output need not be UTF-8.
+    pub stdout: String,
+}
+
+/// Runs `command` with `input` on stdin, keeping its stdout and, with
+/// `echo`, copying it to ours as it arrives. Stderr is inherited.
+pub(crate) fn run_tee(mut command: Command, input: &str, echo: bool) -> Result<Teed, NixError> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()?;
+
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = input.to_string();
+    // Fed from a thread so a child that writes before reading all of
+    // its input cannot deadlock against us. A child that exits early
+    // closes the pipe, which is not an error here.
+    let feeder = thread::spawn(move || {
+        let _ = stdin.write_all(input.as_bytes());
+    });
+
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let mut kept = Vec::new();
+    let mut buffer = [0; 8192];
+    let mut terminal = std::io::stdout();
+    loop {
+        let read = stdout.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        if echo {
+            let _ = terminal.write_all(&buffer[..read]);
+            let _ = terminal.flush();
+        }
+        kept.extend_from_slice(&buffer[..read]);
+    }
+    let _ = feeder.join();
+
+    let status = child.wait()?;
+    Ok(Teed {
+        code: status.code().ok_or(NixError::NoExitCode)?,
+        stdout: String::from_utf8_lossy(&kept).into_owned(),
+    })
+}
+
+/// Spawns `command` with `input` on stdin and returns without waiting
+/// for it to exit.
+///
+/// The input is written before returning, not from a thread: the
+/// child must have all of it even if we exit right away. This cannot
+/// deadlock as long as the child's output does not go to a pipe we
+/// read. A child that exits early closes the pipe, which is not an
+/// error here.
+pub(crate) fn spawn_fed(mut command: Command, input: &str) -> std::io::Result<Child> {
+    let mut child = command.stdin(Stdio::piped()).spawn()?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let _ = stdin.write_all(input.as_bytes());
+    Ok(child)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -100,4 +189,43 @@ mod tests {
         // Assert
         assert!(matches!(result, Err(NixError::Spawn(_))));
     }
+
+    #[test]
+    fn stdout_returns_output_of_successful_command() {
+        // Arrange
+        let mut command = Command::new("sh");
+        command.arg("-c").arg("echo result; echo progress 1>&2");
+
+        // Act
+        let stdout = run_stdout(command).unwrap();
+
+        // Assert
+        assert_eq!(stdout, "result\n");
+    }
+
+    #[test]
+    fn stdout_maps_failure_to_non_zero_exit() {
+        // Arrange
+        let mut command = Command::new("sh");
+        command.arg("-c").arg("exit 4");
+
+        // Act
+        let result = run_stdout(command);
+
+        // Assert
+        assert!(matches!(result, Err(NixError::NonZeroExit(4))));
+    }
+
+    #[test]
+    fn tee_runs_stdin_and_keeps_stdout() {
+        // Arrange
+        let command = Command::new("sh");
+
+        // Act
+        let teed = run_tee(command, "echo kept\nexit 2\n", false).unwrap();
+
+        // Assert
+        assert_eq!(teed.code, 2);
+        assert_eq!(teed.stdout, "kept\n");
+    }
 }
@@ -1,4 +1,5 @@
 use clap::{Parser, Subcommand};
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
@@ -37,13 +38,21 @@ pub enum Commands {
     Update,
 
     /// Generate flake.nix from lazynix.yaml without entering the shell
-    Generate,
+    Generate {
+        /// Regenerate whenever the config or a file it references changes
+        #[arg(long)]
+        watch: bool,
+    },
 
     /// Generate flake.nix from lazynix.yaml and enter nix develop shell
     Develop {
         /// Update flake.lock before entering the shell
         #[arg(long)]
         update: bool,
+
+        /// Dev shell to use (a key of `devShells` in lazynix.yaml)
+        #[arg(long, default_value = "default", value_name = "NAME")]
+        shell: String,
     },
 
     /// Run a command in the development environment
@@ -56,22 +65,130 @@ pub enum Commands {
         #[arg(long)]
         no_regen: bool,
 
+        /// Dev shell to use (a key of `devShells` in lazynix.yaml)
+        #[arg(long, default_value = "default", value_name = "NAME")]
+        shell: String,
+
         /// The command to run in the development environment
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         command: Vec<String>,
     },
 
+    /// Print the dev environment's variables for other tools to load
+    Env {
+        /// Dev shell to use (a key of `devShells` in lazynix.yaml)
+        #[arg(long, default_value = "default", value_name = "NAME")]
+        shell: String,
+
+        /// Output format: json, dotenv, sh, or fish
+        #[arg(long, default_value = "json", value_name = "FORMAT")]
+        format: String,
+    },
+
     /// Run tests defined in lazynix.yaml
     Test {
         /// Update flake.lock before running tests
         #[arg(long)]
         update: bool,
+
+        /// Dev shell to use (a key of `devShells` in lazynix.yaml)
+        #[arg(long, default_value = "default", value_name = "NAME")]
+        shell: String,
+
+        /// Stop at the first failing test
+        #[arg(long)]
+        fail_fast: bool,
+
+        /// Run up to N tests at once; overrides `test.parallel`
+        #[arg(long, short = 'j', value_name = "N")]
+        jobs: Option<NonZeroUsize>,
+
+        /// Write a report as `<format>=<path>` (format: junit, tap, json); repeatable
+        #[arg(long = "report", value_name = "FORMAT=PATH")]
+        reports: Vec<String>,
+
+        /// Rerun whenever the config or a file it references changes
+        #[arg(long, conflicts_with = "update")]
+        watch: bool,
+
+        /// Only run tests whose name matches (`*` and `?` wildcards)
+        #[arg(value_name = "NAME")]
+        patterns: Vec<String>,
+    },
+
+    /// Start the services defined in lazynix.yaml in the background
+    Up {
+        /// Dev shell to use (a key of `devShells` in lazynix.yaml)
+        #[arg(long, default_value = "default", value_name = "NAME")]
+        shell: String,
+
+        /// Only start these services
+        #[arg(value_name = "SERVICE")]
+        services: Vec<String>,
+    },
+
+    /// Stop services started by `lnix up`
+    Down {
+        /// Dev shell to use (a key of `devShells` in lazynix.yaml)
+        #[arg(long, default_value = "default", value_name = "NAME")]
+        shell: String,
+
+        /// Only stop these services
+        #[arg(value_name = "SERVICE")]
+        services: Vec<String>,
+    },
+
+    /// Show whether each service is running
+    Ps {
+        /// Dev shell to use (a key of `devShells` in lazynix.yaml)
+        #[arg(long, default_value = "default", value_name = "NAME")]
+        shell: String,
+    },
+
+    /// Print the output of a service
+    Logs {
+        /// Dev shell to use (a key of `devShells` in lazynix.yaml)
+        #[arg(long, default_value = "default", value_name = "NAME")]
+        shell: String,
+
+        /// Keep printing new output until interrupted
+        #[arg(short, long)]
+        follow: bool,
+
+        /// Name of the service
+        service: String,
+    },
+
+    /// Set up direnv to load the dev shell
+    Direnv {
+        #[command(subcommand)]
+        command: DirenvCommand,
     },
 
     /// Run a task defined in lazynix.yaml
+    ///
+    /// `lnix task <TASK_NAME> --help` prints the task's own usage.
+    #[command(disable_help_flag = true)]
     Task {
+        /// Dev shell to use (a key of `devShells` in lazynix.yaml)
+        #[arg(long, default_value = "default", value_name = "NAME")]
+        shell: String,
+
+        /// Print the order the task and its dependencies would run in, without running them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Run tasks even when their sources are unchanged since the last run
+        #[arg(long)]
+        force: bool,
+
+        /// Print help (of the task, when TASK_NAME is given)
+        #[arg(short, long)]
+        help: bool,
+
         /// Name of the task to run
-        task_name: String,
+        #[arg(required_unless_present = "help")]
+        task_name: Option<String>,
 
         /// Arguments to pass to the task
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
@@ -106,4 +223,28 @@ pub enum Commands {
         #[arg(short = '1', long)]
         one: bool,
     },
+
+    /// Print the JSON Schema of lazynix.yaml for editors to validate against
+    Schema {
+        /// Print the schema of lazynix-settings.yaml instead
+        #[arg(long)]
+        settings: bool,
+    },
+
+    /// Run a language server for lazynix.yaml over stdio
+    Lsp,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DirenvCommand {
+    /// Write an .envrc that regenerates flake.nix and loads the dev shell
+    Init {
+        /// Dev shell to load (a key of `devShells` in lazynix.yaml)
+        #[arg(long, default_value = "default", value_name = "NAME")]
+        shell: String,
+
+        /// Overwrite an existing .envrc
+        #[arg(short, long)]
+        force: bool,
+    },
 }
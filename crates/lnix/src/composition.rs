@@ -8,10 +8,15 @@ use std::path::Path;
 
 use lnix_app::Deps;
 use lnix_infra::WorkspacePaths;
-use lnix_infra::gateway::{NixVersionsResolver, SubprocessNixEvaluator, SubprocessNixRunner};
+use lnix_infra::gateway::{
+    NixPackageIndex, NixVersionsResolver, SubprocessNixEvaluator, SubprocessNixRunner,
+    SubprocessServiceSupervisor,
+};
+use lnix_infra::lsp::StdioLanguageClient;
 use lnix_infra::output::TerminalOutput;
 use lnix_infra::persistence::{
-    FsConfigRepository, FsEnvFileChecker, FsFlakeReader, FsFlakeWriter, FsProjectScaffolder,
+    FsConfigRepository, FsConfigWatcher, FsEnvFileChecker, FsFlakeReader, FsFlakeWriter,
+    FsProjectScaffolder, FsReportWriter, FsTaskStateStore,
 };
 
 /// Owns one adapter per port for the duration of a command.
@@ -24,6 +29,12 @@ pub struct AdapterSet {
     nix: SubprocessNixRunner,
     nix_eval: SubprocessNixEvaluator,
     resolver: NixVersionsResolver,
+    packages: NixPackageIndex,
+    task_state: FsTaskStateStore,
+    reports: FsReportWriter,
+    services: SubprocessServiceSupervisor,
+    watcher: FsConfigWatcher,
+    lsp: StdioLanguageClient,
     out: TerminalOutput,
 }
 
@@ -35,10 +46,16 @@ impl AdapterSet {
             flake_writer: FsFlakeWriter::new(paths.clone()),
             flake_reader: FsFlakeReader::new(paths.clone()),
             env: FsEnvFileChecker::new(paths.clone()),
-            scaffolder: FsProjectScaffolder::new(paths),
+            scaffolder: FsProjectScaffolder::new(paths.clone()),
             nix: SubprocessNixRunner,
             nix_eval: SubprocessNixEvaluator,
             resolver: NixVersionsResolver,
+            packages: NixPackageIndex::new(paths.clone()),
+            task_state: FsTaskStateStore::new(paths.clone()),
+            reports: FsReportWriter,
+            services: SubprocessServiceSupervisor::new(paths.clone()),
+            watcher: FsConfigWatcher::new(paths),
+            lsp: StdioLanguageClient::new(),
             out: TerminalOutput,
         }
     }
@@ -53,6 +70,12 @@ impl AdapterSet {
             nix: &self.nix,
             nix_eval: &self.nix_eval,
             resolver: &self.resolver,
+            packages: &self.packages,
+            task_state: &self.task_state,
+            reports: &self.reports,
+            services: &self.services,
+            watcher: &self.watcher,
+            lsp: &self.lsp,
             out: &self.out,
         }
     }
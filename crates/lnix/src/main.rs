@@ -3,10 +3,10 @@ mod composition;
 
 use std::process;
 
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 use lnix_app::{ApplicationError, Deps};
 
-use cli_parser::{Cli, Commands};
+use cli_parser::{Cli, Commands, DirenvCommand};
 use composition::AdapterSet;
 
 fn main() {
@@ -32,15 +32,67 @@ fn route(command: Commands, d: &Deps) -> Result<i32, ApplicationError> {
     match command {
         Commands::Init { force } => lnix_app::init(d, force),
         Commands::Update => lnix_app::update(d),
-        Commands::Generate => lnix_app::generate(d),
-        Commands::Develop { update } => lnix_app::develop(d, update),
-        Commands::Test { update } => lnix_app::test(d, update),
+        Commands::Generate { watch: false } => lnix_app::generate(d),
+        Commands::Generate { watch: true } => lnix_app::watch(d, lnix_app::generate),
+        Commands::Develop { update, shell } => lnix_app::develop(d, &shell, update),
+        Commands::Env { shell, format } => lnix_app::env(d, &shell, &format),
+        Commands::Test {
+            update,
+            shell,
+            fail_fast,
+            jobs,
+            reports,
+            watch,
+            patterns,
+        } => {
+            let options = lnix_app::TestOptions {
+                update_lock: update,
+                patterns,
+                fail_fast,
+                reports,
+                jobs,
+            };
+            if watch {
+                lnix_app::watch(d, |d| lnix_app::test(d, &shell, &options))
+            } else {
+                lnix_app::test(d, &shell, &options)
+            }
+        }
         Commands::Run {
             update,
             no_regen,
+            shell,
             command,
-        } => lnix_app::run(d, update, !no_regen, command),
-        Commands::Task { task_name, args } => lnix_app::task(d, &task_name, &args),
+        } => lnix_app::run(d, &shell, update, !no_regen, command),
+        Commands::Up { shell, services } => lnix_app::up(d, &shell, &services),
+        Commands::Down { shell, services } => lnix_app::down(d, &shell, &services),
+        Commands::Ps { shell } => lnix_app::ps(d, &shell),
+        Commands::Logs {
+            shell,
+            follow,
+            service,
+        } => lnix_app::logs(d, &shell, &service, follow),
+        Commands::Task {
+            task_name: None, ..
+        } => print_subcommand_help("task"),
+        Commands::Task {
+            shell,
+            dry_run,
+            force,
+            help,
+            task_name: Some(task_name),
+            args,
+        } => {
+            let options = lnix_app::TaskOptions {
+                dry_run,
+                help,
+                force,
+            };
+            lnix_app::task(d, &shell, &task_name, &args, &options)
+        }
+        Commands::Direnv {
+            command: DirenvCommand::Init { shell, force },
+        } => lnix_app::direnv_init(d, &shell, force),
         Commands::Lint { verbose, arch } => lnix_app::lint(d, verbose, arch.as_deref()),
         Commands::Search {
             package_name,
@@ -48,5 +100,20 @@ fn route(command: Commands, d: &Deps) -> Result<i32, ApplicationError> {
             json,
             one,
         } => lnix_app::search(d, &package_name, version.as_deref(), json, one),
+        Commands::Schema { settings } => lnix_app::schema(d, settings),
+        Commands::Lsp => lnix_app::lsp(d),
+    }
+}
+
+/// Prints clap's help for a subcommand that handles `--help` itself.
+fn print_subcommand_help(name: &str) -> Result<i32, ApplicationError> {
+    let mut command = Cli::command();
+    if let Some(subcommand) = command.find_subcommand_mut(name) {
+        let help = subcommand
+            .clone()
+            .bin_name(format!("lnix {}", name))
+            .render_long_help();
+        print!("{}", help);
     }
+    Ok(0)
 }
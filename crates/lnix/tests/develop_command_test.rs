@@ -11,7 +11,8 @@ fn test_develop_help_message() {
         .assert()
         .success()
         .stdout(predicate::str::contains("Generate flake.nix"))
-        .stdout(predicate::str::contains("--update"));
+        .stdout(predicate::str::contains("--update"))
+        .stdout(predicate::str::contains("--shell"));
 }
 
 #[test]
@@ -0,0 +1,62 @@
+use predicates::prelude::*;
+
+mod common;
+use common::*;
+
+#[test]
+fn test_direnv_init_help_message() {
+    lnix_cmd()
+        .arg("direnv")
+        .arg("init")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Write an .envrc"))
+        .stdout(predicate::str::contains("--force"));
+}
+
+#[test]
+fn test_direnv_init_writes_envrc_into_config_dir() {
+    let temp_dir = setup_test_dir();
+    let config_dir = temp_dir.path().join("nix");
+    std::fs::create_dir(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("lazynix.yaml"),
+        config_with_packages(&["bash"], &[]),
+    )
+    .unwrap();
+
+    lnix_cmd()
+        .current_dir(temp_dir.path())
+        .arg("-C")
+        .arg("nix")
+        .arg("direnv")
+        .arg("init")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("✓ Created: nix/.envrc"));
+
+    let envrc = std::fs::read_to_string(config_dir.join(".envrc")).unwrap();
+    assert!(envrc.contains("watch_file"));
+    assert!(envrc.contains("lnix -C . generate"));
+    assert!(envrc.ends_with("use flake\n"));
+    assert!(!temp_dir.path().join(".envrc").exists());
+}
+
+#[test]
+fn test_direnv_init_keeps_existing_envrc() {
+    let temp_dir = setup_test_dir_with_config(&config_with_packages(&["bash"], &[]));
+    let envrc = temp_dir.path().join(".envrc");
+    std::fs::write(&envrc, "use flake\n").unwrap();
+
+    lnix_cmd()
+        .arg("-C")
+        .arg(temp_dir.path())
+        .arg("direnv")
+        .arg("init")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("File already exists"));
+
+    assert_eq!(std::fs::read_to_string(&envrc).unwrap(), "use flake\n");
+}
@@ -0,0 +1,46 @@
+use predicates::prelude::*;
+
+mod common;
+use common::*;
+
+#[test]
+fn test_env_help_message() {
+    lnix_cmd()
+        .arg("env")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Print the dev environment"))
+        .stdout(predicate::str::contains("--format"));
+}
+
+#[test]
+fn test_env_missing_config_file() {
+    let temp_dir = setup_test_dir();
+
+    lnix_cmd()
+        .arg("-C")
+        .arg(temp_dir.path())
+        .arg("env")
+        .assert()
+        .failure()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("lazynix.yaml"));
+}
+
+#[test]
+fn test_env_invalid_format() {
+    let temp_dir = setup_test_dir_with_config(&config_with_packages(&["bash"], &[]));
+
+    lnix_cmd()
+        .arg("-C")
+        .arg(temp_dir.path())
+        .arg("env")
+        .arg("--format")
+        .arg("yaml")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid env format: 'yaml'"));
+
+    assert!(!temp_dir.path().join("flake.nix").exists());
+}
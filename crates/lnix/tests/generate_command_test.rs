@@ -53,3 +53,29 @@ fn test_generate_writes_flake_nix_without_nix_subprocess() {
 
     assert!(temp_dir.path().join("flake.nix").exists());
 }
+
+#[test]
+fn test_generate_watch_regenerates_after_config_change() {
+    let temp_dir = setup_test_dir_with_config(&config_with_packages(&["bash"], &[]));
+    let config = temp_dir.path().join("lazynix.yaml");
+    let editor = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(1500));
+        std::fs::write(config, config_with_packages(&["bash", "ripgrep"], &[])).unwrap();
+    });
+
+    // The watch only ends when killed at the timeout.
+    let output = lnix_cmd()
+        .arg("-C")
+        .arg(temp_dir.path())
+        .arg("generate")
+        .arg("--watch")
+        .timeout(std::time::Duration::from_secs(4))
+        .output()
+        .unwrap();
+    editor.join().unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Changed: lazynix.yaml"), "got: {stdout}");
+    let flake = std::fs::read_to_string(temp_dir.path().join("flake.nix")).unwrap();
+    assert!(flake.contains("ripgrep"));
+}
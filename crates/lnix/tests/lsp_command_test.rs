@@ -0,0 +1,63 @@
+use predicates::prelude::*;
+
+mod common;
+use common::*;
+
+/// `body` with its `Content-Length` header, as an editor sends it.
+fn frame(body: &str) -> String {
+    format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+}
+
+#[test]
+fn test_lsp_help_message() {
+    lnix_cmd()
+        .arg("lsp")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("language server"));
+}
+
+#[test]
+fn test_lsp_answers_an_editor_session_over_stdio() {
+    let temp_dir = setup_test_dir();
+    let open = r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{"textDocument":{"uri":"file:///p/lazynix.yaml","languageId":"yaml","version":1,"text":"devShell:\n  package:\n    stable:\n      - name: \"bad name\"\n"}}}"#;
+    let session = [
+        frame(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#),
+        frame(r#"{"jsonrpc":"2.0","method":"initialized","params":{}}"#),
+        frame(r#"{"jsonrpc":"2.0","id":"#),
+        frame(open),
+        frame(r#"{"jsonrpc":"2.0","id":2,"method":"shutdown"}"#),
+        frame(r#"{"jsonrpc":"2.0","method":"exit"}"#),
+    ]
+    .concat();
+
+    lnix_cmd()
+        .arg("-C")
+        .arg(temp_dir.path())
+        .arg("lsp")
+        .write_stdin(session)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""name":"lnix""#))
+        .stdout(predicate::str::contains(r#""code":-32700"#))
+        .stdout(predicate::str::contains("textDocument/publishDiagnostics"))
+        .stdout(predicate::str::contains("Invalid package name"))
+        .stdout(predicate::str::contains(
+            r#"{"id":2,"jsonrpc":"2.0","result":null}"#,
+        ));
+}
+
+#[test]
+fn test_lsp_fails_when_the_editor_hangs_up_without_shutdown() {
+    let temp_dir = setup_test_dir();
+
+    lnix_cmd()
+        .arg("-C")
+        .arg(temp_dir.path())
+        .arg("lsp")
+        .write_stdin("")
+        .assert()
+        .code(1)
+        .stdout(predicate::str::is_empty());
+}
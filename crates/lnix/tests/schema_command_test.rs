@@ -0,0 +1,60 @@
+use std::fs;
+use std::path::Path;
+
+use predicates::prelude::*;
+
+mod common;
+use common::*;
+
+/// The committed schema file `name` under `schema/`, which editors
+/// fetch by URL.
+fn committed_schema(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../schema")
+        .join(name);
+    fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+/// Runs `lnix schema` with `args` in a directory without a config.
+fn schema_output(args: &[&str]) -> String {
+    let temp_dir = setup_test_dir();
+    let output = lnix_cmd()
+        .arg("-C")
+        .arg(temp_dir.path())
+        .arg("schema")
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_schema_help_message() {
+    lnix_cmd()
+        .arg("schema")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("JSON Schema"))
+        .stdout(predicate::str::contains("--settings"));
+}
+
+#[test]
+fn test_schema_matches_committed_config_schema() {
+    assert_eq!(
+        schema_output(&[]),
+        committed_schema("lazynix.schema.json"),
+        "schema/lazynix.schema.json is stale; run `lnix schema > schema/lazynix.schema.json`"
+    );
+}
+
+#[test]
+fn test_schema_matches_committed_settings_schema() {
+    assert_eq!(
+        schema_output(&["--settings"]),
+        committed_schema("lazynix-settings.schema.json"),
+        "schema/lazynix-settings.schema.json is stale; \
+         run `lnix schema --settings > schema/lazynix-settings.schema.json`"
+    );
+}
@@ -0,0 +1,83 @@
+use predicates::prelude::*;
+
+mod common;
+use common::*;
+
+const SERVICES_CONFIG: &str = r#"devShell:
+  package:
+    stable:
+      - name: postgresql
+  services:
+    - name: db
+      command: postgres -D "$LAZYNIX_SERVICE_DIR"
+      ready:
+        port: 5432
+    - name: cache
+      command: redis-server
+"#;
+
+#[test]
+fn test_up_help_message() {
+    lnix_cmd()
+        .arg("up")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Start the services"))
+        .stdout(predicate::str::contains("--shell"));
+}
+
+#[test]
+fn test_up_no_services_defined() {
+    let temp_dir = setup_test_dir_with_config(&config_with_packages(&["bash"], &[]));
+
+    lnix_cmd()
+        .arg("-C")
+        .arg(temp_dir.path())
+        .arg("up")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No services defined"));
+}
+
+#[test]
+fn test_ps_lists_stopped_services() {
+    let temp_dir = setup_test_dir_with_config(SERVICES_CONFIG);
+
+    lnix_cmd()
+        .arg("-C")
+        .arg(temp_dir.path())
+        .arg("ps")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("db     stopped"))
+        .stdout(predicate::str::contains("cache  stopped"));
+}
+
+#[test]
+fn test_down_when_nothing_is_running() {
+    let temp_dir = setup_test_dir_with_config(SERVICES_CONFIG);
+
+    lnix_cmd()
+        .arg("-C")
+        .arg(temp_dir.path())
+        .arg("down")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cache is not running"))
+        .stdout(predicate::str::contains("db is not running"));
+}
+
+#[test]
+fn test_logs_unknown_service() {
+    let temp_dir = setup_test_dir_with_config(SERVICES_CONFIG);
+
+    lnix_cmd()
+        .arg("-C")
+        .arg(temp_dir.path())
+        .arg("logs")
+        .arg("web")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Service 'web' not found"));
+}
@@ -108,3 +108,115 @@ fn test_task_executes_commands() {
         .success()
         .stdout(predicate::str::contains("success"));
 }
+
+#[test]
+fn test_task_shell_not_found() {
+    let config = config_with_tasks(&[("hello", vec!["echo hello"], Some("Say hello"))]);
+    let temp_dir = setup_test_dir_with_config(&config);
+
+    lnix_cmd()
+        .arg("-C")
+        .arg(temp_dir.path())
+        .arg("task")
+        .arg("--shell")
+        .arg("ci")
+        .arg("hello")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Dev shell 'ci' not found"));
+}
+
+const DEPS_CONFIG: &str = r#"devShell:
+  package:
+    stable:
+      - name: bash
+  task:
+    codegen:
+      commands:
+        - echo gen
+    build:
+      commands:
+        - echo build
+      deps: [codegen]
+"#;
+
+#[test]
+fn test_task_dry_run_prints_dependency_order() {
+    let temp_dir = setup_test_dir_with_config(DEPS_CONFIG);
+
+    lnix_cmd()
+        .arg("-C")
+        .arg(temp_dir.path())
+        .arg("task")
+        .arg("--dry-run")
+        .arg("build")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1. codegen\n  2. build"));
+}
+
+#[test]
+fn test_task_dependency_cycle_is_rejected() {
+    let config = DEPS_CONFIG.replace(
+        "        - echo gen\n",
+        "        - echo gen\n      deps: [build]\n",
+    );
+    let temp_dir = setup_test_dir_with_config(&config);
+
+    lnix_cmd()
+        .arg("-C")
+        .arg(temp_dir.path())
+        .arg("task")
+        .arg("--dry-run")
+        .arg("build")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cycle"));
+}
+
+const ARGS_CONFIG: &str = r#"devShell:
+  package:
+    stable:
+      - name: bash
+  task:
+    deploy:
+      description: Deploy a service
+      args:
+        - name: target
+          required: true
+          help: Host to deploy to
+      commands:
+        - ./deploy {{.ARGS.target}}
+"#;
+
+#[test]
+fn test_task_help_prints_declared_arguments() {
+    let temp_dir = setup_test_dir_with_config(ARGS_CONFIG);
+
+    lnix_cmd()
+        .arg("-C")
+        .arg(temp_dir.path())
+        .arg("task")
+        .arg("deploy")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Usage: lnix task deploy <target>"))
+        .stdout(predicate::str::contains(
+            "--target  Host to deploy to [required]",
+        ));
+}
+
+#[test]
+fn test_task_missing_required_argument() {
+    let temp_dir = setup_test_dir_with_config(ARGS_CONFIG);
+
+    lnix_cmd()
+        .arg("-C")
+        .arg(temp_dir.path())
+        .arg("task")
+        .arg("deploy")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("requires argument 'target'"));
+}
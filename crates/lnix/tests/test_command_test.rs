@@ -84,3 +84,22 @@ fn test_test_with_update_flag() {
         .timeout(std::time::Duration::from_secs(30))
         .assert();
 }
+
+#[test]
+fn test_test_invalid_report_spec() {
+    let temp_dir = setup_test_dir_with_config(&config_with_test_commands(&["echo test"]));
+
+    lnix_cmd()
+        .arg("-C")
+        .arg(temp_dir.path())
+        .arg("test")
+        .arg("--report")
+        .arg("xml=out.xml")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid test report: 'xml=out.xml'",
+        ));
+
+    assert!(!temp_dir.path().join("flake.nix").exists());
+}
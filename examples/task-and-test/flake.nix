@@ -33,38 +33,6 @@
 
           shellHook = ''
             echo 'Try: lnix task fmt / lnix task review <expr> / lnix test'
-
-if [ "$LAZYNIX_TEST_MODE" = "1" ]; then
-TESTS_FAILED=0
-TESTS_PASSED=0
-
-echo "Running tests..."
-echo "================="
-
-for test_cmd in \
-        "uv run ruff check ." \
-        "uv run pytest -q"
-do
-    echo ""
-    echo "Running: $test_cmd"
-    echo "---"
-    if bash -c "$test_cmd"; then
-        TESTS_PASSED=$((TESTS_PASSED + 1))
-        echo "[PASS] $test_cmd"
-    else
-        TESTS_FAILED=$((TESTS_FAILED + 1))
-        echo "[FAIL] $test_cmd"
-    fi
-done
-
-echo ""
-echo "================="
-echo "Test Results: $TESTS_PASSED passed, $TESTS_FAILED failed"
-
-if [ $TESTS_FAILED -gt 0 ]; then
-    exit 1
-fi
-fi
           '';
         };
       }